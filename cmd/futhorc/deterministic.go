@@ -0,0 +1,98 @@
+package main
+
+import (
+	"context"
+	"encoding/hex"
+	"errors"
+	"flag"
+	"fmt"
+	"futhorc/pkg/futhorc"
+
+	"github.com/go-git/go-billy/v5"
+	"github.com/go-git/go-billy/v5/memfs"
+	"github.com/go-git/go-billy/v5/util"
+)
+
+// registerDeterministicFlag registers the `-assert-deterministic` flag, which
+// builds the site twice in memory and fails if the two builds differ (e.g.,
+// because of timestamps or map iteration order leaking into the output).
+func registerDeterministicFlag(flags *flag.FlagSet) *bool {
+	return flags.Bool(
+		"assert-deterministic",
+		false,
+		"build the site twice in memory and fail if the outputs differ",
+	)
+}
+
+func assertDeterministic(buildOpts *buildOptions, dir string) error {
+	pipeline, err := buildOpts.load(dir)
+	if err != nil {
+		return err
+	}
+	return checkDeterministic(&pipeline)
+}
+
+// checkDeterministic builds the site twice in memory, prints the files which
+// differ, and returns `ErrNondeterministic` if there are any.
+//
+// Each build parses the posts afresh rather than sharing them: the builds
+// mutate the posts they parse (e.g., sorting indices and linking single-post
+// tags), and nondeterminism in parsing and converting markdown (e.g., in
+// footnote or heading IDs) is exactly what the check must catch.
+func checkDeterministic(pipeline *futhorc.Pipeline) error {
+	var outputs [2]billy.Filesystem
+	for i := range outputs {
+		outputs[i] = memfs.New()
+		pipeline.OutputDirectory = outputs[i]
+		if err := pipeline.Run(context.Background()); err != nil {
+			return err
+		}
+	}
+
+	diff, err := futhorc.DiffOutputs(outputs[0], outputs[1])
+	if err != nil {
+		return err
+	}
+	printDiffSummary(&diff)
+	for _, p := range diff.Modified {
+		if err := printHexWindow(p, outputs[0], outputs[1]); err != nil {
+			return err
+		}
+	}
+
+	if n := len(diff.Added) + len(diff.Removed) + len(diff.Modified); n > 0 {
+		return fmt.Errorf("%w: %d files differ", ErrNondeterministic, n)
+	}
+	return nil
+}
+
+// ErrNondeterministic is returned by `-assert-deterministic` when two builds
+// of the same site differ.
+var ErrNondeterministic = errors.New("build is not deterministic")
+
+// printHexWindow prints a hex dump of each version of the file at `p` around
+// the first byte at which they differ.
+func printHexWindow(p string, first, second billy.Filesystem) error {
+	a, err := util.ReadFile(first, p)
+	if err != nil {
+		return fmt.Errorf("diffing `%s`: %w", p, err)
+	}
+	b, err := util.ReadFile(second, p)
+	if err != nil {
+		return fmt.Errorf("diffing `%s`: %w", p, err)
+	}
+
+	offset := 0
+	for offset < min(len(a), len(b)) && a[offset] == b[offset] {
+		offset++
+	}
+	const window = 32
+	start := max(offset-window/2, 0)
+	fmt.Printf("\n%s: first difference at byte %d\n", p, offset)
+	dump := func(data []byte) string {
+		return hex.Dump(data[start:min(start+window, len(data))])
+	}
+	fmt.Printf("first build:\n%s", dump(a))
+	fmt.Printf("second build:\n%s", dump(b))
+	return nil
+}
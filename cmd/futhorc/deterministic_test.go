@@ -0,0 +1,37 @@
+package main
+
+import (
+	"errors"
+	"futhorc/pkg/futhorc"
+	"html/template"
+	"testing"
+	"time"
+)
+
+func TestCheckDeterministic(t *testing.T) {
+	dir := t.TempDir()
+	if err := initProject([]string{dir}); err != nil {
+		t.Fatal(err)
+	}
+	pipeline, err := futhorc.LoadPipeline(dir, "https://example.com/")
+	if err != nil {
+		t.Fatal(err)
+	}
+	if err := checkDeterministic(&pipeline); err != nil {
+		t.Fatalf("wanted the scaffold project to be deterministic: %v", err)
+	}
+
+	// a template function which leaks the build time into the output
+	pipeline.IndexTemplate = template.Must(
+		template.New("index.html").Funcs(template.FuncMap{
+			"now": func() string {
+				return time.Now().Format(time.RFC3339Nano)
+			},
+		}).Parse("built at {{ now }}"),
+	)
+	if err := checkDeterministic(
+		&pipeline,
+	); !errors.Is(err, ErrNondeterministic) {
+		t.Fatalf("wanted `ErrNondeterministic`; found `%v`", err)
+	}
+}
@@ -0,0 +1,126 @@
+package main
+
+import (
+	"context"
+	"flag"
+	"fmt"
+	"futhorc/pkg/futhorc"
+
+	"github.com/go-git/go-billy/v5"
+	"github.com/go-git/go-billy/v5/memfs"
+	"github.com/go-git/go-billy/v5/util"
+)
+
+// diffOptions configures the `-diff` mode of `build`, which builds the site
+// in memory and reports how it differs from the existing output directory
+// without modifying it.
+type diffOptions struct {
+	diff    bool
+	content bool
+	maxSize int
+}
+
+func (opts *diffOptions) registerFlags(flags *flag.FlagSet) {
+	flags.BoolVar(
+		&opts.diff,
+		"diff",
+		false,
+		"report the files which differ from the existing output without "+
+			"writing it",
+	)
+	flags.BoolVar(
+		&opts.content,
+		"diff-content",
+		false,
+		"like `-diff`, but also print unified diffs of modified files",
+	)
+	flags.IntVar(
+		&opts.maxSize,
+		"diff-max-size",
+		64*1024,
+		"the size in bytes above which `-diff-content` omits a file's diff",
+	)
+}
+
+func (opts *diffOptions) enabled() bool { return opts.diff || opts.content }
+
+func (opts *diffOptions) run(buildOpts *buildOptions, dir string) error {
+	pipeline, err := buildOpts.load(dir)
+	if err != nil {
+		return err
+	}
+	before := pipeline.OutputDirectory
+	pipeline.OutputDirectory = memfs.New()
+	if err := pipeline.Run(context.Background()); err != nil {
+		return err
+	}
+
+	diff, err := futhorc.DiffOutputs(before, pipeline.OutputDirectory)
+	if err != nil {
+		return err
+	}
+	printDiffSummary(&diff)
+
+	if opts.content {
+		for _, p := range diff.Modified {
+			if err := opts.printDiff(
+				p,
+				before,
+				pipeline.OutputDirectory,
+			); err != nil {
+				return err
+			}
+		}
+	}
+	return nil
+}
+
+// printDiffSummary prints each path in `diff` on its own line, prefixed by
+// `A` if it was added, `D` if it was removed, or `M` if it was modified.
+func printDiffSummary(diff *futhorc.OutputDiff) {
+	for _, p := range diff.Added {
+		fmt.Printf("A %s\n", p)
+	}
+	for _, p := range diff.Removed {
+		fmt.Printf("D %s\n", p)
+	}
+	for _, p := range diff.Modified {
+		fmt.Printf("M %s\n", p)
+	}
+}
+
+func (opts *diffOptions) printDiff(
+	p string,
+	before billy.Filesystem,
+	after billy.Filesystem,
+) error {
+	a, err := util.ReadFile(before, p)
+	if err != nil {
+		return fmt.Errorf("diffing `%s`: %w", p, err)
+	}
+	b, err := util.ReadFile(after, p)
+	if err != nil {
+		return fmt.Errorf("diffing `%s`: %w", p, err)
+	}
+	if len(a) > opts.maxSize || len(b) > opts.maxSize {
+		fmt.Printf("\n%s: too large to diff\n", p)
+		return nil
+	}
+	if !isText(a) || !isText(b) {
+		fmt.Printf("\n%s: binary files differ\n", p)
+		return nil
+	}
+	fmt.Printf("\n%s", futhorc.UnifiedDiff(p, a, b))
+	return nil
+}
+
+// isText reports whether `data` looks like text, using the same heuristic as
+// git (a NUL byte in the first 8000 bytes means binary).
+func isText(data []byte) bool {
+	for _, b := range data[:min(len(data), 8000)] {
+		if b == 0 {
+			return false
+		}
+	}
+	return true
+}
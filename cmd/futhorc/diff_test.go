@@ -0,0 +1,141 @@
+package main
+
+import (
+	"bytes"
+	"io"
+	"io/fs"
+	"maps"
+	"os"
+	"path/filepath"
+	"testing"
+)
+
+func TestDiffAgainstPreviousBuild(t *testing.T) {
+	fixture := filepath.Join(
+		"..", "..", "pkg", "futhorc", "testdata", "golden", "tags",
+	)
+	dir := t.TempDir()
+	for _, p := range []string{"futhorc.jsonc", "posts", "theme"} {
+		copyTree(t, filepath.Join(fixture, p), filepath.Join(dir, p))
+	}
+	opts := buildOptions{siteRoot: "https://example.com/"}
+	if _, err := opts.run(dir); err != nil {
+		t.Fatal(err)
+	}
+	output := filepath.Join(dir, "_output")
+	previous := readFiles(t, output)
+
+	// only the part of the post after its summary changes, so its page and
+	// the feed page with its full content are modified but its summaries on
+	// the index pages aren't
+	post := filepath.Join(dir, "posts", "post-01.md")
+	data, err := os.ReadFile(post)
+	if err != nil {
+		t.Fatal(err)
+	}
+	data = bytes.Replace(
+		data,
+		[]byte("The rest of post 01."),
+		[]byte("The revised rest of post 01."),
+		1,
+	)
+	if err := os.WriteFile(post, data, 0644); err != nil {
+		t.Fatal(err)
+	}
+
+	diff := diffOptions{diff: true}
+	printed := captureStdout(t, func() error { return diff.run(&opts, dir) })
+	const wanted = "M page-001.json\nM posts/post-01.html\n"
+	if printed != wanted {
+		t.Errorf("wanted the diff %q; found %q", wanted, printed)
+	}
+	if found := readFiles(t, output); !maps.Equal(found, previous) {
+		t.Error("wanted `-diff` to leave the output directory unmodified")
+	}
+}
+
+// captureStdout returns what `f` prints to standard output.
+func captureStdout(t *testing.T, f func() error) string {
+	t.Helper()
+	r, w, err := os.Pipe()
+	if err != nil {
+		t.Fatal(err)
+	}
+	printed := make(chan []byte)
+	go func() {
+		data, _ := io.ReadAll(r)
+		printed <- data
+	}()
+
+	stdout := os.Stdout
+	os.Stdout = w
+	err = f()
+	os.Stdout = stdout
+	if closeErr := w.Close(); closeErr != nil {
+		t.Fatal(closeErr)
+	}
+	data := <-printed
+	if err != nil {
+		t.Fatal(err)
+	}
+	return string(data)
+}
+
+// copyTree copies the file or directory at `src` to `dst`.
+func copyTree(t *testing.T, src, dst string) {
+	t.Helper()
+	if err := filepath.WalkDir(
+		src,
+		func(p string, entry fs.DirEntry, err error) error {
+			if err != nil {
+				return err
+			}
+			rel, err := filepath.Rel(src, p)
+			if err != nil {
+				return err
+			}
+			target := filepath.Join(dst, rel)
+			if entry.IsDir() {
+				return os.MkdirAll(target, 0755)
+			}
+			data, err := os.ReadFile(p)
+			if err != nil {
+				return err
+			}
+			if err := os.MkdirAll(filepath.Dir(target), 0755); err != nil {
+				return err
+			}
+			return os.WriteFile(target, data, 0644)
+		},
+	); err != nil {
+		t.Fatal(err)
+	}
+}
+
+// readFiles maps the slash-separated path of each file under `dir` to its
+// contents.
+func readFiles(t *testing.T, dir string) map[string]string {
+	t.Helper()
+	files := map[string]string{}
+	if err := filepath.WalkDir(
+		dir,
+		func(p string, entry fs.DirEntry, err error) error {
+			if err != nil || entry.IsDir() {
+				return err
+			}
+			rel, err := filepath.Rel(dir, p)
+			if err != nil {
+				return err
+			}
+			data, err := os.ReadFile(p)
+			if err != nil {
+				return err
+			}
+			files[filepath.ToSlash(rel)] = string(data)
+			return nil
+		},
+	); err != nil {
+		t.Fatal(err)
+	}
+	return files
+}
@@ -0,0 +1,289 @@
+package main
+
+import (
+	"bytes"
+	"errors"
+	"flag"
+	"fmt"
+	"futhorc/pkg/futhorc"
+	"io/fs"
+	"log/slog"
+	"os"
+	"path/filepath"
+	"regexp"
+	"slices"
+	"strings"
+	"time"
+
+	"gopkg.in/yaml.v3"
+)
+
+// importPosts copies the posts of a Jekyll or Hugo site (the first positional
+// argument) into the enclosing project's `posts` directory, translating their
+// frontmatter. Keys which can't be translated are dropped and reported for
+// each post. Existing posts are never overwritten unless `-force` is passed.
+func importPosts(args []string) error {
+	flags := flag.NewFlagSet("import", flag.ExitOnError)
+	from := flags.String("from", "", "the source layout: `jekyll` or `hugo`")
+	force := flags.Bool("force", false, "overwrite existing posts")
+	if err := flags.Parse(args); err != nil {
+		return err
+	}
+	if flags.NArg() != 1 {
+		return errors.New("importing posts: expected exactly one directory")
+	}
+
+	var sources string
+	switch *from {
+	case "jekyll":
+		sources = filepath.Join(flags.Arg(0), "_posts")
+	case "hugo":
+		sources = filepath.Join(flags.Arg(0), "content")
+		if isDir(filepath.Join(sources, "posts")) {
+			sources = filepath.Join(sources, "posts")
+		}
+	default:
+		return fmt.Errorf(
+			"importing posts: invalid `-from` value `%s`: expected `jekyll` "+
+				"or `hugo`",
+			*from,
+		)
+	}
+
+	dir, err := futhorc.FindProjectDir(".")
+	if err != nil {
+		return fmt.Errorf("importing posts: %w", err)
+	}
+	posts := filepath.Join(dir, "posts")
+
+	var imported, failed int
+	if err := filepath.WalkDir(
+		sources,
+		func(path string, entry fs.DirEntry, err error) error {
+			if err != nil {
+				return err
+			}
+			if entry.IsDir() || !isMarkdownFile(path) {
+				return nil
+			}
+			if err := importPost(path, posts, *force); err != nil {
+				slog.Error("failed to import post", "err", err)
+				failed++
+				return nil
+			}
+			imported++
+			return nil
+		},
+	); err != nil {
+		return fmt.Errorf("importing posts: %w", err)
+	}
+
+	slog.Info("imported posts", "imported", imported, "failed", failed)
+	if failed > 0 {
+		return fmt.Errorf("importing posts: %d posts failed", failed)
+	}
+	return nil
+}
+
+func importPost(source, posts string, force bool) error {
+	data, err := os.ReadFile(source)
+	if err != nil {
+		return fmt.Errorf("importing post `%s`: %w", source, err)
+	}
+
+	name, date := splitDatePrefix(filepath.Base(source))
+	name = strings.TrimSuffix(name, filepath.Ext(name)) + ".md"
+	converted, untranslated, err := convertPost(data, date)
+	if err != nil {
+		return fmt.Errorf("importing post `%s`: %w", source, err)
+	}
+
+	// make sure the converted post is one that futhorc can build
+	if _, err := futhorc.ParsePost(converted, name); err != nil {
+		return fmt.Errorf("importing post `%s`: %w", source, err)
+	}
+
+	target := filepath.Join(posts, name)
+	mode := os.O_WRONLY | os.O_CREATE | os.O_TRUNC
+	if !force {
+		mode |= os.O_EXCL
+	}
+	f, err := os.OpenFile(target, mode, 0644)
+	if err != nil {
+		if errors.Is(err, fs.ErrExist) {
+			return fmt.Errorf(
+				"importing post `%s`: `%s` already exists (pass `-force` to "+
+					"overwrite it)",
+				source,
+				target,
+			)
+		}
+		return fmt.Errorf("importing post `%s`: %w", source, err)
+	}
+	if _, err := f.Write(converted); err != nil {
+		f.Close()
+		return fmt.Errorf("importing post `%s`: %w", source, err)
+	}
+	if err := f.Close(); err != nil {
+		return fmt.Errorf("importing post `%s`: %w", source, err)
+	}
+
+	if len(untranslated) > 0 {
+		slog.Warn(
+			"dropped untranslated frontmatter keys",
+			"post", source,
+			"keys", untranslated,
+		)
+	}
+	slog.Debug("imported post", "source", source, "target", target)
+	return nil
+}
+
+// convertPost translates a Jekyll or Hugo post into a futhorc post. `date` is
+// the date from the file name, if any, which is used if the frontmatter
+// doesn't have one. The keys which couldn't be translated are returned in
+// sorted order.
+func convertPost(
+	data []byte,
+	date string,
+) (converted []byte, untranslated []string, err error) {
+	data = bytes.ReplaceAll(data, []byte("\r\n"), []byte("\n"))
+	if bytes.HasPrefix(data, []byte("+++\n")) {
+		return nil, nil, errors.New("TOML frontmatter isn't supported")
+	}
+	if !bytes.HasPrefix(data, []byte("---\n")) {
+		return nil, nil, errors.New("missing frontmatter")
+	}
+	data = data[len("---\n"):]
+	end := bytes.Index(data, []byte("\n---\n"))
+	if end < 0 {
+		return nil, nil, errors.New("unterminated frontmatter")
+	}
+	var source map[string]any
+	if err := yaml.Unmarshal(data[:end], &source); err != nil {
+		return nil, nil, fmt.Errorf("parsing frontmatter: %w", err)
+	}
+	body := data[end+len("\n---\n"):]
+
+	var frontmatter importedFrontmatter
+	for key, value := range source {
+		switch strings.ToLower(key) {
+		case "title":
+			frontmatter.Title = fmt.Sprint(value)
+		case "author":
+			frontmatter.Author = fmt.Sprint(value)
+		case "date":
+			if frontmatter.Date, err = importDate(value); err != nil {
+				return nil, nil, err
+			}
+		case "tags", "categories":
+			frontmatter.Tags = append(frontmatter.Tags, importTags(value)...)
+		case "draft":
+			frontmatter.Draft = value == true
+		case "published":
+			frontmatter.Draft = value == false
+		case "layout":
+			// layouts are a theme concern, so they're dropped silently
+		default:
+			untranslated = append(untranslated, key)
+		}
+	}
+	if frontmatter.Date == "" {
+		if date == "" {
+			return nil, nil, errors.New("missing date")
+		}
+		frontmatter.Date = date
+	}
+	frontmatter.Tags = dedupe(frontmatter.Tags)
+	slices.Sort(untranslated)
+
+	// marshal the frontmatter so values are quoted where YAML requires it
+	header, err := yaml.Marshal(&frontmatter)
+	if err != nil {
+		return nil, nil, err
+	}
+	converted = fmt.Appendf(nil, "---\n%s---\n%s", header, body)
+	return converted, untranslated, nil
+}
+
+type importedFrontmatter struct {
+	Title  string   `yaml:"Title"`
+	Author string   `yaml:"Author,omitempty"`
+	Date   string   `yaml:"Date"`
+	Tags   []string `yaml:"Tags,flow"`
+	Draft  bool     `yaml:"Draft,omitempty"`
+}
+
+// importDate normalizes a frontmatter date, which YAML may have already
+// decoded as a timestamp.
+func importDate(value any) (string, error) {
+	switch value := value.(type) {
+	case time.Time:
+		return value.Format(time.DateOnly), nil
+	case string:
+		for _, layout := range importDateLayouts {
+			if t, err := time.Parse(layout, value); err == nil {
+				return t.Format(time.DateOnly), nil
+			}
+		}
+	}
+	return "", fmt.Errorf("invalid date `%v`", value)
+}
+
+var importDateLayouts = []string{
+	time.RFC3339,
+	"2006-01-02 15:04:05 -0700",
+	"2006-01-02 15:04:05",
+	"2006-01-02 15:04",
+	time.DateOnly,
+}
+
+// importTags returns the tags from a `tags` or `categories` value, which may
+// be a list or (in Jekyll) a space-separated string.
+func importTags(value any) []string {
+	switch value := value.(type) {
+	case string:
+		return strings.Fields(value)
+	case []any:
+		tags := make([]string, 0, len(value))
+		for _, tag := range value {
+			tags = append(tags, fmt.Sprint(tag))
+		}
+		return tags
+	}
+	return nil
+}
+
+func dedupe(tags []string) []string {
+	result := []string{}
+	for _, tag := range tags {
+		if !slices.Contains(result, tag) {
+			result = append(result, tag)
+		}
+	}
+	return result
+}
+
+// splitDatePrefix splits the date prefix of Jekyll-style file names
+// (`2021-04-16-title.md`) from the rest of the name.
+func splitDatePrefix(name string) (rest, date string) {
+	if m := datePrefix.FindStringSubmatch(name); m != nil {
+		return name[len(m[0]):], m[1]
+	}
+	return name, ""
+}
+
+var datePrefix = regexp.MustCompile(`^(\d{4}-\d{2}-\d{2})-`)
+
+func isMarkdownFile(path string) bool {
+	switch filepath.Ext(path) {
+	case ".md", ".markdown":
+		return true
+	}
+	return false
+}
+
+func isDir(path string) bool {
+	info, err := os.Stat(path)
+	return err == nil && info.IsDir()
+}
@@ -0,0 +1,112 @@
+package main
+
+import (
+	"embed"
+	"errors"
+	"flag"
+	"fmt"
+	"io/fs"
+	"log/slog"
+	"os"
+	"path/filepath"
+	"strings"
+)
+
+// scaffold is a minimal project which builds out of the box. The `all:`
+// prefix includes the `.gitkeep` files which preserve the empty asset
+// directories.
+//
+//go:embed all:scaffold
+var scaffold embed.FS
+
+// initProject writes the scaffold project into the project directory. It
+// refuses to overwrite any existing file (e.g., the theme of a project which
+// predates its config) unless `-force` is passed.
+func initProject(args []string) error {
+	flags := flag.NewFlagSet("init", flag.ExitOnError)
+	force := flags.Bool("force", false, "overwrite existing files")
+	if err := flags.Parse(args); err != nil {
+		return err
+	}
+
+	dir, err := projectDir(flags)
+	if err != nil {
+		return err
+	}
+
+	files, err := fs.Sub(scaffold, "scaffold")
+	if err != nil {
+		return fmt.Errorf("initializing project: %w", err)
+	}
+	var paths []string
+	if err := fs.WalkDir(
+		files,
+		".",
+		func(path string, entry fs.DirEntry, err error) error {
+			if err == nil && !entry.IsDir() {
+				paths = append(paths, path)
+			}
+			return err
+		},
+	); err != nil {
+		return fmt.Errorf("initializing project: %w", err)
+	}
+
+	// check every file before writing any, so a refused init doesn't leave
+	// a partial scaffold behind
+	if !*force {
+		var existing []string
+		for _, path := range paths {
+			target := filepath.Join(dir, filepath.FromSlash(path))
+			if _, err := os.Lstat(target); err == nil {
+				existing = append(existing, "`"+target+"`")
+			} else if !errors.Is(err, fs.ErrNotExist) {
+				return fmt.Errorf("initializing project: %w", err)
+			}
+		}
+		if len(existing) > 0 {
+			return fmt.Errorf(
+				"initializing project: %s already exist (pass `-force` to "+
+					"overwrite them)",
+				strings.Join(existing, ", "),
+			)
+		}
+	}
+
+	for _, path := range paths {
+		data, err := fs.ReadFile(files, path)
+		if err != nil {
+			return fmt.Errorf("initializing project: %w", err)
+		}
+		target := filepath.Join(dir, filepath.FromSlash(path))
+		slog.Debug("writing scaffold file", "path", target)
+		if err := writeScaffoldFile(target, data, *force); err != nil {
+			return fmt.Errorf("initializing project: %w", err)
+		}
+	}
+
+	slog.Info("initialized project", "dir", dir)
+	return nil
+}
+
+// writeScaffoldFile writes a scaffold file to `target`, creating its parent
+// directories. Unless `force` is set, it fails if `target` exists (e.g., if
+// it was created since `initProject` checked for it).
+func writeScaffoldFile(target string, data []byte, force bool) error {
+	if err := os.MkdirAll(filepath.Dir(target), 0755); err != nil {
+		return err
+	}
+	flags := os.O_WRONLY | os.O_CREATE | os.O_TRUNC
+	if !force {
+		flags |= os.O_EXCL
+	}
+	file, err := os.OpenFile(target, flags, 0644)
+	if err != nil {
+		return err
+	}
+	if _, err := file.Write(data); err != nil {
+		file.Close()
+		return err
+	}
+	return file.Close()
+}
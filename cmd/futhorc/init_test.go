@@ -0,0 +1,190 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"futhorc/pkg/futhorc"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+func TestInitProjectBuilds(t *testing.T) {
+	dir := t.TempDir()
+	if err := initProject([]string{dir}); err != nil {
+		t.Fatal(err)
+	}
+
+	pipeline, err := futhorc.LoadPipeline(dir, "https://example.com/")
+	if err != nil {
+		t.Fatal(err)
+	}
+	pipeline.DebugTemplates = true
+	pipeline.StrictTemplates = true
+	if err := pipeline.Run(context.Background()); err != nil {
+		t.Fatalf("building the scaffold project: %v", err)
+	}
+
+	output := filepath.Join(dir, "_output")
+	index, err := os.ReadFile(filepath.Join(output, "index.html"))
+	if err != nil {
+		t.Fatal(err)
+	}
+	if !strings.Contains(string(index), "<title>My Blog</title>") {
+		t.Errorf("wanted the configured title in `index.html`:\n%s", index)
+	}
+	if _, err := os.Stat(
+		filepath.Join(output, "posts", "example.html"),
+	); err != nil {
+		t.Errorf("wanted the example post: %v", err)
+	}
+
+	data, err := os.ReadFile(filepath.Join(output, "index.json"))
+	if err != nil {
+		t.Fatal(err)
+	}
+	var feed struct {
+		Title       string `json:"title"`
+		Description string `json:"description"`
+		Authors     []struct {
+			Name string `json:"name"`
+		} `json:"authors"`
+	}
+	if err := json.Unmarshal(data, &feed); err != nil {
+		t.Fatal(err)
+	}
+	if feed.Title != "My Blog" || feed.Description != "A new futhorc site" {
+		t.Errorf(
+			"wanted the configured title and description; found %q and %q",
+			feed.Title,
+			feed.Description,
+		)
+	}
+	if len(feed.Authors) != 1 || feed.Authors[0].Name != "Your Name" {
+		t.Errorf("wanted the configured author; found %+v", feed.Authors)
+	}
+}
+
+func TestScaffoldDiscussionAndWebmention(t *testing.T) {
+	const (
+		discussion = `<a href="https://social.example.org/@me/1">` +
+			"Join the discussion</a>"
+		endpoints = `<link rel="webmention" ` +
+			`href="https://example.com/webmention">` + "\n\t" +
+			`<link rel="pingback" href="https://example.com/pingback">`
+	)
+	for _, tc := range []struct {
+		name       string
+		config     string
+		webmention bool
+	}{
+		{name: "unconfigured", config: `{"title": "My Blog"}`},
+		{
+			name: "configured",
+			config: `{"title": "My Blog", "webmention": {
+				"endpoint": "https://example.com/webmention",
+				"pingback": "https://example.com/pingback"
+			}}`,
+			webmention: true,
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			dir := t.TempDir()
+			if err := initProject([]string{dir}); err != nil {
+				t.Fatal(err)
+			}
+			for p, contents := range map[string]string{
+				"futhorc.jsonc": tc.config,
+				"posts/discussed.md": "---\nTitle: Discussed\n" +
+					"Date: 2024-01-02\n" +
+					"Discussion: https://social.example.org/@me/1\n---\n",
+			} {
+				if err := os.WriteFile(
+					filepath.Join(dir, filepath.FromSlash(p)),
+					[]byte(contents),
+					0644,
+				); err != nil {
+					t.Fatal(err)
+				}
+			}
+			pipeline, err := futhorc.LoadPipeline(dir, "https://example.com/")
+			if err != nil {
+				t.Fatal(err)
+			}
+			if err := pipeline.Run(context.Background()); err != nil {
+				t.Fatal(err)
+			}
+
+			// only the post with a `Discussion` links to it, but every page
+			// advertises the configured endpoints
+			for p, discussed := range map[string]bool{
+				"index.html":           false,
+				"posts/example.html":   false,
+				"posts/discussed.html": true,
+			} {
+				data, err := os.ReadFile(
+					filepath.Join(dir, "_output", filepath.FromSlash(p)),
+				)
+				if err != nil {
+					t.Fatal(err)
+				}
+				page := string(data)
+				found := strings.Contains(page, discussion)
+				if found != discussed {
+					t.Errorf(
+						"`%s`: wanted the discussion link %t; found %t",
+						p,
+						discussed,
+						found,
+					)
+				}
+				if tc.webmention && !strings.Contains(page, endpoints) {
+					t.Errorf("`%s`: wanted the endpoint links:\n%s", p, page)
+				}
+				if !tc.webmention && strings.Contains(page, "webmention") {
+					t.Errorf("`%s`: wanted no endpoint links:\n%s", p, page)
+				}
+			}
+		})
+	}
+}
+
+func TestInitProjectRefusesToOverwrite(t *testing.T) {
+	dir := t.TempDir()
+	if err := initProject([]string{dir}); err != nil {
+		t.Fatal(err)
+	}
+	if err := initProject([]string{dir}); err == nil {
+		t.Fatal("wanted an error for an existing project")
+	}
+	if err := initProject([]string{"-force", dir}); err != nil {
+		t.Fatalf("wanted `-force` to overwrite the project: %v", err)
+	}
+}
+
+func TestInitProjectKeepsExistingTheme(t *testing.T) {
+	// a project which predates its config
+	dir := t.TempDir()
+	index := filepath.Join(dir, "theme", "index.html")
+	if err := os.MkdirAll(filepath.Dir(index), 0755); err != nil {
+		t.Fatal(err)
+	}
+	if err := os.WriteFile(index, []byte("mine"), 0644); err != nil {
+		t.Fatal(err)
+	}
+
+	err := initProject([]string{dir})
+	if err == nil || !strings.Contains(err.Error(), index) {
+		t.Fatalf("wanted an error naming `%s`; found `%v`", index, err)
+	}
+	if data, err := os.ReadFile(index); err != nil || string(data) != "mine" {
+		t.Errorf("wanted `%s` untouched; found %q (%v)", index, data, err)
+	}
+
+	// nothing is written when init is refused
+	config := filepath.Join(dir, "futhorc.jsonc")
+	if _, err := os.Stat(config); err == nil {
+		t.Errorf("wanted no `%s`", config)
+	}
+}
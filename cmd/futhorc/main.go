@@ -26,35 +26,62 @@ func main() {
 	defer func() { slog.Debug("completed", "elapsed", time.Since(start)) }()
 	slog.Debug("started", "time", start)
 
-	var siteRoot string
-	flag.StringVar(
-		&siteRoot,
+	// `build` is the default command so that `futhorc [flags] [dir]` keeps
+	// working.
+	command, args := build, os.Args[1:]
+	if len(args) > 0 {
+		if c, found := commands()[args[0]]; found {
+			command, args = c, args[1:]
+		}
+	}
+
+	if err := command(args); err != nil {
+		log.Fatal(err)
+	}
+}
+
+func commands() map[string]func([]string) error {
+	return map[string]func([]string) error{
+		"build":  build,
+		"import": importPosts,
+		"init":   initProject,
+		"new":    newPost,
+		"serve":  serve,
+		"stats":  stats,
+	}
+}
+
+func build(args []string) error {
+	flags := flag.NewFlagSet("build", flag.ExitOnError)
+	var opts buildOptions
+	flags.StringVar(
+		&opts.siteRoot,
 		"site-root",
 		"",
 		"the URL to the root of the site",
 	)
-
-	flag.Parse()
-
-	dir := "."
-	if args := flag.Args(); len(args) > 0 {
-		dir = args[0]
+	opts.registerFlags(flags)
+	var diff diffOptions
+	diff.registerFlags(flags)
+	deterministic := registerDeterministicFlag(flags)
+	if err := flags.Parse(args); err != nil {
+		return err
 	}
 
-	var err error
-	if dir, err = filepath.Abs(dir); err != nil {
-		log.Fatal(err)
+	dir, err := projectDir(flags)
+	if err != nil {
+		return err
 	}
 
 	pproff, err := os.Create("./run.pprof")
 	if err != nil {
-		log.Fatal(err)
+		return err
 	}
 	defer pproff.Close()
 
 	tracef, err := os.Create("./run.trace")
 	if err != nil {
-		log.Fatal(err)
+		return err
 	}
 	defer tracef.Close()
 
@@ -67,12 +94,95 @@ func main() {
 	}
 	defer trace.Stop()
 
-	pipeline, err := futhorc.LoadPipeline(dir, siteRoot)
+	if *deterministic {
+		return assertDeterministic(&opts, dir)
+	}
+	if diff.enabled() {
+		return diff.run(&opts, dir)
+	}
+	_, err = opts.run(dir)
+	return err
+}
+
+// projectDir returns the absolute path of the project directory, which is
+// the first positional argument (defaulting to the working directory).
+func projectDir(flags *flag.FlagSet) (string, error) {
+	dir := "."
+	if args := flags.Args(); len(args) > 0 {
+		dir = args[0]
+	}
+	return filepath.Abs(dir)
+}
+
+// buildOptions holds the settings shared by the commands which build the
+// site.
+type buildOptions struct {
+	siteRoot       string
+	debugTemplates bool
+	strict         bool
+	drafts         bool
+	wait           bool
+	force          bool
+}
+
+// registerFlags registers the flags for building into the project's output
+// directory.
+func (opts *buildOptions) registerFlags(flags *flag.FlagSet) {
+	opts.registerContentFlags(flags)
+	flags.BoolVar(
+		&opts.wait,
+		"wait",
+		false,
+		"wait for another build of the same output directory to finish",
+	)
+	flags.BoolVar(
+		&opts.force,
+		"force",
+		false,
+		"build into a non-empty output directory without a `.futhorc` marker",
+	)
+}
+
+// registerContentFlags registers the flags which affect what's built,
+// leaving out those which only concern the project's output directory.
+func (opts *buildOptions) registerContentFlags(flags *flag.FlagSet) {
+	flags.BoolVar(
+		&opts.debugTemplates,
+		"debug-templates",
+		false,
+		"warn about template accesses to missing values",
+	)
+	flags.BoolVar(
+		&opts.strict,
+		"strict",
+		false,
+		"treat the warnings from `-debug-templates` as errors",
+	)
+	flags.BoolVar(
+		&opts.drafts,
+		"drafts",
+		false,
+		"publish drafts along with the other posts",
+	)
+}
+
+func (opts *buildOptions) run(dir string) (futhorc.Pipeline, error) {
+	pipeline, err := opts.load(dir)
 	if err != nil {
-		log.Fatal(err)
+		return pipeline, err
 	}
+	return pipeline, pipeline.RunStaged(context.Background())
+}
 
-	if err := pipeline.Run(context.Background()); err != nil {
-		log.Fatal(err)
+func (opts *buildOptions) load(dir string) (futhorc.Pipeline, error) {
+	pipeline, err := futhorc.LoadPipeline(dir, opts.siteRoot)
+	if err != nil {
+		return pipeline, err
 	}
+	pipeline.DebugTemplates = opts.debugTemplates
+	pipeline.StrictTemplates = opts.strict
+	pipeline.Drafts = opts.drafts
+	pipeline.WaitForLock = opts.wait
+	pipeline.Force = opts.force
+	return pipeline, nil
 }
@@ -0,0 +1,105 @@
+package main
+
+import (
+	"errors"
+	"flag"
+	"fmt"
+	"futhorc/pkg/futhorc"
+	"futhorc/pkg/slug"
+	"io/fs"
+	"log/slog"
+	"os"
+	"path/filepath"
+	"strings"
+	"time"
+
+	"gopkg.in/yaml.v3"
+)
+
+// newPost creates a post in the enclosing project with the title given by the
+// first positional argument. The file name is the slugified title, and the
+// frontmatter holds the title, today's date, and the `-tags`.
+func newPost(args []string) error {
+	flags := flag.NewFlagSet("new", flag.ExitOnError)
+	tags := flags.String("tags", "", "a comma-separated list of tags")
+	if err := flags.Parse(args); err != nil {
+		return err
+	}
+	if flags.NArg() != 1 {
+		return errors.New("creating post: expected exactly one title")
+	}
+	title := flags.Arg(0)
+
+	name := slug.Slugify(title)
+	if name == "" {
+		return fmt.Errorf(
+			"creating post: title `%s` has no letters or digits",
+			title,
+		)
+	}
+
+	dir, err := futhorc.FindProjectDir(".")
+	if err != nil {
+		return fmt.Errorf("creating post: %w", err)
+	}
+
+	frontmatter, err := newFrontmatter(title, time.Now(), splitTags(*tags))
+	if err != nil {
+		return fmt.Errorf("creating post: %w", err)
+	}
+
+	path := filepath.Join(dir, "posts", name+".md")
+	f, err := os.OpenFile(path, os.O_WRONLY|os.O_CREATE|os.O_EXCL, 0644)
+	if err != nil {
+		if errors.Is(err, fs.ErrExist) {
+			return fmt.Errorf("creating post: `%s` already exists", path)
+		}
+		return fmt.Errorf("creating post: %w", err)
+	}
+	if _, err := f.Write(frontmatter); err != nil {
+		f.Close()
+		return fmt.Errorf("creating post: %w", err)
+	}
+	if err := f.Close(); err != nil {
+		return fmt.Errorf("creating post: %w", err)
+	}
+
+	slog.Info("created post", "path", path)
+	return nil
+}
+
+func newFrontmatter(
+	title string,
+	date time.Time,
+	tags []string,
+) ([]byte, error) {
+	// marshal the title and tags so they're quoted where YAML requires it
+	titleLine, err := yaml.Marshal(struct {
+		Title string `yaml:"Title"`
+	}{title})
+	if err != nil {
+		return nil, err
+	}
+	tagsLine, err := yaml.Marshal(struct {
+		Tags []string `yaml:"Tags,flow"`
+	}{tags})
+	if err != nil {
+		return nil, err
+	}
+	return []byte(fmt.Sprintf(
+		"---\n%sDate: %s\n%s---\n\n",
+		titleLine,
+		date.Format("2006-01-02"),
+		tagsLine,
+	)), nil
+}
+
+func splitTags(s string) []string {
+	tags := []string{}
+	for _, tag := range strings.Split(s, ",") {
+		if tag = strings.TrimSpace(tag); tag != "" {
+			tags = append(tags, tag)
+		}
+	}
+	return tags
+}
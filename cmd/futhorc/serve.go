@@ -0,0 +1,148 @@
+package main
+
+import (
+	"context"
+	"errors"
+	"flag"
+	"fmt"
+	"futhorc/pkg/futhorc"
+	"io"
+	"log/slog"
+	"mime"
+	"net/http"
+	"os"
+	"os/signal"
+	"path"
+	"syscall"
+
+	"github.com/go-git/go-billy/v5/osfs"
+)
+
+// serve builds the site for `http://localhost:{port}/` into a temporary
+// directory and serves it over HTTP for local development. The project's
+// output directory is left alone, since the local build's links point at the
+// local server. The server runs until it's interrupted, and then the
+// temporary directory is removed.
+func serve(args []string) (err error) {
+	var opts buildOptions
+	flags, port := serveFlags(&opts)
+	if err := flags.Parse(args); err != nil {
+		return err
+	}
+
+	dir, err := projectDir(flags)
+	if err != nil {
+		return err
+	}
+
+	ctx, stop := signal.NotifyContext(
+		context.Background(),
+		os.Interrupt,
+		syscall.SIGTERM,
+	)
+	defer stop()
+
+	// override the site root so internal links point at the local server
+	opts.siteRoot = fmt.Sprintf("http://localhost:%d/", *port)
+	pipeline, err := opts.load(dir)
+	if err != nil {
+		return err
+	}
+	output, err := os.MkdirTemp("", "futhorc-serve-")
+	if err != nil {
+		return fmt.Errorf("creating output directory: %w", err)
+	}
+	defer func() { err = errors.Join(err, os.RemoveAll(output)) }()
+	pipeline.OutputPath = ""
+	pipeline.OutputDirectory = osfs.New(output)
+	if err := pipeline.Run(ctx); err != nil {
+		return err
+	}
+
+	if err := registerFeedTypes(); err != nil {
+		return err
+	}
+
+	slog.Info("serving site", "url", opts.siteRoot)
+	return listenAndServe(ctx, &http.Server{
+		Addr:    fmt.Sprintf(":%d", *port),
+		Handler: siteHandler(http.Dir(output)),
+	})
+}
+
+// listenAndServe runs `server` until `ctx` is done, and then shuts it down.
+func listenAndServe(ctx context.Context, server *http.Server) error {
+	shutdown := make(chan error, 1)
+	go func() {
+		<-ctx.Done()
+		shutdown <- server.Shutdown(context.Background())
+	}()
+	if err := server.ListenAndServe(); !errors.Is(err, http.ErrServerClosed) {
+		return err
+	}
+	slog.Info("stopped serving site")
+	return <-shutdown
+}
+
+// serveFlags returns the `serve` command's flags, which register into `opts`,
+// and its port. The site is built into a temporary directory, so the flags
+// for the project's output directory (`-wait` and `-force`) don't apply.
+func serveFlags(opts *buildOptions) (*flag.FlagSet, *int) {
+	flags := flag.NewFlagSet("serve", flag.ExitOnError)
+	port := flags.Int("port", 8080, "the port to serve the site on")
+	opts.registerContentFlags(flags)
+	return flags, port
+}
+
+// registerFeedTypes registers the content types of the feed formats which
+// `mime` doesn't know about out of the box (Atom and RSS).
+func registerFeedTypes() error {
+	for _, format := range []futhorc.FeedFormat{
+		futhorc.FeedFormatAtom,
+		futhorc.FeedFormatRSS,
+	} {
+		if err := mime.AddExtensionType(
+			"."+string(format),
+			format.ContentType(),
+		); err != nil {
+			return fmt.Errorf("registering content types: %w", err)
+		}
+	}
+	return nil
+}
+
+// siteHandler serves the files in `root`. `http.Dir` and `http.FileServer`
+// clean the request path, so requests can't escape `root`, and directory
+// requests are served from their `index.html`. Missing files are served the
+// site's `404.html` if it has one.
+func siteHandler(root http.Dir) http.Handler {
+	files := http.FileServer(root)
+	return http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		f, err := root.Open(path.Clean("/" + r.URL.Path))
+		if err != nil {
+			notFound(w, r, root)
+			return
+		}
+		if err := f.Close(); err != nil {
+			slog.Warn("closing file", "path", r.URL.Path, "err", err)
+		}
+		files.ServeHTTP(w, r)
+	})
+}
+
+func notFound(w http.ResponseWriter, r *http.Request, root http.Dir) {
+	f, err := root.Open("/" + notFoundPage)
+	if err != nil {
+		http.NotFound(w, r)
+		return
+	}
+	defer f.Close()
+
+	w.Header().Set("Content-Type", "text/html; charset=utf-8")
+	w.WriteHeader(http.StatusNotFound)
+	if _, err := io.Copy(w, f); err != nil {
+		slog.Warn("writing not-found page", "path", r.URL.Path, "err", err)
+	}
+}
+
+const notFoundPage = "404.html"
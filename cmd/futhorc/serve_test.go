@@ -0,0 +1,166 @@
+package main
+
+import (
+	"context"
+	"futhorc/pkg/futhorc"
+	"io"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+	"time"
+)
+
+func TestServeFlags(t *testing.T) {
+	var opts buildOptions
+	flags, _ := serveFlags(&opts)
+	for _, name := range []string{
+		"port",
+		"drafts",
+		"strict",
+		"debug-templates",
+	} {
+		if flags.Lookup(name) == nil {
+			t.Errorf("wanted the `-%s` flag", name)
+		}
+	}
+	for _, name := range []string{"wait", "force"} {
+		if flags.Lookup(name) != nil {
+			t.Errorf("wanted no `-%s` flag", name)
+		}
+	}
+}
+
+func TestSiteHandler(t *testing.T) {
+	if err := registerFeedTypes(); err != nil {
+		t.Fatal(err)
+	}
+	for _, tc := range []struct {
+		name string
+
+		// files are written to the site's root
+		files map[string]string
+		path  string
+
+		status      int
+		contentType string
+		body        string
+	}{
+		{
+			name:        "index",
+			files:       map[string]string{"posts/index.html": "posts"},
+			path:        "/posts/",
+			status:      http.StatusOK,
+			contentType: "text/html; charset=utf-8",
+			body:        "posts",
+		},
+		{
+			name:        "atom",
+			files:       map[string]string{"index.atom": "<feed/>"},
+			path:        "/index.atom",
+			status:      http.StatusOK,
+			contentType: futhorc.FeedFormatAtom.ContentType(),
+			body:        "<feed/>",
+		},
+		{
+			name:        "rss",
+			files:       map[string]string{"index.rss": "<rss/>"},
+			path:        "/index.rss",
+			status:      http.StatusOK,
+			contentType: futhorc.FeedFormatRSS.ContentType(),
+			body:        "<rss/>",
+		},
+		{
+			name:        "not-found-page",
+			files:       map[string]string{notFoundPage: "lost?"},
+			path:        "/missing.html",
+			status:      http.StatusNotFound,
+			contentType: "text/html; charset=utf-8",
+			body:        "lost?",
+		},
+		{
+			name:        "not-found",
+			path:        "/missing.html",
+			status:      http.StatusNotFound,
+			contentType: "text/plain; charset=utf-8",
+			body:        "404 page not found\n",
+		},
+		{
+			name:        "escape",
+			files:       map[string]string{notFoundPage: "lost?"},
+			path:        "/../serve_test.go",
+			status:      http.StatusNotFound,
+			contentType: "text/html; charset=utf-8",
+			body:        "lost?",
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			root := t.TempDir()
+			for p, contents := range tc.files {
+				target := filepath.Join(root, filepath.FromSlash(p))
+				if err := os.MkdirAll(filepath.Dir(target), 0755); err != nil {
+					t.Fatal(err)
+				}
+				if err := os.WriteFile(
+					target,
+					[]byte(contents),
+					0644,
+				); err != nil {
+					t.Fatal(err)
+				}
+			}
+
+			w := httptest.NewRecorder()
+			siteHandler(http.Dir(root)).ServeHTTP(
+				w,
+				httptest.NewRequest(http.MethodGet, tc.path, nil),
+			)
+			rsp := w.Result()
+			body, err := io.ReadAll(rsp.Body)
+			if err != nil {
+				t.Fatal(err)
+			}
+			if rsp.StatusCode != tc.status {
+				t.Errorf(
+					"wanted status %d; found %d",
+					tc.status,
+					rsp.StatusCode,
+				)
+			}
+			contentType := rsp.Header.Get("Content-Type")
+			if !strings.HasPrefix(contentType, tc.contentType) {
+				t.Errorf(
+					"wanted content type `%s`; found `%s`",
+					tc.contentType,
+					contentType,
+				)
+			}
+			if string(body) != tc.body {
+				t.Errorf("wanted body %q; found %q", tc.body, body)
+			}
+		})
+	}
+}
+
+func TestListenAndServeShutdown(t *testing.T) {
+	ctx, cancel := context.WithCancel(context.Background())
+	served := make(chan error, 1)
+	go func() {
+		served <- listenAndServe(ctx, &http.Server{
+			Addr:    "127.0.0.1:0",
+			Handler: http.NotFoundHandler(),
+		})
+	}()
+
+	cancel()
+	select {
+	case err := <-served:
+		if err != nil {
+			t.Fatalf("wanted the server to shut down cleanly; found `%v`", err)
+		}
+	case <-time.After(10 * time.Second):
+		t.Fatal("still serving after the context was done")
+	}
+}
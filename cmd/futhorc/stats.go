@@ -0,0 +1,79 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"flag"
+	"fmt"
+	"futhorc/pkg/futhorc"
+	"os"
+	"text/tabwriter"
+
+	"github.com/go-git/go-billy/v5/memfs"
+)
+
+// stats builds the site in memory (without writing the output directory) and
+// prints the word counts and reading times of its posts, aggregated per tag
+// and per year, as tables or (with `-json`) as JSON.
+func stats(args []string) error {
+	flags := flag.NewFlagSet("stats", flag.ExitOnError)
+	asJSON := flags.Bool("json", false, "print the statistics as JSON")
+	var opts buildOptions
+	opts.registerFlags(flags)
+	if err := flags.Parse(args); err != nil {
+		return err
+	}
+
+	dir, err := projectDir(flags)
+	if err != nil {
+		return err
+	}
+	pipeline, err := opts.load(dir)
+	if err != nil {
+		return err
+	}
+	pipeline.OutputDirectory = memfs.New()
+	if err := pipeline.Run(context.Background()); err != nil {
+		return err
+	}
+
+	stats := pipeline.Report.Stats()
+	if *asJSON {
+		encoder := json.NewEncoder(os.Stdout)
+		encoder.SetIndent("", "  ")
+		return encoder.Encode(&stats)
+	}
+
+	w := tabwriter.NewWriter(os.Stdout, 0, 4, 2, ' ', tabwriter.AlignRight)
+	printGroups(w, "YEAR", stats.Years, &stats.Total)
+	fmt.Fprintln(w)
+	printGroups(w, "TAG", stats.Tags, nil)
+	return w.Flush()
+}
+
+func printGroups(
+	w *tabwriter.Writer,
+	heading string,
+	groups []futhorc.GroupStats,
+	total *futhorc.GroupStats,
+) {
+	fmt.Fprintf(w, "%s\tPOSTS\tWORDS\tCHARACTERS\tAVG. MINUTES\t\n", heading)
+	for i := range groups {
+		printGroup(w, &groups[i])
+	}
+	if total != nil {
+		printGroup(w, total)
+	}
+}
+
+func printGroup(w *tabwriter.Writer, group *futhorc.GroupStats) {
+	fmt.Fprintf(
+		w,
+		"%s\t%d\t%d\t%d\t%.1f\t\n",
+		group.Name,
+		group.Posts,
+		group.Words,
+		group.Characters,
+		group.AverageReadingTime,
+	)
+}
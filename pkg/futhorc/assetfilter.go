@@ -0,0 +1,115 @@
+package futhorc
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"log/slog"
+	"path"
+	"path/filepath"
+	"strings"
+
+	"futhorc/pkg/actor"
+)
+
+// AssetFilter decides which asset files are copied to the output. Patterns
+// use `path.Match` syntax. A pattern without a slash matches any element of
+// an asset's path (so `*.map` matches `js/app.js.map` and `src` matches
+// everything beneath any `src` directory), while a pattern with a slash
+// matches the leading elements of the path (so `vendor/*.txt` only matches
+// text files directly in the top-level `vendor` directory and `vendor/*`
+// matches everything in it).
+type AssetFilter struct {
+	// Ignore lists the patterns of assets which aren't copied. Dotfiles (and
+	// the contents of dot-directories) are always ignored unless they match
+	// `Include`.
+	Ignore []string
+
+	// Include lists the patterns of assets which are copied even if they
+	// match `Ignore` or are dotfiles (e.g., `.well-known`).
+	Include []string
+}
+
+// Validate checks that every pattern is well-formed.
+func (filter *AssetFilter) Validate() error {
+	for _, patterns := range [][]string{filter.Ignore, filter.Include} {
+		for _, pattern := range patterns {
+			if _, err := path.Match(pattern, ""); err != nil {
+				return fmt.Errorf(
+					"invalid asset pattern `%s`: %w",
+					pattern,
+					err,
+				)
+			}
+		}
+	}
+	return nil
+}
+
+// Ignored reports whether the asset at the slash-separated path `p` is
+// excluded from the output.
+func (filter *AssetFilter) Ignored(p string) bool {
+	if matchesAny(filter.Include, p) {
+		return false
+	}
+	for _, elt := range strings.Split(p, "/") {
+		if strings.HasPrefix(elt, ".") {
+			return true
+		}
+	}
+	return matchesAny(filter.Ignore, p)
+}
+
+func matchesAny(patterns []string, p string) bool {
+	elts := strings.Split(p, "/")
+	for _, pattern := range patterns {
+		if strings.Contains(pattern, "/") {
+			depth := strings.Count(pattern, "/") + 1
+			if depth <= len(elts) && matches(
+				pattern,
+				strings.Join(elts[:depth], "/"),
+			) {
+				return true
+			}
+			continue
+		}
+		for _, elt := range elts {
+			if matches(pattern, elt) {
+				return true
+			}
+		}
+	}
+	return false
+}
+
+// matches is `path.Match` for patterns which have already been validated.
+func matches(pattern, name string) bool {
+	matched, _ := path.Match(pattern, name)
+	return matched
+}
+
+// FilterAssets wraps a `FileFinder`, skipping the assets which `filter`
+// ignores. The number of ignored assets is logged once `find` is exhausted.
+func FilterAssets(
+	name string,
+	find actor.OutputCallback[string],
+	filter *AssetFilter,
+) actor.OutputCallback[string] {
+	var ignored int
+	return func(ctx context.Context) (string, error) {
+		for {
+			p, err := find(ctx)
+			if errors.Is(err, actor.ErrStop) && ignored > 0 {
+				slog.Info("ignored assets", "finder", name, "count", ignored)
+			}
+			if err != nil {
+				return p, err
+			}
+			if !filter.Ignored(filepath.ToSlash(p)) {
+				return p, nil
+			}
+			slog.Debug("ignoring asset", "finder", name, "path", p)
+			ignored++
+		}
+	}
+}
@@ -0,0 +1,224 @@
+package futhorc
+
+import (
+	"cmp"
+	"futhorc/pkg/markdown"
+	"log/slog"
+	"maps"
+	"slices"
+	"sync"
+	"time"
+)
+
+// BuildReport lists the pages and feeds written by a build, along with the
+// statistics of its posts. It's safe for concurrent use.
+type BuildReport struct {
+	mu    sync.Mutex
+	files []ReportedFile
+	posts []PostStats
+
+	// links maps the source paths of the reported posts to their links to
+	// the site (see `LinkCheck`).
+	links map[string][]markdown.Link
+
+	// Assets is the number of assets in the output (whether they were copied
+	// or were already up to date).
+	Assets int
+
+	// Removed is the number of stale files (previous outputs which the build
+	// no longer produces) removed from the output directory. Only staged
+	// builds remove stale files; see `RunStaged`.
+	Removed int
+
+	// Elapsed is the duration of the build.
+	Elapsed time.Duration
+}
+
+// ReportedFile describes a file written by a build.
+type ReportedFile struct {
+	// Path is the output path of the file.
+	Path string
+
+	// URL is the file's URL (empty for feeds).
+	URL string
+
+	Kind ReportKind
+
+	// Size is the size of the file in bytes.
+	Size int
+
+	// Unchanged is true if the file already existed with the same content,
+	// in which case it wasn't rewritten.
+	Unchanged bool
+}
+
+// ReportKind identifies the kind of a reported file.
+type ReportKind string
+
+const (
+	ReportKindPost      ReportKind = "post"
+	ReportKindMainIndex ReportKind = "main index"
+	ReportKindTagIndex  ReportKind = "tag index"
+	ReportKindTagsPage  ReportKind = "tags page"
+	ReportKindFeed      ReportKind = "feed"
+)
+
+// Record adds a file to the report. It's a no-op on a nil report.
+func (report *BuildReport) Record(file ReportedFile) {
+	if report == nil {
+		return
+	}
+	report.mu.Lock()
+	defer report.mu.Unlock()
+	report.files = append(report.files, file)
+}
+
+// RecordPost adds a published post's statistics to the report. It's a no-op
+// on a nil report.
+func (report *BuildReport) RecordPost(p *Post) {
+	if report == nil {
+		return
+	}
+	report.mu.Lock()
+	defer report.mu.Unlock()
+	report.posts = append(report.posts, newPostStats(p))
+	if len(p.Links) > 0 {
+		if report.links == nil {
+			report.links = map[string][]markdown.Link{}
+		}
+		report.links[p.Source] = p.Links
+	}
+}
+
+// postLinks returns the links of the reported posts by source path.
+func (report *BuildReport) postLinks() map[string][]markdown.Link {
+	report.mu.Lock()
+	defer report.mu.Unlock()
+	return maps.Clone(report.links)
+}
+
+// Stats returns the statistics of the reported posts.
+func (report *BuildReport) Stats() SiteStats {
+	report.mu.Lock()
+	defer report.mu.Unlock()
+	return buildSiteStats(report.posts)
+}
+
+// Files returns the reported files sorted by path.
+func (report *BuildReport) Files() []ReportedFile {
+	report.mu.Lock()
+	defer report.mu.Unlock()
+	files := slices.Clone(report.files)
+	slices.SortFunc(files, func(a, b ReportedFile) int {
+		return cmp.Compare(a.Path, b.Path)
+	})
+	return files
+}
+
+// Count returns the number of reported files of kind `kind`.
+func (report *BuildReport) Count(kind ReportKind) int {
+	report.mu.Lock()
+	defer report.mu.Unlock()
+	var n int
+	for _, file := range report.files {
+		if file.Kind == kind {
+			n++
+		}
+	}
+	return n
+}
+
+// Rewritten returns the number of reported files which were written (i.e.,
+// which were new or changed).
+func (report *BuildReport) Rewritten() int {
+	report.mu.Lock()
+	defer report.mu.Unlock()
+	var n int
+	for _, file := range report.files {
+		if !file.Unchanged {
+			n++
+		}
+	}
+	return n
+}
+
+// Bytes returns the total size of the reported files.
+func (report *BuildReport) Bytes() int {
+	report.mu.Lock()
+	defer report.mu.Unlock()
+	var n int
+	for _, file := range report.files {
+		n += file.Size
+	}
+	return n
+}
+
+// KindTotal is the number and total size of the reported files of a kind.
+type KindTotal struct {
+	Kind  ReportKind
+	Files int
+	Bytes int
+}
+
+// Totals returns the totals for each kind of reported file, sorted by kind.
+func (report *BuildReport) Totals() []KindTotal {
+	report.mu.Lock()
+	defer report.mu.Unlock()
+	var totals []KindTotal
+	for _, file := range report.files {
+		i := slices.IndexFunc(totals, func(total KindTotal) bool {
+			return total.Kind == file.Kind
+		})
+		if i < 0 {
+			i = len(totals)
+			totals = append(totals, KindTotal{Kind: file.Kind})
+		}
+		totals[i].Files++
+		totals[i].Bytes += file.Size
+	}
+	slices.SortFunc(totals, func(a, b KindTotal) int {
+		return cmp.Compare(a.Kind, b.Kind)
+	})
+	return totals
+}
+
+// Largest returns the `n` largest reported files, largest first (and by path
+// among files of the same size).
+func (report *BuildReport) Largest(n int) []ReportedFile {
+	files := report.Files()
+	slices.SortStableFunc(files, func(a, b ReportedFile) int {
+		return cmp.Compare(b.Size, a.Size)
+	})
+	return files[:min(n, len(files))]
+}
+
+// Log logs a summary of the report: a line with the build's totals, then
+// the per-kind totals and the largest files.
+func (report *BuildReport) Log() {
+	slog.Info(
+		"built site",
+		"posts", report.Count(ReportKindPost),
+		"indexPages", report.Count(ReportKindMainIndex)+
+			report.Count(ReportKindTagIndex),
+		"feeds", report.Count(ReportKindFeed),
+		"assets", report.Assets,
+		"words", report.Stats().Total.Words,
+		"rewritten", report.Rewritten(),
+		"bytes", report.Bytes(),
+		"elapsed", report.Elapsed,
+	)
+	for _, total := range report.Totals() {
+		slog.Info(
+			"output size",
+			"kind", total.Kind,
+			"files", total.Files,
+			"bytes", total.Bytes,
+		)
+	}
+	for _, file := range report.Largest(largestFiles) {
+		slog.Info("large output", "path", file.Path, "bytes", file.Size)
+	}
+}
+
+// largestFiles is the number of the largest files listed by `Log`.
+const largestFiles = 5
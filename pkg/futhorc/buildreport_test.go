@@ -0,0 +1,135 @@
+package futhorc
+
+import (
+	"context"
+	"io/fs"
+	"maps"
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+func TestBuildReportMatchesOutput(t *testing.T) {
+	pipeline, err := LoadPipeline(
+		writeProject(t, testTheme, map[string]string{
+			"futhorc.jsonc": `{"feeds": ["json", "atom"]}`,
+			"posts/a.md": "---\nTitle: A\nDate: 2024-01-01\n" +
+				"Tags: [go]\n---\n\nAlpha.\n",
+			"posts/b.md": "---\nTitle: B\nDate: 2024-01-02\n" +
+				"Tags: [go, rust]\n---\n\nBeta.\n",
+		}),
+		goldenSiteRoot,
+	)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if err := pipeline.Run(context.Background()); err != nil {
+		t.Fatal(err)
+	}
+	report := pipeline.Report
+
+	// every file on disk except for the assets (which are only counted) and
+	// the output marker is reported with its size
+	onDisk := map[string]int{}
+	if err := filepath.WalkDir(
+		pipeline.OutputPath,
+		func(p string, entry fs.DirEntry, err error) error {
+			if err != nil || entry.IsDir() {
+				return err
+			}
+			rel, err := filepath.Rel(pipeline.OutputPath, p)
+			if err != nil {
+				return err
+			}
+			rel = filepath.ToSlash(rel)
+			if rel == markerFile || strings.HasPrefix(rel, "assets/") {
+				return nil
+			}
+			info, err := entry.Info()
+			if err != nil {
+				return err
+			}
+			onDisk[rel] = int(info.Size())
+			return nil
+		},
+	); err != nil {
+		t.Fatal(err)
+	}
+	reported := map[string]int{}
+	for _, file := range report.Files() {
+		reported[file.Path] = file.Size
+	}
+	if !maps.Equal(reported, onDisk) {
+		t.Errorf("wanted the files on disk %v; found %v", onDisk, reported)
+	}
+
+	wanted := map[string]ReportKind{
+		"posts/a.html":    ReportKindPost,
+		"posts/b.html":    ReportKindPost,
+		"index.html":      ReportKindMainIndex,
+		"go/index.html":   ReportKindTagIndex,
+		"rust/index.html": ReportKindTagIndex,
+		"index.json":      ReportKindFeed,
+		"index.atom":      ReportKindFeed,
+		"go/index.json":   ReportKindFeed,
+		"go/index.atom":   ReportKindFeed,
+		"rust/index.json": ReportKindFeed,
+		"rust/index.atom": ReportKindFeed,
+	}
+	var bytes int
+	for _, file := range report.Files() {
+		bytes += file.Size
+		if kind, found := wanted[file.Path]; !found || kind != file.Kind {
+			t.Errorf(
+				"`%s`: wanted the kind `%s`; found `%s`",
+				file.Path,
+				kind,
+				file.Kind,
+			)
+		}
+
+		// pages have URLs, and feeds don't
+		url := goldenSiteRoot + file.Path
+		if file.Kind == ReportKindFeed {
+			url = ""
+		}
+		if file.URL != url {
+			t.Errorf(
+				"`%s`: wanted the URL `%s`; found `%s`",
+				file.Path,
+				url,
+				file.URL,
+			)
+		}
+	}
+	for _, tc := range []struct {
+		kind   ReportKind
+		wanted int
+	}{
+		{ReportKindPost, 2},
+		{ReportKindMainIndex, 1},
+		{ReportKindTagIndex, 2},
+		{ReportKindFeed, 6},
+	} {
+		if found := report.Count(tc.kind); found != tc.wanted {
+			t.Errorf("wanted %d %s files; found %d", tc.wanted, tc.kind, found)
+		}
+	}
+	if report.Bytes() != bytes {
+		t.Errorf("wanted %d bytes; found %d", bytes, report.Bytes())
+	}
+	if report.Assets != 1 {
+		t.Errorf("wanted 1 asset; found %d", report.Assets)
+	}
+
+	// a rebuild rewrites nothing
+	if err := pipeline.Run(context.Background()); err != nil {
+		t.Fatal(err)
+	}
+	if n := pipeline.Report.Rewritten(); n != 0 {
+		t.Errorf("wanted no files rewritten; found %d", n)
+	}
+	if n := len(pipeline.Report.Files()); n != len(onDisk) {
+		t.Errorf("wanted %d files reported again; found %d", len(onDisk), n)
+	}
+}
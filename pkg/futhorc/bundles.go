@@ -0,0 +1,51 @@
+package futhorc
+
+import (
+	"context"
+	"io/fs"
+	"path/filepath"
+	"strings"
+
+	"futhorc/pkg/actor"
+)
+
+// PostFinder finds the posts in `posts`. Every other file (other than in
+// `assets/`, which is copied separately, and those ignored by `filter`) is an
+// asset which is copied alongside the rendered posts, preserving its
+// directory, so relative references like `![x](diagram.png)` resolve to the
+// copy whether or not the post is in a bundle (a directory containing an
+// `index.md` post). The assets are sent to `assets` as they're found, so the
+// posts directory is only walked once, and `assets` is closed once the walk
+// is complete (or fails).
+func PostFinder(
+	posts fs.FS,
+	filter *AssetFilter,
+	assets chan<- string,
+) actor.OutputCallback[string] {
+	find := FileFinder(posts, "")
+	return func(ctx context.Context) (string, error) {
+		for {
+			p, err := find(ctx)
+			if err != nil {
+				close(assets)
+				return "", err
+			}
+			p = filepath.ToSlash(p)
+			if strings.HasSuffix(p, markdownSuffix) {
+				return p, nil
+			}
+			if strings.HasPrefix(p, postAssetsDirectory+"/") ||
+				filter.Ignored(p) {
+				continue
+			}
+			select {
+			case assets <- p:
+			case <-ctx.Done():
+				close(assets)
+				return "", ctx.Err()
+			}
+		}
+	}
+}
+
+const postAssetsDirectory = "assets"
@@ -0,0 +1,170 @@
+package futhorc
+
+import (
+	"context"
+	"errors"
+	"path/filepath"
+	"slices"
+	"strings"
+	"testing"
+	"testing/fstest"
+
+	"futhorc/pkg/actor"
+
+	"github.com/go-git/go-billy/v5/memfs"
+	"github.com/go-git/go-billy/v5/util"
+)
+
+func TestBundleAssetNames(t *testing.T) {
+	output := buildFixture(t, filepath.Join("testdata", "golden", "bundles"))
+
+	// the assets are copied under their own names
+	for _, asset := range []string{
+		"posts/trip/screen shot (1).png",
+		"posts/trip/写真.png",
+	} {
+		if _, err := output.Stat(asset); err != nil {
+			t.Errorf("wanted `%s` in the output: %v", asset, err)
+		}
+	}
+
+	// and linked by their encoded names
+	page, err := util.ReadFile(output, "posts/trip/index.html")
+	if err != nil {
+		t.Fatal(err)
+	}
+	for _, src := range []string{
+		`src="https://example.com/posts/trip/screen%20shot%20%281%29.png"`,
+		`src="https://example.com/posts/trip/%E5%86%99%E7%9C%9F.png"`,
+	} {
+		if !strings.Contains(string(page), src) {
+			t.Errorf("wanted %q in the post page", src)
+		}
+	}
+}
+
+func TestPostFinder(t *testing.T) {
+	posts := fstest.MapFS{
+		"a.md":              {Data: []byte("a")},
+		"diagram.png":       {Data: []byte("diagram")},
+		"sub/b.md":          {Data: []byte("b")},
+		"sub/chart.svg":     {Data: []byte("chart")},
+		"sub/deeper/x.txt":  {Data: []byte("x")},
+		"assets/logo.png":   {Data: []byte("logo")},
+		".drafts/c.txt":     {Data: []byte("c")},
+		"trip/.DS_Store":    {Data: []byte("junk")},
+		"trip/index.md":     {Data: []byte("trip")},
+		"trip/photo.png":    {Data: []byte("photo")},
+		"trip/map (1).json": {Data: []byte("map")},
+	}
+
+	// the assets are sent while the posts are found
+	assets := make(chan string)
+	found := make(chan []string)
+	go func() {
+		var all []string
+		for asset := range assets {
+			all = append(all, asset)
+		}
+		found <- all
+	}()
+
+	find := PostFinder(posts, &AssetFilter{}, assets)
+	var sources []string
+	for {
+		p, err := find(context.Background())
+		if errors.Is(err, actor.ErrStop) {
+			break
+		}
+		if err != nil {
+			t.Fatal(err)
+		}
+		sources = append(sources, p)
+	}
+
+	slices.Sort(sources)
+	wantSources := []string{"a.md", "sub/b.md", "trip/index.md"}
+	if !slices.Equal(sources, wantSources) {
+		t.Errorf("wanted posts %v; found %v", wantSources, sources)
+	}
+	copied := <-found
+	slices.Sort(copied)
+	wantAssets := []string{
+		"diagram.png",
+		"sub/chart.svg",
+		"sub/deeper/x.txt",
+		"trip/map (1).json",
+		"trip/photo.png",
+	}
+	if !slices.Equal(copied, wantAssets) {
+		t.Errorf("wanted assets %v; found %v", wantAssets, copied)
+	}
+}
+
+func TestPostAssets(t *testing.T) {
+	const post = "---\nTitle: Post\nDate: 2024-01-01\n---\n"
+	output, err := buildProject(writeProject(t, testTheme, map[string]string{
+		"posts/a.md":          post,
+		"posts/diagram.png":   "diagram",
+		"posts/sub/b.md":      post,
+		"posts/sub/chart.svg": "chart",
+		"posts/other/x.txt":   "x",
+	}))
+	if err != nil {
+		t.Fatal(err)
+	}
+	for _, p := range []string{
+		"posts/a.html",
+		"posts/diagram.png",
+		"posts/sub/b.html",
+		"posts/sub/chart.svg",
+		"posts/other/x.txt",
+	} {
+		if _, err := output.Stat(p); err != nil {
+			t.Errorf("wanted `%s` in the output: %v", p, err)
+		}
+	}
+}
+
+func TestPostAssetConflicts(t *testing.T) {
+	const post = "---\nTitle: Post\nDate: 2024-01-01\n---\n"
+	for _, tc := range []struct {
+		name  string
+		files map[string]string
+		path  string
+	}{
+		{
+			name:  "loose",
+			files: map[string]string{"posts/a.md": post, "posts/a.html": "a"},
+			path:  "posts/a.html",
+		},
+		{
+			name: "subdirectory",
+			files: map[string]string{
+				"posts/sub/b.md":   post,
+				"posts/sub/b.html": "b",
+			},
+			path: "posts/sub/b.html",
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			output, err := buildProject(writeProject(t, testTheme, tc.files))
+			var conflict *OutputConflictError
+			if !errors.As(err, &conflict) {
+				t.Fatalf("wanted an `*OutputConflictError`; found `%v`", err)
+			}
+			if conflict.Path != tc.path {
+				t.Errorf("wanted a conflict at `%s`; found `%v`", tc.path, err)
+			}
+
+			// nothing is written if the output plan has a conflict
+			diff, err := DiffOutputs(memfs.New(), output)
+			if err != nil {
+				t.Fatal(err)
+			}
+			if !diff.Empty() {
+				t.Errorf("wanted no output; found %v", diff.Added)
+			}
+		})
+	}
+}
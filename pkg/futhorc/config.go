@@ -0,0 +1,252 @@
+package futhorc
+
+import (
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io/fs"
+	"os"
+	"path/filepath"
+
+	"github.com/tailscale/hujson"
+)
+
+// Config holds the project-level settings. It's loaded from the optional
+// `futhorc.jsonc` file at the root of the project directory; a missing file
+// yields the zero value, which selects the defaults for every setting.
+type Config struct {
+	// Title and Description describe the site in its feeds and, as `.Title`
+	// and `.Description`, to every page. The title defaults to the host of
+	// the site root or, for a `file://` root, to the project directory's
+	// name.
+	Title       string `json:"title"`
+	Description string `json:"description"`
+
+	// Author is the site's author, who's credited in its feeds (e.g.,
+	// `{"name": "Jane Doe", "email": "jane@example.com"}`). The feeds have no
+	// author if it isn't set.
+	Author *Author `json:"author"`
+
+	// Language is the language of the site's content (e.g., `en` or `de`).
+	Language string `json:"language"`
+
+	// DateLocale overrides `Language` for the purpose of rendering month and
+	// weekday names in display dates.
+	DateLocale string `json:"dateLocale"`
+
+	// DateFormat is the Go time layout used to render display dates. It
+	// defaults to the locale's layout (`January 2, 2006` in English).
+	DateFormat string `json:"dateFormat"`
+
+	// OutputNormalization is the whitespace normalization applied to rendered
+	// pages: `off` (the default), `trailing-newline`, or
+	// `collapse-blank-lines`.
+	OutputNormalization string `json:"outputNormalization"`
+
+	// ReadingTime configures the reading time estimates for posts.
+	ReadingTime ReadingTime `json:"readingTime"`
+
+	// PostLimits guards the build against very large posts and pathological
+	// markdown.
+	PostLimits PostLimits `json:"postLimits"`
+
+	// FeedOrder is the order of the feed's entries: `published` (the
+	// default) or `updated`.
+	FeedOrder string `json:"feedOrder"`
+
+	// PreviewOutput renders drafts and future-dated posts into the
+	// `_preview/` subtree of the output directory instead of dropping them
+	// (drafts) or publishing them (future-dated posts). Previews aren't
+	// linked from any index or included in the feed.
+	PreviewOutput bool `json:"previewOutput"`
+
+	// MaxPageSizeKB is the size in kilobytes above which a rendered page logs
+	// a warning. It's disabled by default.
+	MaxPageSizeKB int `json:"maxPageSizeKB"`
+
+	// IndexGroups enables the per-tag groups of recent posts on the main
+	// index when it's set.
+	IndexGroups *IndexGroups `json:"indexGroups"`
+
+	// UncategorizedTag is the name of a tag given to posts without any tags,
+	// so they appear in a tag index of their own. It's disabled by default.
+	UncategorizedTag string `json:"uncategorizedTag"`
+
+	// IndexJSON writes each index page's data as JSON alongside the HTML
+	// page; see `IndexDataWriter`.
+	IndexJSON bool `json:"indexJSON"`
+
+	// LossyEncoding replaces the invalid bytes in post files which aren't
+	// valid UTF-8 (logging a warning) rather than failing the build.
+	LossyEncoding bool `json:"lossyEncoding"`
+
+	// StaticIgnore lists the patterns of theme and post assets which aren't
+	// copied to the output, in addition to the theme's; see `AssetFilter`.
+	StaticIgnore []string `json:"staticIgnore"`
+
+	// StaticInclude lists the patterns of assets which are copied even if
+	// they're ignored or dotfiles (e.g., `.well-known`).
+	StaticInclude []string `json:"staticInclude"`
+
+	// TagRedirects maps the old names of renamed tags to their new names.
+	// Each page of an old tag's index is replaced with a redirect to the
+	// corresponding page of the new tag's index. Like every setting which
+	// names tags, the names are matched by their slugs (so `Rust` and ` rust`
+	// are the same tag).
+	TagRedirects map[string]string `json:"tagRedirects"`
+
+	// CopyCheck determines when an asset whose copy already exists in the
+	// output is skipped: `mtime` (the default) when the size and modification
+	// time match, `hash` when the contents match, or `none` to always copy.
+	CopyCheck string `json:"copyCheck"`
+
+	// ExcludeFeatured removes the featured post from the regular list of
+	// posts on the first page of the main index, so themes which render it
+	// separately don't show it twice.
+	ExcludeFeatured bool `json:"excludeFeatured"`
+
+	// PrettyURLs writes each page to an `index.html` in a directory of its
+	// own and links to it by the directory (e.g., `posts/foo/` rather than
+	// `posts/foo.html`).
+	PrettyURLs bool `json:"prettyURLs"`
+
+	// MetaTags lists `<meta>` tags (e.g., `google-site-verification`) which
+	// are exposed to every page as `.MetaTags`.
+	MetaTags []MetaTag `json:"metaTags"`
+
+	// Webmention configures the webmention and pingback endpoints which are
+	// exposed to every page as `.Webmention`.
+	Webmention *Webmention `json:"webmention"`
+
+	// IndexSummaryMode is `full` (the default) to summarize each post on the
+	// index pages with its snippet, or `compact` to list just the titles,
+	// dates, and tags. TagSummaryModes overrides it for individual tags'
+	// indices.
+	IndexSummaryMode string            `json:"indexSummaryMode"`
+	TagSummaryModes  map[string]string `json:"tagSummaryModes"`
+
+	// SummarySeparator is the HTML comment which ends each post's snippet
+	// (`<!-- more -->` by default). Comments match if their text is the same
+	// regardless of the whitespace around it, so the default also matches
+	// `<!--more-->` (but not `<!-- More -->`). The comment delimiters are
+	// optional, so `✂` is the same separator as `<!-- ✂ -->`.
+	SummarySeparator string `json:"summarySeparator"`
+
+	// SinglePostTags determines how tags with exactly one post are indexed:
+	// `list` (the default) like any other tag, `inline` with an index page
+	// which can render the whole post, or `link` without an index, linking
+	// the tag straight to the post.
+	SinglePostTags string `json:"singlePostTags"`
+
+	// Feeds lists the formats in which the feed is written: `json` (the
+	// default), `rss`, and/or `atom`. The first is the site's primary feed
+	// (`.FeedURL`).
+	Feeds []string `json:"feeds"`
+
+	// FeedEntryLimit is the maximum number of posts in the feed (the most
+	// recent). It's unlimited by default (or when 0).
+	FeedEntryLimit int `json:"feedEntryLimit"`
+
+	// ExternalLinksNewTab makes links in posts to other hosts than the
+	// site's open in a new tab, with `rel="noopener noreferrer"`. Relative
+	// and `mailto:` links are never changed.
+	ExternalLinksNewTab bool `json:"externalLinksNewTab"`
+
+	// CheckLinks determines what happens to links in posts to pages and
+	// files on the site which the build doesn't write (e.g., renamed posts):
+	// `off` (the default) doesn't check them, `warn` logs each broken link,
+	// and `error` fails the build. Fragments aren't checked.
+	CheckLinks string `json:"checkLinks"`
+
+	// HeadingShift is the number of levels by which post headings are
+	// demoted (2 by default, so `#` renders as `<h3>` beneath the site's
+	// `<h1>` and the post's `<h2>`). Headings are never demoted below
+	// `<h6>`, and 0 leaves them untouched.
+	HeadingShift *int `json:"headingShift"`
+}
+
+// Author identifies the author of a site. The email address is optional.
+type Author struct {
+	Name  string `json:"name"`
+	Email string `json:"email"`
+}
+
+func LoadConfig(dir fs.FS) (config Config, err error) {
+	var data []byte
+	if data, err = fs.ReadFile(dir, configFile); err != nil {
+		if errors.Is(err, fs.ErrNotExist) {
+			err = nil
+			return
+		}
+		err = fmt.Errorf("loading config: %w", err)
+		return
+	}
+	if data, err = hujson.Standardize(data); err != nil {
+		err = fmt.Errorf("loading config: %w", err)
+		return
+	}
+	if err = json.Unmarshal(data, &config); err != nil {
+		err = fmt.Errorf("loading config: %w", err)
+		return
+	}
+	return
+}
+
+// DateFormatter builds the formatter for display dates from the `DateFormat`,
+// `DateLocale`, and `Language` settings. Without a `DateFormat`, dates are
+// written the locale's way (e.g., `2. January 2006` in German).
+func (config *Config) DateFormatter() DateFormatter {
+	tag := config.DateLocale
+	if tag == "" {
+		tag = config.Language
+	}
+	locale := LookupLocale(tag)
+	layout := config.DateFormat
+	if layout == "" {
+		layout = defaultDateFormat
+		if locale != nil {
+			layout = locale.Layout
+		}
+	}
+	return DateFormatter{Layout: layout, Locale: locale}
+}
+
+const (
+	configFile        = "futhorc.jsonc"
+	defaultDateFormat = "January 2, 2006"
+
+	// a shift of 5 demotes every heading to `<h6>`
+	defaultHeadingShift = 2
+	maxHeadingShift     = 5
+)
+
+// FindProjectDir searches `start` and its parent directories for a project
+// directory, i.e., one containing a `futhorc.jsonc` or `theme/theme.jsonc`
+// file.
+func FindProjectDir(start string) (string, error) {
+	dir, err := filepath.Abs(start)
+	if err != nil {
+		return "", fmt.Errorf("finding project directory: %w", err)
+	}
+	for {
+		for _, marker := range projectMarkers {
+			if _, err := os.Stat(filepath.Join(dir, marker)); err == nil {
+				return dir, nil
+			} else if !errors.Is(err, fs.ErrNotExist) {
+				return "", fmt.Errorf("finding project directory: %w", err)
+			}
+		}
+		parent := filepath.Dir(dir)
+		if parent == dir {
+			return "", fmt.Errorf(
+				"finding project directory: no `%s` found in `%s` or any "+
+					"parent directory",
+				configFile,
+				start,
+			)
+		}
+		dir = parent
+	}
+}
+
+var projectMarkers = []string{configFile, "theme/theme.jsonc"}
@@ -0,0 +1,169 @@
+package futhorc
+
+import (
+	"errors"
+	"fmt"
+	"path"
+	"path/filepath"
+	"slices"
+	"strings"
+)
+
+// OutputConflictError is returned when two outputs can't coexist because one
+// needs `Path` to be a file while another needs it to be a directory (or two
+// outputs would be written to the same file).
+type OutputConflictError struct {
+	Path   string
+	First  string
+	Second string
+
+	// IsDir is true if `Second` needs `Path` to be a directory.
+	IsDir bool
+}
+
+func (err *OutputConflictError) Error() string {
+	if err.IsDir {
+		return fmt.Sprintf(
+			"output conflict: `%s` is a file for %s but a directory for %s",
+			err.Path,
+			err.First,
+			err.Second,
+		)
+	}
+	return fmt.Sprintf(
+		"output conflict: `%s` is written by both %s and %s",
+		err.Path,
+		err.First,
+		err.Second,
+	)
+}
+
+// plannedOutput is a page the pipeline will write, along with a description
+// of what produces it.
+type plannedOutput struct {
+	path   string
+	source string
+}
+
+// CheckOutputConflicts plans the post, index, and feed pages produced by
+// `posts` (and the tags page, if `tagsPage` is set), along with the preview
+// pages `previews` and the copies of the post assets `assets`, and returns an
+// error describing every conflict among them. It runs before any pages are
+// written or files are copied (see `Gate`) so that a conflict never leaves
+// the output partially written (or silently shadowed).
+func CheckOutputConflicts(
+	posts []OrderedPage[Post],
+	previews []*OrderedPage[Post],
+	pageSize int,
+	prettyURLs bool,
+	feedFormats []FeedFormat,
+	tagsPage bool,
+	assets []string,
+) error {
+	outputs := planOutputs(posts, pageSize, prettyURLs, feedFormats, tagsPage)
+	for _, preview := range previews {
+		outputs = append(outputs, plannedOutput{
+			path: preview.Path,
+			source: fmt.Sprintf(
+				"the preview of post `%s`",
+				preview.Content.Source,
+			),
+		})
+	}
+	for _, asset := range assets {
+		outputs = append(outputs, plannedOutput{
+			path:   path.Join("posts", asset),
+			source: fmt.Sprintf("asset `%s`", asset),
+		})
+	}
+	return checkConflicts(outputs)
+}
+
+func planOutputs(
+	posts []OrderedPage[Post],
+	pageSize int,
+	prettyURLs bool,
+	feedFormats []FeedFormat,
+	tagsPage bool,
+) []plannedOutput {
+	outputs := make([]plannedOutput, 0, len(posts))
+	if tagsPage {
+		outputs = append(outputs, plannedOutput{
+			path:   tagsPagePath(prettyURLs),
+			source: "the tags page",
+		})
+	}
+	counts := map[string]int{"": len(posts)}
+	for i := range posts {
+		outputs = append(outputs, plannedOutput{
+			path:   posts[i].Path,
+			source: fmt.Sprintf("post `%s`", posts[i].Content.Source),
+		})
+		for _, tag := range posts[i].Content.Tags {
+			counts[tag.Slug]++
+		}
+	}
+
+	for id, count := range counts {
+		source := "the main index"
+		if id != "" {
+			source = fmt.Sprintf("the index for tag `%s`", id)
+		}
+		for page := range max((count+pageSize-1)/pageSize, 1) {
+			p := indexPagePath(id, page, prettyURLs)
+			outputs = append(outputs, plannedOutput{path: p, source: source})
+			if id == "" {
+				for _, format := range feedFormats {
+					outputs = append(outputs, plannedOutput{
+						path:   feedPath(p, format),
+						source: "the feed",
+					})
+				}
+			}
+		}
+	}
+	return outputs
+}
+
+func checkConflicts(outputs []plannedOutput) error {
+	files := make(map[string]string, len(outputs))
+	dirs := make(map[string]string)
+	var conflicts []*OutputConflictError
+	for _, output := range outputs {
+		p := filepath.ToSlash(output.path)
+		if source, found := files[p]; found {
+			conflicts = append(conflicts, &OutputConflictError{
+				Path:   p,
+				First:  source,
+				Second: output.source,
+			})
+			continue
+		}
+		files[p] = output.source
+		for dir := path.Dir(p); dir != "." && dir != "/"; dir = path.Dir(dir) {
+			if _, found := dirs[dir]; !found {
+				dirs[dir] = output.source
+			}
+		}
+	}
+
+	for p, source := range files {
+		if dirSource, found := dirs[p]; found {
+			conflicts = append(conflicts, &OutputConflictError{
+				Path:   p,
+				First:  source,
+				Second: dirSource,
+				IsDir:  true,
+			})
+		}
+	}
+
+	slices.SortFunc(conflicts, func(a, b *OutputConflictError) int {
+		return strings.Compare(a.Path, b.Path)
+	})
+	errs := make([]error, len(conflicts))
+	for i := range conflicts {
+		errs[i] = conflicts[i]
+	}
+	return errors.Join(errs...)
+}
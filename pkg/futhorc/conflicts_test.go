@@ -0,0 +1,79 @@
+package futhorc
+
+import (
+	"errors"
+	"strings"
+	"testing"
+
+	"github.com/go-git/go-billy/v5/memfs"
+)
+
+func TestCheckConflicts(t *testing.T) {
+	err := checkConflicts([]plannedOutput{
+		{path: "posts/a.html", source: "post `a.md`"},
+		{path: "index.html", source: "the main index"},
+		{path: "posts/a.html/b.html", source: "post `a.html/b.md`"},
+		{path: "index.html", source: "the tags page"},
+		{path: "posts/c.html", source: "post `c.md`"},
+	})
+	joined, ok := err.(interface{ Unwrap() []error })
+	if !ok {
+		t.Fatalf("wanted the joined conflicts; found `%v`", err)
+	}
+
+	// every conflict is reported, in path order
+	wanted := []OutputConflictError{
+		{
+			Path:   "index.html",
+			First:  "the main index",
+			Second: "the tags page",
+		},
+		{
+			Path:   "posts/a.html",
+			First:  "post `a.md`",
+			Second: "post `a.html/b.md`",
+			IsDir:  true,
+		},
+	}
+	errs := joined.Unwrap()
+	if len(errs) != len(wanted) {
+		t.Fatalf(
+			"wanted %d conflicts; found %d: %v",
+			len(wanted),
+			len(errs),
+			err,
+		)
+	}
+	for i := range wanted {
+		var conflict *OutputConflictError
+		if !errors.As(errs[i], &conflict) || *conflict != wanted[i] {
+			t.Errorf("wanted the conflict %+v; found `%v`", wanted[i], errs[i])
+		}
+	}
+}
+
+func TestOutputConflictBeforeWriting(t *testing.T) {
+	const post = "---\nTitle: Post\nDate: 2024-01-01\n---\n"
+	output, err := buildProject(writeProject(t, testTheme, map[string]string{
+		"posts/foo.md":          post,
+		"posts/foo.html/bar.md": post,
+	}))
+	var conflict *OutputConflictError
+	if !errors.As(err, &conflict) {
+		t.Fatalf("wanted an `*OutputConflictError`; found `%v`", err)
+	}
+	for _, source := range []string{"`foo.md`", "`foo.html/bar.md`"} {
+		if !strings.Contains(err.Error(), source) {
+			t.Errorf("wanted the error to name %s: %v", source, err)
+		}
+	}
+
+	// the conflict is found before anything is written
+	diff, err := DiffOutputs(memfs.New(), output)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if !diff.Empty() {
+		t.Errorf("wanted no output; found %v", diff.Added)
+	}
+}
@@ -0,0 +1,202 @@
+package futhorc
+
+import (
+	"bytes"
+	"crypto/sha256"
+	"errors"
+	"fmt"
+	"io/fs"
+	"os"
+	"path"
+	"path/filepath"
+	"slices"
+	"strings"
+
+	"github.com/go-git/go-billy/v5"
+	"github.com/go-git/go-billy/v5/util"
+)
+
+// OutputDiff describes the differences between two builds of a site. Each
+// list holds slash-separated paths relative to the output directory, sorted.
+type OutputDiff struct {
+	Added    []string
+	Removed  []string
+	Modified []string
+}
+
+// Empty reports whether the builds are identical.
+func (diff *OutputDiff) Empty() bool {
+	return len(diff.Added) < 1 && len(diff.Removed) < 1 &&
+		len(diff.Modified) < 1
+}
+
+// DiffOutputs compares the output of a previous build (`before`) with a new
+// one (`after`), where files are modified if their contents differ. A missing
+// `before` output directory is treated as empty. The output directory's marker
+// isn't a build output, so it's ignored. Neither filesystem is modified.
+func DiffOutputs(before, after billy.Filesystem) (diff OutputDiff, err error) {
+	var oldHashes, newHashes map[string][sha256.Size]byte
+	if oldHashes, err = hashFiles(before); err != nil {
+		err = fmt.Errorf("diffing outputs: %w", err)
+		return
+	}
+	if newHashes, err = hashFiles(after); err != nil {
+		err = fmt.Errorf("diffing outputs: %w", err)
+		return
+	}
+
+	for p, newHash := range newHashes {
+		if oldHash, found := oldHashes[p]; !found {
+			diff.Added = append(diff.Added, p)
+		} else if oldHash != newHash {
+			diff.Modified = append(diff.Modified, p)
+		}
+	}
+	for p := range oldHashes {
+		if _, found := newHashes[p]; !found {
+			diff.Removed = append(diff.Removed, p)
+		}
+	}
+	slices.Sort(diff.Added)
+	slices.Sort(diff.Removed)
+	slices.Sort(diff.Modified)
+	return
+}
+
+func hashFiles(fsys billy.Filesystem) (map[string][sha256.Size]byte, error) {
+	hashes := map[string][sha256.Size]byte{}
+	err := util.Walk(
+		fsys,
+		"/",
+		func(p string, info fs.FileInfo, err error) error {
+			if err != nil {
+				return err
+			}
+			if info.IsDir() {
+				return nil
+			}
+			if diffPath(p) == markerFile {
+				return nil
+			}
+			data, err := util.ReadFile(fsys, p)
+			if err != nil {
+				return err
+			}
+			hashes[diffPath(p)] = sha256.Sum256(data)
+			return nil
+		},
+	)
+	if errors.Is(err, os.ErrNotExist) && len(hashes) < 1 {
+		err = nil
+	}
+	return hashes, err
+}
+
+func diffPath(p string) string {
+	return strings.TrimPrefix(path.Clean(filepath.ToSlash(p)), "/")
+}
+
+// UnifiedDiff renders a unified diff of the lines of `before` and `after` with
+// three lines of context. The diff is computed from the longest common
+// subsequence of lines, which is quadratic, so callers should limit the size
+// of the inputs.
+func UnifiedDiff(name string, before, after []byte) string {
+	a, b := splitLines(before), splitLines(after)
+
+	// lcs[i][j] is the length of the longest common subsequence of a[i:]
+	// and b[j:]
+	lcs := make([][]int, len(a)+1)
+	for i := range lcs {
+		lcs[i] = make([]int, len(b)+1)
+	}
+	for i := len(a) - 1; i >= 0; i-- {
+		for j := len(b) - 1; j >= 0; j-- {
+			if a[i] == b[j] {
+				lcs[i][j] = lcs[i+1][j+1] + 1
+			} else {
+				lcs[i][j] = max(lcs[i+1][j], lcs[i][j+1])
+			}
+		}
+	}
+
+	// walk the table to produce the edit script
+	type edit struct {
+		op   byte
+		line string
+		i, j int // the line's position in `a` and `b`
+	}
+	var edits []edit
+	i, j := 0, 0
+	for i < len(a) || j < len(b) {
+		switch {
+		case i < len(a) && j < len(b) && a[i] == b[j]:
+			edits = append(edits, edit{' ', a[i], i, j})
+			i, j = i+1, j+1
+		case j < len(b) && (i == len(a) || lcs[i][j+1] >= lcs[i+1][j]):
+			edits = append(edits, edit{'+', b[j], i, j})
+			j++
+		default:
+			edits = append(edits, edit{'-', a[i], i, j})
+			i++
+		}
+	}
+
+	const context = 3
+	var out strings.Builder
+	fmt.Fprintf(&out, "--- a/%s\n+++ b/%s\n", name, name)
+	for start := 0; start < len(edits); {
+		// find the next change
+		for start < len(edits) && edits[start].op == ' ' {
+			start++
+		}
+		if start == len(edits) {
+			break
+		}
+
+		// extend the hunk until there are more than `2*context` unchanged
+		// lines between changes
+		end, unchanged := start, 0
+		for k := start; k < len(edits) && unchanged <= 2*context; k++ {
+			if edits[k].op == ' ' {
+				unchanged++
+			} else {
+				end, unchanged = k+1, 0
+			}
+		}
+		first := max(start-context, 0)
+		last := min(end+context, len(edits))
+
+		var oldLines, newLines int
+		for _, e := range edits[first:last] {
+			if e.op != '+' {
+				oldLines++
+			}
+			if e.op != '-' {
+				newLines++
+			}
+		}
+		fmt.Fprintf(
+			&out,
+			"@@ -%d,%d +%d,%d @@\n",
+			edits[first].i+1,
+			oldLines,
+			edits[first].j+1,
+			newLines,
+		)
+		for _, e := range edits[first:last] {
+			out.WriteByte(e.op)
+			out.WriteString(e.line)
+			out.WriteByte('\n')
+		}
+		start = last
+	}
+	return out.String()
+}
+
+func splitLines(data []byte) []string {
+	data = bytes.TrimSuffix(data, []byte("\n"))
+	if len(data) < 1 {
+		return nil
+	}
+	return strings.Split(string(data), "\n")
+}
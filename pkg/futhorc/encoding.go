@@ -0,0 +1,76 @@
+package futhorc
+
+import (
+	"bytes"
+	"fmt"
+	"log/slog"
+	"unicode/utf8"
+)
+
+// InvalidEncodingError is returned for a post whose contents or file name
+// aren't valid UTF-8.
+type InvalidEncodingError struct {
+	// Path is the source path of the post.
+	Path string
+
+	// Line is the first line of the file which isn't valid UTF-8 (0 if the
+	// file name is invalid).
+	Line int
+}
+
+func (err *InvalidEncodingError) Error() string {
+	if err.Line < 1 {
+		return fmt.Sprintf(
+			"post %q: file name isn't valid UTF-8; rename the file",
+			err.Path,
+		)
+	}
+	return fmt.Sprintf(
+		"post `%s`: line %d isn't valid UTF-8; convert the file to UTF-8 "+
+			"(e.g., `iconv -f latin1 -t utf-8`) or enable `lossyEncoding`",
+		err.Path,
+		err.Line,
+	)
+}
+
+// checkEncoding verifies that a post file is UTF-8. If `lossy` is true,
+// invalid contents are replaced with the Unicode replacement character rather
+// than rejected. Invalid file names are always rejected since the post's
+// output path is derived from them.
+func checkEncoding(file *FileData, lossy bool) error {
+	if !utf8.ValidString(file.Path) {
+		return &InvalidEncodingError{Path: file.Path}
+	}
+	if utf8.Valid(file.Data) {
+		return nil
+	}
+
+	line := invalidLine(file.Data)
+	if !lossy {
+		return &InvalidEncodingError{Path: file.Path, Line: line}
+	}
+	slog.Warn(
+		"post isn't valid UTF-8; replacing invalid bytes",
+		"post", file.Path,
+		"line", line,
+	)
+	file.Data = bytes.ToValidUTF8(file.Data, []byte(string(utf8.RuneError)))
+	return nil
+}
+
+// invalidLine returns the 1-based line number of the first invalid UTF-8 in
+// `data`.
+func invalidLine(data []byte) int {
+	line := 1
+	for len(data) > 0 {
+		r, size := utf8.DecodeRune(data)
+		if r == utf8.RuneError && size < 2 {
+			return line
+		}
+		if r == '\n' {
+			line++
+		}
+		data = data[size:]
+	}
+	return line
+}
@@ -0,0 +1,76 @@
+package futhorc
+
+import (
+	"context"
+	"errors"
+	"strings"
+	"testing"
+
+	"github.com/go-git/go-billy/v5/memfs"
+	"github.com/go-git/go-billy/v5/util"
+)
+
+func TestLatin1Post(t *testing.T) {
+	// "café" in Latin-1 on the sixth line: `é` is the lone byte 0xe9, which
+	// isn't valid UTF-8
+	const latin1 = "---\nTitle: Latin-1\nDate: 2024-01-01\n---\n\ncaf\xe9\n"
+	for _, lossy := range []bool{false, true} {
+		dir := writeProject(t, testTheme, map[string]string{
+			"posts/a.md":      "---\nTitle: A\nDate: 2024-01-02\n---\n",
+			"posts/latin1.md": latin1,
+		})
+		pipeline, err := LoadPipeline(dir, goldenSiteRoot)
+		if err != nil {
+			t.Fatal(err)
+		}
+		output := memfs.New()
+		pipeline.OutputPath = ""
+		pipeline.OutputDirectory = output
+		pipeline.LossyEncoding = lossy
+		err = pipeline.Run(context.Background())
+
+		if !lossy {
+			var encodingErr *InvalidEncodingError
+			if !errors.As(err, &encodingErr) {
+				t.Fatalf("wanted an `*InvalidEncodingError`; found `%v`", err)
+			}
+			if encodingErr.Path != "latin1.md" || encodingErr.Line != 6 {
+				t.Errorf(
+					"wanted `latin1.md` line 6; found `%s` line %d",
+					encodingErr.Path,
+					encodingErr.Line,
+				)
+			}
+			continue
+		}
+
+		if err != nil {
+			t.Fatalf("wanted the lossy build to succeed: %v", err)
+		}
+		data, err := util.ReadFile(output, "posts/latin1.html")
+		if err != nil {
+			t.Fatal(err)
+		}
+		if wanted := "caf\uFFFD"; !strings.Contains(string(data), wanted) {
+			t.Errorf("wanted %q in the post; found %q", wanted, data)
+		}
+	}
+}
+
+func TestInvalidLine(t *testing.T) {
+	for _, tc := range []struct {
+		name   string
+		data   string
+		wanted int
+	}{
+		{name: "first", data: "\xe9\nfine\n", wanted: 1},
+		{name: "after-newlines", data: "fine\n\nok \xff\n", wanted: 3},
+		{name: "multibyte", data: "naïve\ncafé\n\xe9", wanted: 3},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			if found := invalidLine([]byte(tc.data)); found != tc.wanted {
+				t.Errorf("wanted line %d; found %d", tc.wanted, found)
+			}
+		})
+	}
+}
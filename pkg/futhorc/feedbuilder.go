@@ -1,14 +1,19 @@
 package futhorc
 
 import (
+	"cmp"
 	"context"
 	"encoding/json"
-	"errors"
 	"fmt"
+	"net/url"
+	"path"
+	"slices"
+	"strings"
 	"time"
 	"unsafe"
 
 	"futhorc/pkg/actor"
+	"futhorc/pkg/markdown"
 
 	"github.com/go-git/go-billy/v5"
 	"github.com/gorilla/feeds"
@@ -17,59 +22,122 @@ import (
 func FeedBuilder(
 	header *feeds.Feed,
 	output billy.Filesystem,
+	formats []FeedFormat,
+	report *BuildReport,
 ) actor.InputCallback[*OrderedPage[IndexPage]] {
 	return func(ctx context.Context, page *OrderedPage[IndexPage]) error {
-		return buildFeed(header, output, page)
+		return buildFeed(header, output, formats, report, page)
 	}
 }
 
 func buildFeed(
 	header *feeds.Feed,
 	output billy.Filesystem,
+	formats []FeedFormat,
+	report *BuildReport,
 	page *OrderedPage[IndexPage],
 ) error {
 	// skip tag indices
 	if page.Content.IndexID != "" {
 		return nil
 	}
-	path := page.Path[:len(page.Path)-len(htmlSuffix)] + jsonSuffix
 
 	feed := buildFeedPage(header, &page.Page)
+	for _, format := range formats {
+		path := feedPath(page.Path, format)
+		data, err := renderFeed(&feed, page, format)
+		if err != nil {
+			return fmt.Errorf(
+				"rendering feed for index page `%s`: %w",
+				path,
+				err,
+			)
+		}
+
+		written, err := writeFile(output, path, data)
+		if err != nil {
+			return fmt.Errorf(
+				"rendering feed for index page `%s`: %w",
+				path,
+				err,
+			)
+		}
+		report.Record(ReportedFile{
+			Path: path,
+			Kind: ReportKindFeed,
+			Size: len(data),
+
+			Unchanged: !written,
+		})
+	}
+
+	return nil
+}
+
+func renderFeed(
+	feed *feeds.Feed,
+	page *OrderedPage[IndexPage],
+	format FeedFormat,
+) ([]byte, error) {
+	switch format {
+	case FeedFormatRSS:
+		data, err := feeds.ToXML(newRSSFeed(feed, page))
+		return []byte(data), err
+	case FeedFormatAtom:
+		data, err := feeds.ToXML(newAtomFeed(feed, page))
+		return []byte(data), err
+	}
+
+	// `next_url` is the feed of the next (older) index page, not the page
+	// itself
 	var next string
-	if page.Next != nil {
-		next = page.Next.String()
+	if page.Content.NextPage != nil {
+		next = feedURL(page.Content.NextPage.URL, FeedFormatJSON).String()
 	}
-	data, err := json.Marshal(struct {
+	jsonFeed := (&feeds.JSON{Feed: feed}).JSONFeed()
+	jsonFeed.Version = jsonFeedVersion
+	jsonFeed.FeedUrl = feedURL(page.URL, FeedFormatJSON).String()
+
+	// JSON Feed 1.1 replaces `author` with `authors` for the feed and its
+	// items, but `author` is kept for 1.0 readers
+	items := make([]jsonFeedItem, len(jsonFeed.Items))
+	for i, item := range jsonFeed.Items {
+		item.Tags = feedTags(&page.Content.Posts[i].Content)
+		items[i] = jsonFeedItem{
+			JSONItem: item,
+			Authors:  jsonAuthors(item.Author),
+		}
+	}
+	return json.Marshal(struct {
 		*feeds.JSONFeed
-		Next string `json:"next_url,omitempty"`
+		Items   []jsonFeedItem      `json:"items,omitempty"`
+		Authors []*feeds.JSONAuthor `json:"authors,omitempty"`
+		Next    string              `json:"next_url,omitempty"`
 	}{
-		JSONFeed: (&feeds.JSON{Feed: &feed}).JSONFeed(),
+		JSONFeed: jsonFeed,
+		Items:    items,
+		Authors:  jsonAuthors(jsonFeed.Author),
 		Next:     next,
 	})
-	if err != nil {
-		return fmt.Errorf("rendering feed for index page `%s`: %w", path, err)
-	}
-
-	file, err := output.Create(path)
-	if err != nil {
-		return fmt.Errorf("rendering feed for index page `%s`: %w", path, err)
-	}
+}
 
-	if _, err := file.Write(data); err != nil {
-		return fmt.Errorf(
-			"rendering feed for index page `%s`: %w",
-			path,
-			errors.Join(err, file.Close()),
-		)
-	}
+// jsonFeedItem is a JSON Feed item with its JSON Feed 1.1 `authors`.
+type jsonFeedItem struct {
+	*feeds.JSONItem
+	Authors []*feeds.JSONAuthor `json:"authors,omitempty"`
+}
 
-	if err := file.Close(); err != nil {
-		return fmt.Errorf("rendering feed for index page `%s`: %w", path, err)
+// jsonAuthors returns the JSON Feed 1.1 `authors` for a JSON Feed 1.0
+// `author`, which may be nil.
+func jsonAuthors(author *feeds.JSONAuthor) []*feeds.JSONAuthor {
+	if author == nil {
+		return nil
 	}
-
-	return nil
+	return []*feeds.JSONAuthor{author}
 }
 
+const jsonFeedVersion = "https://jsonfeed.org/version/1.1"
+
 func buildFeedPage(
 	header *feeds.Feed,
 	page *Page[IndexPage],
@@ -91,6 +159,13 @@ func buildFeedPage(
 			&items[i].link,
 		)
 		feed.Items[i] = &items[i].item
+
+		// the feed is updated when its newest entry is (never at build time)
+		// so that rebuilding unchanged posts produces an identical feed
+		modified := page.Content.Posts[i].Content.Modified()
+		if modified.After(feed.Updated) {
+			feed.Updated = modified
+		}
 	}
 	return
 }
@@ -100,13 +175,289 @@ func buildFeedItem(
 	author *feeds.Author,
 	link *feeds.Link,
 ) (item feeds.Item) {
-	author.Name = p.Content.Author
-	item.Author = author
+	item.Title = p.Content.Title
+	if p.Content.Author != "" {
+		author.Name = p.Content.Author
+		item.Author = author
+	}
 	item.Created = time.Time(p.Content.Date)
+	if p.Content.Updated != nil {
+		item.Updated = time.Time(*p.Content.Updated)
+	}
 	link.Href = p.URL.String()
 	item.Link = link
+	item.Id = p.URL.String()
 	item.Description = *(*string)(unsafe.Pointer(&p.Content.Snippet))
+	if p.Content.ContentWarning != "" {
+		item.Description = p.Content.ContentWarning
+	}
+
+	// the body's links are already absolute except for its footnote links,
+	// which are local to the post page
+	content := markdown.AbsoluteFootnotes(p.Content.Body, p.URL)
+	item.Content = *(*string)(unsafe.Pointer(&content))
 	return
 }
 
-const jsonSuffix = ".json"
+// feedTags returns the sorted names of the post's tags, excluding the implicit
+// uncategorized tag.
+func feedTags(p *Post) []string {
+	var tags []string
+	for _, tag := range p.Tags {
+		if !tag.Implicit {
+			tags = append(tags, tag.Text)
+		}
+	}
+	slices.Sort(tags)
+	return tags
+}
+
+// feedCategory is a tag as a feed category, identified by its index URL.
+type feedCategory struct {
+	Name string
+	URL  string
+}
+
+// postCategories returns the categories of the post's tags, excluding the
+// implicit uncategorized tag, sorted by name.
+func postCategories(p *Post) []feedCategory {
+	var categories []feedCategory
+	for _, tag := range p.Tags {
+		if !tag.Implicit {
+			categories = append(categories, feedCategory{
+				Name: tag.Text,
+				URL:  string(tag.URL),
+			})
+		}
+	}
+	sortCategories(categories)
+	return categories
+}
+
+// pageCategories returns the union of the categories of the posts on the
+// page, sorted by name.
+func pageCategories(page *OrderedPage[IndexPage]) []feedCategory {
+	var categories []feedCategory
+	seen := map[feedCategory]struct{}{}
+	for _, post := range page.Content.Posts {
+		for _, category := range postCategories(&post.Content) {
+			if _, found := seen[category]; !found {
+				seen[category] = struct{}{}
+				categories = append(categories, category)
+			}
+		}
+	}
+	sortCategories(categories)
+	return categories
+}
+
+func sortCategories(categories []feedCategory) {
+	slices.SortFunc(categories, func(a, b feedCategory) int {
+		return cmp.Or(
+			strings.Compare(a.Name, b.Name),
+			strings.Compare(a.URL, b.URL),
+		)
+	})
+}
+
+// `feeds.Item` has no categories, so the Atom and RSS feeds are extended
+// with them after they're built. The fields here shadow their (string)
+// namesakes in the embedded structs.
+
+type atomFeed struct {
+	*feeds.AtomFeed
+	Categories []atomCategory `xml:"category"`
+	Entries    []atomEntry    `xml:"entry"`
+}
+
+type atomEntry struct {
+	*feeds.AtomEntry
+	Categories []atomCategory `xml:"category"`
+}
+
+type atomCategory struct {
+	Term   string `xml:"term,attr"`
+	Scheme string `xml:"scheme,attr,omitempty"`
+	Label  string `xml:"label,attr,omitempty"`
+}
+
+func newAtomFeed(feed *feeds.Feed, page *OrderedPage[IndexPage]) *atomFeed {
+	atom := atomFeed{AtomFeed: (&feeds.Atom{Feed: feed}).AtomFeed()}
+	atom.Categories = atomCategories(pageCategories(page))
+	atom.Entries = make([]atomEntry, len(atom.AtomFeed.Entries))
+	for i, entry := range atom.AtomFeed.Entries {
+		atom.Entries[i] = atomEntry{
+			AtomEntry: entry,
+			Categories: atomCategories(
+				postCategories(&page.Content.Posts[i].Content),
+			),
+		}
+	}
+	return &atom
+}
+
+func (feed *atomFeed) FeedXml() interface{} { return feed }
+
+func atomCategories(categories []feedCategory) []atomCategory {
+	out := make([]atomCategory, len(categories))
+	for i, category := range categories {
+		out[i] = atomCategory{
+			Term:   category.Name,
+			Scheme: category.URL,
+			Label:  category.Name,
+		}
+	}
+	return out
+}
+
+type rssFeed struct {
+	*feeds.RssFeedXml
+	Channel *rssChannel `xml:"channel"`
+}
+
+type rssChannel struct {
+	*feeds.RssFeed
+	Categories []rssCategory `xml:"category"`
+	Items      []rssItem     `xml:"item"`
+}
+
+type rssItem struct {
+	*feeds.RssItem
+	Categories []rssCategory `xml:"category"`
+}
+
+type rssCategory struct {
+	Domain string `xml:"domain,attr,omitempty"`
+	Name   string `xml:",chardata"`
+}
+
+func newRSSFeed(feed *feeds.Feed, page *OrderedPage[IndexPage]) *rssFeed {
+	rss := rssFeed{
+		RssFeedXml: (&feeds.Rss{Feed: feed}).FeedXml().(*feeds.RssFeedXml),
+	}
+	rss.Channel = &rssChannel{
+		RssFeed:    rss.RssFeedXml.Channel,
+		Categories: rssCategories(pageCategories(page)),
+		Items:      make([]rssItem, len(rss.RssFeedXml.Channel.Items)),
+	}
+	for i, item := range rss.RssFeedXml.Channel.Items {
+		rss.Channel.Items[i] = rssItem{
+			RssItem: item,
+			Categories: rssCategories(
+				postCategories(&page.Content.Posts[i].Content),
+			),
+		}
+	}
+	return &rss
+}
+
+func (feed *rssFeed) FeedXml() interface{} { return feed }
+
+func rssCategories(categories []feedCategory) []rssCategory {
+	out := make([]rssCategory, len(categories))
+	for i, category := range categories {
+		out[i] = rssCategory{Domain: category.URL, Name: category.Name}
+	}
+	return out
+}
+
+// feedPath returns the output path of the feed page in `format` for the index
+// page at `indexPath`.
+func feedPath(indexPath string, format FeedFormat) string {
+	return strings.TrimSuffix(indexPath, htmlSuffix) + format.suffix()
+}
+
+// feedURL returns the URL of the feed in `format` for the index page at
+// `page`, which is either a file (`page-002.html`) or, with pretty URLs, a
+// directory (`page-002/`).
+func feedURL(page *url.URL, format FeedFormat) *url.URL {
+	file := feedPath(indexFile, format)
+	if strings.HasSuffix(page.Path, htmlSuffix) {
+		file = path.Base(feedPath(page.Path, format))
+	}
+	return page.ResolveReference(&url.URL{Path: file})
+}
+
+// FeedFormat is a format in which the feed is written.
+type FeedFormat string
+
+const (
+	// FeedFormatJSON is JSON Feed (`index.json`), the default.
+	FeedFormatJSON FeedFormat = "json"
+
+	// FeedFormatRSS is RSS 2.0 (`index.rss`).
+	FeedFormatRSS FeedFormat = "rss"
+
+	// FeedFormatAtom is Atom (`index.atom`).
+	FeedFormatAtom FeedFormat = "atom"
+)
+
+func (format FeedFormat) suffix() string { return "." + string(format) }
+
+// ContentType returns the format's MIME type.
+func (format FeedFormat) ContentType() string {
+	switch format {
+	case FeedFormatRSS:
+		return "application/rss+xml"
+	case FeedFormatAtom:
+		return "application/atom+xml"
+	default:
+		return "application/feed+json"
+	}
+}
+
+// ParseFeedFormats parses the list of feed formats, which defaults to just
+// JSON Feed.
+func ParseFeedFormats(formats []string) ([]FeedFormat, error) {
+	if len(formats) < 1 {
+		return []FeedFormat{FeedFormatJSON}, nil
+	}
+	parsed := make([]FeedFormat, 0, len(formats))
+	for _, s := range formats {
+		switch format := FeedFormat(s); format {
+		case FeedFormatJSON, FeedFormatRSS, FeedFormatAtom:
+			if !slices.Contains(parsed, format) {
+				parsed = append(parsed, format)
+			}
+		default:
+			return nil, fmt.Errorf(
+				"invalid feed format `%s`: expected `%s`, `%s`, or `%s`",
+				s,
+				FeedFormatJSON,
+				FeedFormatRSS,
+				FeedFormatAtom,
+			)
+		}
+	}
+	return parsed, nil
+}
+
+// FeedOrder determines the order of the feed's entries.
+type FeedOrder string
+
+const (
+	// FeedOrderPublished orders entries by publication date, mirroring the
+	// main index.
+	FeedOrderPublished FeedOrder = "published"
+
+	// FeedOrderUpdated orders entries by the date they were last updated
+	// (falling back to the publication date), so revised posts resurface in
+	// feed readers.
+	FeedOrderUpdated FeedOrder = "updated"
+)
+
+func ParseFeedOrder(s string) (FeedOrder, error) {
+	switch order := FeedOrder(s); order {
+	case "":
+		return FeedOrderPublished, nil
+	case FeedOrderPublished, FeedOrderUpdated:
+		return order, nil
+	default:
+		return "", fmt.Errorf(
+			"invalid feed order `%s`: expected `%s` or `%s`",
+			s,
+			FeedOrderPublished,
+			FeedOrderUpdated,
+		)
+	}
+}
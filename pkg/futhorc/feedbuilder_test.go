@@ -0,0 +1,653 @@
+package futhorc
+
+import (
+	"encoding/json"
+	"encoding/xml"
+	"fmt"
+	"html/template"
+	"maps"
+	"net/url"
+	"slices"
+	"strings"
+	"testing"
+	"time"
+
+	"futhorc/pkg/markdown"
+
+	"github.com/go-git/go-billy/v5"
+	"github.com/go-git/go-billy/v5/util"
+)
+
+func TestFeedCategories(t *testing.T) {
+	a := "---\nTitle: A\nDate: 2024-01-02\nTags: [wasm, rust]\n---\n"
+	b := "---\nTitle: B\nDate: 2024-01-01\nTags: [rust]\n---\n"
+	output, err := buildProject(writeProject(t, testTheme, map[string]string{
+		"futhorc.jsonc": `{"feeds": ["atom", "rss"]}`,
+		"posts/a.md":    a,
+		"posts/b.md":    b,
+	}))
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	type category struct{ name, url string }
+	var (
+		rust = category{"rust", "https://example.com/rust/index.html"}
+		wasm = category{"wasm", "https://example.com/wasm/index.html"}
+
+		// the categories of the feed, and of its entries in order
+		wantFeed    = []category{rust, wasm}
+		wantEntries = [][]category{{rust, wasm}, {rust}}
+	)
+	check := func(format string, feed []category, entries [][]category) {
+		if !slices.Equal(feed, wantFeed) {
+			t.Errorf("%s: wanted %v; found %v", format, wantFeed, feed)
+		}
+		if len(entries) != len(wantEntries) {
+			t.Fatalf("%s: wanted 2 entries; found %d", format, len(entries))
+		}
+		for i, want := range wantEntries {
+			if !slices.Equal(entries[i], want) {
+				t.Errorf(
+					"%s: entry %d: wanted categories %v; found %v",
+					format,
+					i,
+					want,
+					entries[i],
+				)
+			}
+		}
+	}
+
+	type atomCategory struct {
+		Term   string `xml:"term,attr"`
+		Scheme string `xml:"scheme,attr"`
+	}
+	var atom struct {
+		Categories []atomCategory `xml:"category"`
+		Entries    []struct {
+			Categories []atomCategory `xml:"category"`
+		} `xml:"entry"`
+	}
+	readXML(t, output, "index.atom", &atom)
+	atomCategories := func(categories []atomCategory) []category {
+		out := make([]category, len(categories))
+		for i, c := range categories {
+			out[i] = category{c.Term, c.Scheme}
+		}
+		return out
+	}
+	var atomEntries [][]category
+	for _, entry := range atom.Entries {
+		atomEntries = append(atomEntries, atomCategories(entry.Categories))
+	}
+	check("atom", atomCategories(atom.Categories), atomEntries)
+
+	type rssCategory struct {
+		Domain string `xml:"domain,attr"`
+		Name   string `xml:",chardata"`
+	}
+	var rss struct {
+		Channel struct {
+			Categories []rssCategory `xml:"category"`
+			Items      []struct {
+				Categories []rssCategory `xml:"category"`
+			} `xml:"item"`
+		} `xml:"channel"`
+	}
+	readXML(t, output, "index.rss", &rss)
+	rssCategories := func(categories []rssCategory) []category {
+		out := make([]category, len(categories))
+		for i, c := range categories {
+			out[i] = category{c.Name, c.Domain}
+		}
+		return out
+	}
+	var rssItems [][]category
+	for _, item := range rss.Channel.Items {
+		rssItems = append(rssItems, rssCategories(item.Categories))
+	}
+	check("rss", rssCategories(rss.Channel.Categories), rssItems)
+}
+
+func TestFeedItemAuthors(t *testing.T) {
+	output, err := buildProject(writeProject(t, testTheme, map[string]string{
+		"posts/a.md": "---\nTitle: A\nDate: 2024-01-02\nAuthor: Ann\n---\n",
+		"posts/b.md": "---\nTitle: B\nDate: 2024-01-01\n---\n",
+	}))
+	if err != nil {
+		t.Fatal(err)
+	}
+	data, err := util.ReadFile(output, "index.json")
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	type author struct {
+		Name string `json:"name"`
+	}
+	var feed struct {
+		Items []struct {
+			Title   string    `json:"title"`
+			Author  *author   `json:"author"`
+			Authors []*author `json:"authors"`
+		} `json:"items"`
+	}
+	if err := json.Unmarshal(data, &feed); err != nil {
+		t.Fatal(err)
+	}
+	if len(feed.Items) != 2 {
+		t.Fatalf("wanted 2 items; found %d", len(feed.Items))
+	}
+
+	// the post with an author has both the JSON Feed 1.0 `author` and the
+	// 1.1 `authors`; the other has neither rather than an empty author
+	a, b := feed.Items[0], feed.Items[1]
+	if a.Author == nil || a.Author.Name != "Ann" {
+		t.Errorf("`%s`: wanted the author `Ann`; found %v", a.Title, a.Author)
+	}
+	if len(a.Authors) != 1 || a.Authors[0].Name != "Ann" {
+		t.Errorf("`%s`: wanted the authors [Ann]; found %v", a.Title, a.Authors)
+	}
+	if b.Author != nil || b.Authors != nil {
+		t.Errorf(
+			"`%s`: wanted no authors; found %v and %v",
+			b.Title,
+			b.Author,
+			b.Authors,
+		)
+	}
+}
+
+func TestFeedOrder(t *testing.T) {
+	// the old post was revised after the recent posts were published
+	files := map[string]string{
+		"posts/old.md": "---\nTitle: Old\nDate: 2023-01-01\n" +
+			"Updated: 2024-06-01\n---\n",
+		"posts/new.md":   "---\nTitle: New\nDate: 2024-03-01\n---\n",
+		"posts/newer.md": "---\nTitle: Newer\nDate: 2024-04-01\n---\n",
+	}
+	for _, tc := range []struct {
+		order string
+
+		// entries are the titles and publication dates of the feed's two
+		// entries in order
+		entries [][2]string
+		updated string
+	}{
+		{
+			order: "published",
+			entries: [][2]string{
+				{"Newer", "2024-04-01T00:00:00Z"},
+				{"New", "2024-03-01T00:00:00Z"},
+			},
+			updated: "2024-04-01T00:00:00Z",
+		},
+		{
+			order: "updated",
+			entries: [][2]string{
+				{"Old", "2023-01-01T00:00:00Z"},
+				{"Newer", "2024-04-01T00:00:00Z"},
+			},
+			updated: "2024-06-01T00:00:00Z",
+		},
+	} {
+		t.Run(tc.order, func(t *testing.T) {
+			dir := writeProject(t, testTheme, files)
+			writeFiles(t, dir, map[string]string{
+				"futhorc.jsonc": `{
+					"feeds": ["atom"],
+					"feedEntryLimit": 2,
+					"feedOrder": "` + tc.order + `"
+				}`,
+			})
+			output, err := buildProject(dir)
+			if err != nil {
+				t.Fatal(err)
+			}
+
+			var atom struct {
+				Updated string `xml:"updated"`
+				Entries []struct {
+					Title     string `xml:"title"`
+					Published string `xml:"published"`
+				} `xml:"entry"`
+			}
+			readXML(t, output, "index.atom", &atom)
+			var entries [][2]string
+			for _, entry := range atom.Entries {
+				entries = append(
+					entries,
+					[2]string{entry.Title, entry.Published},
+				)
+			}
+			if !slices.Equal(entries, tc.entries) {
+				t.Errorf("wanted the entries %v; found %v", tc.entries, entries)
+			}
+			if atom.Updated != tc.updated {
+				t.Errorf(
+					"wanted the feed updated at %s; found %s",
+					tc.updated,
+					atom.Updated,
+				)
+			}
+		})
+	}
+}
+
+func TestFeedOptOut(t *testing.T) {
+	// the roundup is the newest post, so it would take the feed's only slot
+	// if it weren't excluded
+	output, err := buildProject(writeProject(t, testTheme, map[string]string{
+		"futhorc.jsonc": `{
+			"feeds": ["json", "atom", "rss"],
+			"feedEntryLimit": 1
+		}`,
+		"theme/post.html": "in feed: {{ .Content.InFeed }}",
+		"posts/a.md":      "---\nTitle: A\nDate: 2024-01-01\n---\n",
+		"posts/roundup.md": "---\nTitle: Roundup\nDate: 2024-01-02\n" +
+			"Feed: false\n---\n",
+	}))
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	const roundup = "https://example.com/posts/roundup.html"
+	for _, tc := range []struct {
+		path      string
+		mentioned bool
+	}{
+		{path: "index.html", mentioned: true},
+		{path: "index.json"},
+		{path: "index.atom"},
+		{path: "index.rss"},
+	} {
+		data, err := util.ReadFile(output, tc.path)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if strings.Contains(string(data), roundup) != tc.mentioned {
+			t.Errorf(
+				"`%s`: wanted mentions of the roundup %t; found %q",
+				tc.path,
+				tc.mentioned,
+				data,
+			)
+		}
+		if tc.path != "index.html" &&
+			!strings.Contains(string(data), "posts/a.html") {
+			t.Errorf("`%s`: wanted the other post in the feed", tc.path)
+		}
+	}
+
+	for p, wanted := range map[string]string{
+		"posts/roundup.html": "in feed: false",
+		"posts/a.html":       "in feed: true",
+	} {
+		data, err := util.ReadFile(output, p)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if string(data) != wanted {
+			t.Errorf("`%s`: wanted %q; found %q", p, wanted, data)
+		}
+	}
+}
+
+func TestFeedItemContent(t *testing.T) {
+	output, err := buildProject(writeProject(t, testTheme, map[string]string{
+		"futhorc.jsonc": `{"feeds": ["json", "atom", "rss"]}`,
+		"posts/a.md": "---\nTitle: A\nDate: 2024-01-02\n---\n\n" +
+			"Above the fold.\n\n<!-- more -->\n\n" +
+			"See [b](b.md), *x* < y & z[^1].\n\n[^1]: A footnote.\n",
+		"posts/b.md": "---\nTitle: B\nDate: 2024-01-01\n---\n",
+	}))
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	// every feed carries the post page's body, with its footnote links made
+	// absolute, and the snippet as the summary
+	page, err := util.ReadFile(output, "posts/a.html")
+	if err != nil {
+		t.Fatal(err)
+	}
+	postURL, err := url.Parse(goldenSiteRoot + "posts/a.html")
+	if err != nil {
+		t.Fatal(err)
+	}
+	wanted := string(markdown.AbsoluteFootnotes(template.HTML(page), postURL))
+	for _, fragment := range []string{
+		`href="` + goldenSiteRoot + `posts/b.html"`,
+		`href="` + goldenSiteRoot + `posts/a.html#fn:1"`,
+		"<em>x</em> &lt; y &amp; z",
+	} {
+		if !strings.Contains(wanted, fragment) {
+			t.Fatalf("wanted %q in the body %q", fragment, wanted)
+		}
+	}
+
+	var atom struct {
+		Entries []struct {
+			Summary string `xml:"summary"`
+			Content struct {
+				Type string `xml:"type,attr"`
+				Body string `xml:",chardata"`
+			} `xml:"content"`
+		} `xml:"entry"`
+	}
+	readXML(t, output, "index.atom", &atom)
+	var rss struct {
+		Channel struct {
+			Items []struct {
+				Description string `xml:"description"`
+				Content     string `xml:"encoded"`
+			} `xml:"item"`
+		} `xml:"channel"`
+	}
+	readXML(t, output, "index.rss", &rss)
+	data, err := util.ReadFile(output, "index.json")
+	if err != nil {
+		t.Fatal(err)
+	}
+	var jsonFeed struct {
+		Items []struct {
+			Summary string `json:"summary"`
+			Content string `json:"content_html"`
+		} `json:"items"`
+	}
+	if err := json.Unmarshal(data, &jsonFeed); err != nil {
+		t.Fatal(err)
+	}
+	if len(atom.Entries) < 1 ||
+		len(rss.Channel.Items) < 1 ||
+		len(jsonFeed.Items) < 1 {
+		t.Fatal("wanted an item in each feed")
+	}
+
+	if found := atom.Entries[0].Content.Type; found != "html" {
+		t.Errorf("atom: wanted the content type `html`; found `%s`", found)
+	}
+	for _, feed := range []struct{ name, summary, content string }{
+		{"atom", atom.Entries[0].Summary, atom.Entries[0].Content.Body},
+		{
+			"rss",
+			rss.Channel.Items[0].Description,
+			rss.Channel.Items[0].Content,
+		},
+		{"json", jsonFeed.Items[0].Summary, jsonFeed.Items[0].Content},
+	} {
+		if feed.content != wanted {
+			t.Errorf(
+				"%s: wanted the content %q; found %q",
+				feed.name,
+				wanted,
+				feed.content,
+			)
+		}
+		if !strings.Contains(feed.summary, "Above the fold.") ||
+			strings.Contains(feed.summary, "See") {
+			t.Errorf(
+				"%s: wanted the snippet as the summary; found %q",
+				feed.name,
+				feed.summary,
+			)
+		}
+	}
+}
+
+func TestRSSFeed(t *testing.T) {
+	files := map[string]string{
+		"posts/a.md": "---\nTitle: A\nDate: 2024-01-01\nTags: [go]\n---\n",
+		"posts/b.md": "---\nTitle: B\nDate: 2024-01-02\n---\n",
+		"posts/c.md": "---\nTitle: C\nDate: 2024-01-03\nTags: [go]\n---\n\n" +
+			"Hello.\n",
+	}
+
+	// only the JSON feed is written by default
+	output, err := buildProject(writeProject(t, testTheme, files))
+	if err != nil {
+		t.Fatal(err)
+	}
+	if _, err := output.Stat("index.rss"); err == nil {
+		t.Error("wanted no RSS feed by default")
+	}
+
+	files["futhorc.jsonc"] = `{
+		"title": "My Blog",
+		"description": "Musings",
+		"feeds": ["json", "rss"]
+	}`
+	output, err = buildProject(writeProject(t, testTheme, files))
+	if err != nil {
+		t.Fatal(err)
+	}
+	for _, tc := range []struct {
+		path  string
+		link  string
+		posts []string
+	}{
+		{
+			path:  "index.rss",
+			link:  "index.html",
+			posts: []string{"c", "b", "a"},
+		},
+		{
+			path:  "go/index.rss",
+			link:  "go/index.html",
+			posts: []string{"c", "a"},
+		},
+	} {
+		var rss struct {
+			Channel struct {
+				Title       string `xml:"title"`
+				Link        string `xml:"link"`
+				Description string `xml:"description"`
+				Items       []struct {
+					GUID    string `xml:"guid"`
+					Link    string `xml:"link"`
+					PubDate string `xml:"pubDate"`
+					Content string `xml:"encoded"`
+				} `xml:"item"`
+			} `xml:"channel"`
+		}
+		readXML(t, output, tc.path, &rss)
+		channel := rss.Channel
+		if channel.Title != "My Blog" ||
+			channel.Description != "Musings" ||
+			channel.Link != goldenSiteRoot+tc.link {
+			t.Errorf(
+				"`%s`: wanted the channel `My Blog` (Musings) at `%s`; "+
+					"found `%s` (%s) at `%s`",
+				tc.path,
+				goldenSiteRoot+tc.link,
+				channel.Title,
+				channel.Description,
+				channel.Link,
+			)
+		}
+
+		// each item's GUID is its post's URL
+		var guids []string
+		for _, item := range channel.Items {
+			guids = append(guids, item.GUID)
+			if item.Link != item.GUID {
+				t.Errorf(
+					"`%s`: wanted the link `%s`; found `%s`",
+					tc.path,
+					item.GUID,
+					item.Link,
+				)
+			}
+			if _, err := time.Parse(time.RFC1123Z, item.PubDate); err != nil {
+				t.Errorf("`%s`: wanted an RFC 822 date: %v", tc.path, err)
+			}
+		}
+		var wanted []string
+		for _, name := range tc.posts {
+			wanted = append(wanted, goldenSiteRoot+"posts/"+name+".html")
+		}
+		if !slices.Equal(guids, wanted) {
+			t.Errorf(
+				"`%s`: wanted the GUIDs %v; found %v",
+				tc.path,
+				wanted,
+				guids,
+			)
+		}
+		if len(channel.Items) > 0 &&
+			!strings.Contains(channel.Items[0].Content, "Hello.") {
+			t.Errorf(
+				"`%s`: wanted the full content; found %q",
+				tc.path,
+				channel.Items[0].Content,
+			)
+		}
+	}
+}
+
+func TestFeedEntryLimit(t *testing.T) {
+	// five posts, all tagged `go`
+	files := map[string]string{}
+	for i := range 5 {
+		files[fmt.Sprintf("posts/%d.md", i)] = fmt.Sprintf(
+			"---\nTitle: %d\nDate: 2024-01-%02d\nTags: [go]\n---\n",
+			i,
+			i+1,
+		)
+	}
+	for _, tc := range []struct {
+		name   string
+		limit  int
+		wanted []string
+	}{
+		{name: "unlimited", wanted: []string{"4", "3", "2", "1", "0"}},
+		{name: "smaller", limit: 3, wanted: []string{"4", "3", "2"}},
+		{name: "equal", limit: 5, wanted: []string{"4", "3", "2", "1", "0"}},
+		{name: "larger", limit: 8, wanted: []string{"4", "3", "2", "1", "0"}},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			files := maps.Clone(files)
+			files["futhorc.jsonc"] = fmt.Sprintf(
+				`{"feedEntryLimit": %d}`,
+				tc.limit,
+			)
+			output, err := buildProject(writeProject(t, testTheme, files))
+			if err != nil {
+				t.Fatal(err)
+			}
+
+			// the limit applies to the main feed and to the tag's alike
+			for _, p := range []string{"index.json", "go/index.json"} {
+				data, err := util.ReadFile(output, p)
+				if err != nil {
+					t.Fatal(err)
+				}
+				var feed struct {
+					Items []struct {
+						Title string `json:"title"`
+					} `json:"items"`
+				}
+				if err := json.Unmarshal(data, &feed); err != nil {
+					t.Fatal(err)
+				}
+				var titles []string
+				for _, item := range feed.Items {
+					titles = append(titles, item.Title)
+				}
+				if !slices.Equal(titles, tc.wanted) {
+					t.Errorf(
+						"`%s`: wanted the items %v; found %v",
+						p,
+						tc.wanted,
+						titles,
+					)
+				}
+			}
+
+			// the index itself isn't limited
+			data, err := util.ReadFile(output, "index.html")
+			if err != nil {
+				t.Fatal(err)
+			}
+			if wanted := postURLs(
+				"4", "3", "2", "1", "0",
+			); string(data) != wanted {
+				t.Errorf("wanted the index %q; found %q", wanted, data)
+			}
+		})
+	}
+
+	files["futhorc.jsonc"] = `{"feedEntryLimit": -1}`
+	if _, err := LoadPipeline(
+		writeProject(t, testTheme, files),
+		goldenSiteRoot,
+	); err == nil || !strings.Contains(err.Error(), "feed entry limit") {
+		t.Errorf("wanted an error for a negative limit; found `%v`", err)
+	}
+}
+
+func TestFeedUpdated(t *testing.T) {
+	output, err := buildProject(writeProject(t, testTheme, map[string]string{
+		"futhorc.jsonc": `{"feeds": ["atom"]}`,
+		"theme/post.html": "{{ .Content.Date }} " +
+			"{{ with .Content.Updated }}{{ . }}{{ else }}none{{ end }}",
+		"posts/revised.md": "---\nTitle: Revised\nDate: 2024-01-01\n" +
+			"Updated: 2024-06-01\n---\n",
+		"posts/new.md": "---\nTitle: New\nDate: 2024-03-01\n---\n",
+	}))
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	for p, wanted := range map[string]string{
+		"posts/revised.html": "2024-01-01 2024-06-01",
+		"posts/new.html":     "2024-03-01 none",
+	} {
+		data, err := util.ReadFile(output, p)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if string(data) != wanted {
+			t.Errorf("`%s`: wanted %q; found %q", p, wanted, data)
+		}
+	}
+
+	// each entry is published on its `Date` and updated on its `Updated`
+	// (or its `Date`), and the feed is updated with its latest entry
+	type entry struct {
+		Title     string `xml:"title"`
+		Published string `xml:"published"`
+		Updated   string `xml:"updated"`
+	}
+	var atom struct {
+		Updated string  `xml:"updated"`
+		Entries []entry `xml:"entry"`
+	}
+	readXML(t, output, "index.atom", &atom)
+	wanted := []entry{
+		{"New", "2024-03-01T00:00:00Z", "2024-03-01T00:00:00Z"},
+		{"Revised", "2024-01-01T00:00:00Z", "2024-06-01T00:00:00Z"},
+	}
+	if !slices.Equal(atom.Entries, wanted) {
+		t.Errorf("wanted the entries %v; found %v", wanted, atom.Entries)
+	}
+	if atom.Updated != "2024-06-01T00:00:00Z" {
+		t.Errorf(
+			"wanted the feed updated at 2024-06-01; found %s",
+			atom.Updated,
+		)
+	}
+}
+
+// readXML decodes the XML file at `p` in `output` into `v`.
+func readXML(t *testing.T, output billy.Filesystem, p string, v any) {
+	t.Helper()
+	data, err := util.ReadFile(output, p)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if err := xml.Unmarshal(data, v); err != nil {
+		t.Fatalf("decoding `%s`: %v", p, err)
+	}
+}
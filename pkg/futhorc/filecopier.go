@@ -1,47 +1,202 @@
 package futhorc
 
 import (
+	"bytes"
 	"context"
 	"errors"
 	"fmt"
 	"io"
 	"io/fs"
+	"log/slog"
 	"path/filepath"
+	"sync/atomic"
 
 	"futhorc/pkg/actor"
 
 	"github.com/go-git/go-billy/v5"
+	"github.com/go-git/go-billy/v5/util"
 )
 
-func FileCopier(dst billy.Filesystem, src fs.FS, prefix string) actor.InputCallback[string] {
-	return func(ctx context.Context, path string) (err error) {
-		var df billy.File
-		var sf fs.File
-		if df, err = dst.Create(filepath.Join(prefix, path)); err != nil {
-			err = fmt.Errorf(
-				"copying file `%s`; creating destination file: %w",
+// FileCopier copies files from `src` into `dst` under `prefix`, skipping
+// files whose destination is already up to date according to `check`. The
+// number of copied and skipped files is tallied in `stats`.
+func FileCopier(
+	dst billy.Filesystem,
+	src fs.FS,
+	prefix string,
+	check CopyCheck,
+	stats *CopyStats,
+) actor.InputCallback[string] {
+	return func(ctx context.Context, path string) error {
+		target := filepath.Join(prefix, path)
+		recordOutput(dst, target)
+		info, err := fs.Stat(src, path)
+		if err != nil {
+			return fmt.Errorf(
+				"copying file `%s`; opening source file: %w",
 				path,
 				err,
 			)
-			return
 		}
-		defer func() { err = errors.Join(err, df.Close()) }()
 
-		if sf, err = src.Open(path); err != nil {
-			err = fmt.Errorf(
-				"copying file `%s`; opening source file: %w",
-				path,
-				err,
-			)
-			return
+		skip, err := upToDate(dst, target, src, path, info, check)
+		if err != nil {
+			return fmt.Errorf("copying file `%s`: %w", path, err)
+		}
+		if skip {
+			stats.Skipped.Add(1)
+			return nil
 		}
-		defer func() { err = errors.Join(err, sf.Close()) }()
 
-		if _, err = io.Copy(df, &contextReader{ctx, sf}); err != nil {
-			err = fmt.Errorf("copying file `%s`: %w", path, err)
-			return
+		if err := copyFile(ctx, dst, target, src, path); err != nil {
+			return err
 		}
 
+		// carry over the modification time so the next build can tell that
+		// the copy is up to date
+		if change, ok := dst.(billy.Change); ok {
+			if err := change.Chtimes(
+				target,
+				info.ModTime(),
+				info.ModTime(),
+			); err != nil {
+				return fmt.Errorf("copying file `%s`: %w", path, err)
+			}
+		}
+		stats.Copied.Add(1)
+		return nil
+	}
+}
+
+func copyFile(
+	ctx context.Context,
+	dst billy.Filesystem,
+	target string,
+	src fs.FS,
+	path string,
+) (err error) {
+	var df billy.File
+	var sf fs.File
+	if df, err = createFile(dst, target); err != nil {
+		err = fmt.Errorf(
+			"copying file `%s`; creating destination file: %w",
+			path,
+			err,
+		)
 		return
 	}
+	defer func() { err = errors.Join(err, df.Close()) }()
+
+	if sf, err = src.Open(path); err != nil {
+		err = fmt.Errorf(
+			"copying file `%s`; opening source file: %w",
+			path,
+			err,
+		)
+		return
+	}
+	defer func() { err = errors.Join(err, sf.Close()) }()
+
+	if _, err = io.Copy(df, &contextReader{ctx, sf}); err != nil {
+		err = fmt.Errorf("copying file `%s`: %w", path, err)
+		return
+	}
+
+	return
+}
+
+// upToDate reports whether the destination file already matches the source
+// file. Files of different sizes never match.
+func upToDate(
+	dst billy.Filesystem,
+	target string,
+	src fs.FS,
+	path string,
+	info fs.FileInfo,
+	check CopyCheck,
+) (bool, error) {
+	if check == CopyCheckNone {
+		return false, nil
+	}
+	dstInfo, err := dst.Stat(target)
+	if err != nil {
+		if errors.Is(err, fs.ErrNotExist) {
+			return false, nil
+		}
+		return false, err
+	}
+	if dstInfo.Size() != info.Size() {
+		return false, nil
+	}
+	if check != CopyCheckHash {
+		return dstInfo.ModTime().Equal(info.ModTime()), nil
+	}
+
+	dstData, err := util.ReadFile(dst, target)
+	if err != nil {
+		return false, err
+	}
+	srcData, err := fs.ReadFile(src, path)
+	if err != nil {
+		return false, err
+	}
+	return bytes.Equal(dstData, srcData), nil
+}
+
+// CopyStats tallies the files processed by a `FileCopier`.
+type CopyStats struct {
+	Copied  atomic.Int64
+	Skipped atomic.Int64
+}
+
+// total returns the number of files processed, whether copied or skipped.
+func (stats *CopyStats) total() int {
+	return int(stats.Copied.Load() + stats.Skipped.Load())
+}
+
+// Report returns an actor finalizer which logs the tallies.
+func (stats *CopyStats) Report(name string) func(context.Context) error {
+	return func(context.Context) error {
+		slog.Info(
+			"copied files",
+			"copier", name,
+			"copied", stats.Copied.Load(),
+			"skipped", stats.Skipped.Load(),
+		)
+		return nil
+	}
+}
+
+// CopyCheck selects how a `FileCopier` decides that a destination file is
+// already up to date.
+type CopyCheck string
+
+const (
+	// CopyCheckModTime skips files whose destination has the same size and
+	// modification time as the source.
+	CopyCheckModTime CopyCheck = "mtime"
+
+	// CopyCheckHash skips files whose destination has the same contents as
+	// the source.
+	CopyCheckHash CopyCheck = "hash"
+
+	// CopyCheckNone always copies.
+	CopyCheckNone CopyCheck = "none"
+)
+
+func ParseCopyCheck(s string) (CopyCheck, error) {
+	switch check := CopyCheck(s); check {
+	case "":
+		return CopyCheckModTime, nil
+	case CopyCheckModTime, CopyCheckHash, CopyCheckNone:
+		return check, nil
+	default:
+		return "", fmt.Errorf(
+			"invalid copy check `%s`: expected one of `%s`, `%s`, or `%s`",
+			s,
+			CopyCheckModTime,
+			CopyCheckHash,
+			CopyCheckNone,
+		)
+	}
 }
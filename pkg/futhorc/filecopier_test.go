@@ -0,0 +1,131 @@
+package futhorc
+
+import (
+	"context"
+	"fmt"
+	"os"
+	"path/filepath"
+	"testing"
+	"time"
+
+	"github.com/go-git/go-billy/v5/osfs"
+	"github.com/go-git/go-billy/v5/util"
+)
+
+func TestFileCopierSkipsUpToDate(t *testing.T) {
+	paths := []string{"a.txt", "b.txt", "fonts/woff2/c.woff2"}
+	for _, tc := range []struct {
+		check CopyCheck
+
+		// copied is the number of files copied by the second copy, after
+		// `a.txt` is modified
+		copied int64
+	}{
+		{check: CopyCheckModTime, copied: 1},
+		{check: CopyCheckHash, copied: 1},
+		{check: CopyCheckNone, copied: 3},
+	} {
+		t.Run(string(tc.check), func(t *testing.T) {
+			src, dst := t.TempDir(), t.TempDir()
+			writeFiles(t, src, map[string]string{
+				"a.txt":               "a",
+				"b.txt":               "b",
+				"fonts/woff2/c.woff2": "c",
+			})
+			output := osfs.New(dst)
+			copyAll := func() *CopyStats {
+				var stats CopyStats
+				copier := FileCopier(
+					output,
+					os.DirFS(src),
+					"static",
+					tc.check,
+					&stats,
+				)
+				for _, p := range paths {
+					if err := copier(context.Background(), p); err != nil {
+						t.Fatal(err)
+					}
+				}
+				return &stats
+			}
+
+			if stats := copyAll(); stats.Copied.Load() != 3 {
+				t.Fatalf("wanted 3 files copied; found %d", stats.Copied.Load())
+			}
+
+			// the modified file is the same size, so only its contents (and
+			// modification time) tell it apart
+			modified := filepath.Join(src, "a.txt")
+			if err := os.WriteFile(modified, []byte("A"), 0644); err != nil {
+				t.Fatal(err)
+			}
+			later := time.Now().Add(time.Hour)
+			if err := os.Chtimes(modified, later, later); err != nil {
+				t.Fatal(err)
+			}
+			stats := copyAll()
+			if stats.Copied.Load() != tc.copied ||
+				stats.Skipped.Load() != 3-tc.copied {
+				t.Errorf(
+					"wanted %d copied and %d skipped; found %d and %d",
+					tc.copied,
+					3-tc.copied,
+					stats.Copied.Load(),
+					stats.Skipped.Load(),
+				)
+			}
+
+			for p, wanted := range map[string]string{
+				"static/a.txt":               "A",
+				"static/b.txt":               "b",
+				"static/fonts/woff2/c.woff2": "c",
+			} {
+				data, err := util.ReadFile(output, p)
+				if err != nil {
+					t.Fatal(err)
+				}
+				if string(data) != wanted {
+					t.Errorf("`%s`: wanted %q; found %q", p, wanted, data)
+				}
+			}
+		})
+	}
+}
+
+func BenchmarkFileCopierUpToDate(b *testing.B) {
+	// a synthetic tree of 5000 small files, 100 per directory
+	src, dst := b.TempDir(), b.TempDir()
+	paths := make([]string, 5000)
+	for i := range paths {
+		paths[i] = fmt.Sprintf("%02d/%04d.svg", i/100, i)
+		target := filepath.Join(src, filepath.FromSlash(paths[i]))
+		if err := os.MkdirAll(filepath.Dir(target), 0755); err != nil {
+			b.Fatal(err)
+		}
+		if err := os.WriteFile(target, []byte("<svg/>"), 0644); err != nil {
+			b.Fatal(err)
+		}
+	}
+	var stats CopyStats
+	copier := FileCopier(
+		osfs.New(dst),
+		os.DirFS(src),
+		"static",
+		CopyCheckModTime,
+		&stats,
+	)
+	copyAll := func() {
+		for _, p := range paths {
+			if err := copier(context.Background(), p); err != nil {
+				b.Fatal(err)
+			}
+		}
+	}
+	copyAll()
+
+	b.ResetTimer()
+	for range b.N {
+		copyAll()
+	}
+}
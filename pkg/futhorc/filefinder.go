@@ -2,9 +2,11 @@ package futhorc
 
 import (
 	"context"
+	"errors"
 	"futhorc/pkg/actor"
 	"io/fs"
-	"path/filepath"
+	"log/slog"
+	"path"
 	"strings"
 )
 
@@ -22,11 +24,18 @@ func FileFinder(root fs.FS, extension string) actor.OutputCallback[string] {
 			var entry fs.DirEntry
 			for len(entries) > 0 {
 				entry, entries = entries[0], entries[1:]
-				path := filepath.Join(dir, entry.Name())
+				// `fs.FS` paths are always slash-separated (even on
+				// Windows), so the found paths are too
+				p := path.Join(dir, entry.Name())
 				if entry.IsDir() {
-					dirs = append(dirs, path)
+					dirs = append(dirs, p)
+				} else if entry.Type()&fs.ModeSymlink != 0 &&
+					isDir(root, p) {
+					// symlinked directories aren't followed since they may
+					// form cycles
+					slog.Warn("skipping symlinked directory", "path", p)
 				} else if strings.HasSuffix(entry.Name(), extension) {
-					return path, nil
+					return p, nil
 				}
 			}
 
@@ -44,8 +53,19 @@ func FileFinder(root fs.FS, extension string) actor.OutputCallback[string] {
 
 			var err error
 			if entries, err = fs.ReadDir(root, dir); err != nil {
+				// a missing root directory (e.g., a project without any
+				// post assets) simply has no files
+				if dir == "." && errors.Is(err, fs.ErrNotExist) {
+					return "", actor.ErrStop
+				}
 				return "", err
 			}
 		}
 	}
 }
+
+// isDir reports whether `path` resolves to a directory.
+func isDir(root fs.FS, path string) bool {
+	info, err := fs.Stat(root, path)
+	return err == nil && info.IsDir()
+}
@@ -0,0 +1,54 @@
+package futhorc
+
+import (
+	"maps"
+	"os"
+	"path"
+	"path/filepath"
+	"testing"
+)
+
+func TestThemeAssetTree(t *testing.T) {
+	assets := map[string]string{
+		"style.css":                  "body {}",
+		"fonts/woff2/foo.woff2":      "foo",
+		"fonts/woff2/bar.woff2":      "bar",
+		"fonts/ttf/foo.ttf":          "foo",
+		"images/icons/small/x.svg":   "<svg/>",
+		"images/icons/large/x.svg":   "<svg></svg>",
+		"images/icons/large/y/z.svg": "z",
+	}
+	theme := maps.Clone(testTheme)
+	delete(theme, "theme/assets/style.css")
+	for p, contents := range assets {
+		theme["theme/assets/"+p] = contents
+	}
+	dir := writeProject(t, theme, map[string]string{
+		"posts/a.md": "---\nTitle: A\nDate: 2024-01-01\n---\n",
+	})
+
+	// a symlink back up the tree isn't followed
+	if err := os.Symlink(
+		filepath.Join(dir, "theme", "assets"),
+		filepath.Join(dir, "theme", "assets", "fonts", "loop"),
+	); err != nil {
+		t.Skipf("creating a symlink: %v", err)
+	}
+
+	wanted := map[string]string{}
+	for p, contents := range assets {
+		wanted[p] = contents
+		for d := path.Dir(p); d != "."; d = path.Dir(d) {
+			wanted[d+"/"] = ""
+		}
+	}
+
+	// the second build copies into the existing directories
+	for build := range 2 {
+		output := buildOnDisk(t, dir)
+		found := readTree(t, filepath.Join(output, "assets", "theme"))
+		if !maps.Equal(found, wanted) {
+			t.Errorf("build %d: wanted %v; found %v", build+1, wanted, found)
+		}
+	}
+}
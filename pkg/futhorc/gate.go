@@ -0,0 +1,83 @@
+package futhorc
+
+import (
+	"context"
+	"log/slog"
+)
+
+// Gate holds back the items from `Input` until `Open` is closed, buffering
+// them in the meantime, so that the actors which write them to the output
+// directory don't start before the output plan has been checked (see
+// `CheckOutputConflicts`). If the check fails, the pipeline is canceled and
+// nothing is written.
+type Gate[T any] struct {
+	Name   string
+	Input  <-chan T
+	Output chan T
+	Open   <-chan struct{}
+
+	held   []T
+	err    error
+	filled chan struct{}
+}
+
+// NewGate creates a gate which holds back the items from `input` until `open`
+// is closed.
+func NewGate[T any](
+	name string,
+	input <-chan T,
+	open <-chan struct{},
+) (gate Gate[T]) {
+	gate.Name = name
+	gate.Input = input
+	gate.Output = make(chan T)
+	gate.Open = open
+	gate.filled = make(chan struct{})
+	return
+}
+
+func (gate *Gate[T]) Run(ctx context.Context) error {
+	slog.Debug("starting actor", "name", gate.Name)
+	defer slog.Debug("closing actor", "name", gate.Name)
+	defer close(gate.Output)
+
+	if err := gate.fill(ctx); err != nil {
+		return err
+	}
+
+	select {
+	case <-ctx.Done():
+		return ctx.Err()
+	case <-gate.Open:
+	}
+	for _, elt := range gate.held {
+		if err := send(ctx, gate.Output, elt); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+func (gate *Gate[T]) fill(ctx context.Context) error {
+	defer close(gate.filled)
+	for {
+		select {
+		case <-ctx.Done():
+			gate.err = ctx.Err()
+			return gate.err
+		case elt, ok := <-gate.Input:
+			if !ok {
+				return nil
+			}
+			gate.held = append(gate.held, elt)
+		}
+	}
+}
+
+// Held waits for `Input` to be closed and returns every item it received,
+// e.g., so the items can be checked before the gate is opened. It fails if
+// the gate stopped early because the pipeline was canceled.
+func (gate *Gate[T]) Held() ([]T, error) {
+	<-gate.filled
+	return gate.held, gate.err
+}
@@ -0,0 +1,146 @@
+package futhorc
+
+import (
+	"context"
+	"io/fs"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/go-git/go-billy/v5"
+	"github.com/go-git/go-billy/v5/memfs"
+	"github.com/go-git/go-billy/v5/osfs"
+	"github.com/go-git/go-billy/v5/util"
+)
+
+// goldenSiteRoot is the site root every golden fixture is built with, so the
+// expected output doesn't depend on where the repository is checked out.
+const goldenSiteRoot = "https://example.com/"
+
+// TestGolden builds each fixture project under `testdata/golden` and compares
+// its output with the fixture's `expected` directory. Set `UPDATE_GOLDEN=1` to
+// record the current output as the expected output instead.
+func TestGolden(t *testing.T) {
+	fixtures, err := os.ReadDir(filepath.Join("testdata", "golden"))
+	if err != nil {
+		t.Fatal(err)
+	}
+	for _, fixture := range fixtures {
+		if !fixture.IsDir() {
+			continue
+		}
+		t.Run(fixture.Name(), func(t *testing.T) {
+			dir := filepath.Join("testdata", "golden", fixture.Name())
+			output := buildFixture(t, dir)
+
+			expected := filepath.Join(dir, "expected")
+			if os.Getenv("UPDATE_GOLDEN") != "" {
+				recordGolden(t, expected, output)
+				return
+			}
+			if _, err := os.Stat(expected); os.IsNotExist(err) {
+				t.Fatalf(
+					"no expected output; run with `UPDATE_GOLDEN=1` to "+
+						"record it in `%s`",
+					expected,
+				)
+			}
+
+			checkGolden(t, osfs.New(expected), output)
+		})
+	}
+}
+
+// buildFixture builds the project in `dir` into an in-memory output
+// directory.
+func buildFixture(t *testing.T, dir string) billy.Filesystem {
+	t.Helper()
+	output, err := buildProject(dir)
+	if err != nil {
+		t.Fatalf("building `%s`: %v", dir, err)
+	}
+	return output
+}
+
+// buildProject builds the project in `dir` into an in-memory output
+// directory, which it returns along with any error from the build.
+func buildProject(dir string) (billy.Filesystem, error) {
+	pipeline, err := LoadPipeline(dir, goldenSiteRoot)
+	if err != nil {
+		return nil, err
+	}
+	output := memfs.New()
+	pipeline.OutputPath = ""
+	pipeline.OutputDirectory = output
+	return output, pipeline.Run(context.Background())
+}
+
+// buildOnDisk builds the project in `dir` into its `_output` directory, whose
+// path it returns.
+func buildOnDisk(t *testing.T, dir string) string {
+	t.Helper()
+	pipeline, err := LoadPipeline(dir, goldenSiteRoot)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if err := pipeline.Run(context.Background()); err != nil {
+		t.Fatalf("building `%s`: %v", dir, err)
+	}
+	return pipeline.OutputPath
+}
+
+// checkGolden reports each file that's missing from, added to, or different
+// in `output` compared with `expected`, with a diff of each modified file.
+func checkGolden(t *testing.T, expected, output billy.Filesystem) {
+	t.Helper()
+	diff, err := DiffOutputs(expected, output)
+	if err != nil {
+		t.Fatal(err)
+	}
+	for _, p := range diff.Added {
+		t.Errorf("unexpected output file `%s`", p)
+	}
+	for _, p := range diff.Removed {
+		t.Errorf("missing output file `%s`", p)
+	}
+	for _, p := range diff.Modified {
+		want, err := util.ReadFile(expected, p)
+		if err != nil {
+			t.Fatal(err)
+		}
+		got, err := util.ReadFile(output, p)
+		if err != nil {
+			t.Fatal(err)
+		}
+		t.Errorf("output file `%s` differs:\n%s", p, UnifiedDiff(p, want, got))
+	}
+}
+
+// recordGolden replaces the contents of `expected` with `output`.
+func recordGolden(t *testing.T, expected string, output billy.Filesystem) {
+	t.Helper()
+	if err := os.RemoveAll(expected); err != nil {
+		t.Fatal(err)
+	}
+	if err := util.Walk(
+		output,
+		"/",
+		func(p string, info fs.FileInfo, err error) error {
+			if err != nil || info.IsDir() {
+				return err
+			}
+			data, err := util.ReadFile(output, p)
+			if err != nil {
+				return err
+			}
+			target := filepath.Join(expected, filepath.FromSlash(p))
+			if err := os.MkdirAll(filepath.Dir(target), 0755); err != nil {
+				return err
+			}
+			return os.WriteFile(target, data, 0644)
+		},
+	); err != nil {
+		t.Fatal(err)
+	}
+	t.Logf("recorded the expected output in `%s`", expected)
+}
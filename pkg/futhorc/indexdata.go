@@ -0,0 +1,131 @@
+package futhorc
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"strings"
+	"unsafe"
+
+	"futhorc/pkg/actor"
+
+	"github.com/go-git/go-billy/v5"
+)
+
+// IndexDataWriter writes each index page's data as JSON alongside the HTML
+// page (e.g., `rust/page-001.data.json` beside `rust/page-001.html`) for
+// themes which render pages on the client. The `.data.json` suffix keeps the
+// files distinct from the main index's JSON feed pages.
+func IndexDataWriter(
+	output billy.Filesystem,
+) actor.InputCallback[*OrderedPage[IndexPage]] {
+	return func(ctx context.Context, page *OrderedPage[IndexPage]) error {
+		return writeIndexData(output, page)
+	}
+}
+
+func writeIndexData(
+	output billy.Filesystem,
+	page *OrderedPage[IndexPage],
+) error {
+	path := indexDataPath(page.Path)
+
+	// struct fields are encoded in declaration order, so the output is
+	// stable from build to build
+	data, err := json.MarshalIndent(newIndexData(page), "", "  ")
+	if err != nil {
+		return fmt.Errorf("writing data for index page `%s`: %w", path, err)
+	}
+
+	if _, err := writeFile(output, path, data); err != nil {
+		return fmt.Errorf("writing data for index page `%s`: %w", path, err)
+	}
+
+	return nil
+}
+
+// indexData is the JSON representation of an index page.
+type indexData struct {
+	// Index is the slug of a tag index's tag and IndexName is its display
+	// name (both omitted for the main index).
+	Index     string `json:"index,omitempty"`
+	IndexName string `json:"indexName,omitempty"`
+
+	// Page is the 1-based page number.
+	Page  int               `json:"page"`
+	URL   string            `json:"url"`
+	Prev  string            `json:"prev,omitempty"`
+	Next  string            `json:"next,omitempty"`
+	Posts []postSummaryData `json:"posts"`
+}
+
+type postSummaryData struct {
+	Title       string    `json:"title"`
+	URL         string    `json:"url"`
+	Author      string    `json:"author,omitempty"`
+	Date        string    `json:"date"`
+	DateDisplay string    `json:"dateDisplay"`
+	DateParts   DateParts `json:"dateParts"`
+	Updated     string    `json:"updated,omitempty"`
+	Tags        []tagData `json:"tags"`
+	Snippet     string    `json:"snippet,omitempty"`
+	Warning     string    `json:"contentWarning,omitempty"`
+	ReadingTime int       `json:"readingTime"`
+}
+
+type tagData struct {
+	Name     string `json:"name"`
+	Slug     string `json:"slug"`
+	URL      string `json:"url"`
+	Implicit bool   `json:"implicit,omitempty"`
+}
+
+func newIndexData(page *OrderedPage[IndexPage]) (data indexData) {
+	data.Index = page.Content.IndexID
+	data.IndexName = page.Content.IndexName
+	data.Page = page.Content.Number + 1
+	data.URL = page.URL.String()
+	if page.Content.PrevPage != nil {
+		data.Prev = page.Content.PrevPage.URL.String()
+	}
+	if page.Content.NextPage != nil {
+		data.Next = page.Content.NextPage.URL.String()
+	}
+
+	data.Posts = make([]postSummaryData, len(page.Content.Posts))
+	for i, p := range page.Content.Posts {
+		post := &data.Posts[i]
+		post.Title = p.Content.Title
+		post.URL = p.URL.String()
+		post.Author = p.Content.Author
+		post.Date = p.Content.Date.String()
+		post.DateDisplay = p.Content.DateDisplay
+		post.DateParts = p.Content.DateParts
+		if p.Content.Updated != nil {
+			post.Updated = p.Content.Updated.String()
+		}
+		post.Tags = make([]tagData, len(p.Content.Tags))
+		for j, tag := range p.Content.Tags {
+			post.Tags[j] = tagData{
+				Name:     tag.Text,
+				Slug:     tag.Slug,
+				URL:      string(tag.URL),
+				Implicit: tag.Implicit,
+			}
+		}
+		if page.Content.SummaryMode != SummaryModeCompact {
+			post.Snippet = *(*string)(unsafe.Pointer(&p.Content.Snippet))
+		}
+		post.Warning = p.Content.ContentWarning
+		post.ReadingTime = p.Content.ReadingTime
+	}
+	return
+}
+
+// indexDataPath returns the output path of the data for the index page at
+// `indexPath`.
+func indexDataPath(indexPath string) string {
+	return strings.TrimSuffix(indexPath, htmlSuffix) + indexDataSuffix
+}
+
+const indexDataSuffix = ".data.json"
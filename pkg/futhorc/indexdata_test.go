@@ -0,0 +1,72 @@
+package futhorc
+
+import (
+	"encoding/json"
+	"fmt"
+	"testing"
+
+	"github.com/go-git/go-billy/v5/util"
+)
+
+func TestIndexData(t *testing.T) {
+	// every post is tagged `go`, and every fourth is also tagged `rust`
+	files := map[string]string{"futhorc.jsonc": `{"indexJSON": true}`}
+	for i := range 12 {
+		tags := "go"
+		if i%4 == 0 {
+			tags += ", rust"
+		}
+		files[fmt.Sprintf("posts/%02d.md", i)] = fmt.Sprintf(
+			"---\nTitle: %d\nDate: 2024-01-%02d\nTags: [%s]\n---\n",
+			i,
+			i+1,
+			tags,
+		)
+	}
+	output, err := buildProject(writeProject(t, testTheme, files))
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	for _, tc := range []struct {
+		path  string
+		page  int
+		posts int
+	}{
+		{path: "index.html", page: 1, posts: 10},
+		{path: "page-001.html", page: 2, posts: 2},
+		{path: "go/index.html", page: 1, posts: 10},
+		{path: "go/page-001.html", page: 2, posts: 2},
+		{path: "rust/index.html", page: 1, posts: 3},
+	} {
+		// each HTML page has its data beside it
+		if _, err := output.Stat(tc.path); err != nil {
+			t.Fatal(err)
+		}
+		p := indexDataPath(tc.path)
+		data, err := util.ReadFile(output, p)
+		if err != nil {
+			t.Fatalf("wanted the data for `%s`: %v", tc.path, err)
+		}
+		var index struct {
+			Page  int               `json:"page"`
+			Posts []json.RawMessage `json:"posts"`
+		}
+		if err := json.Unmarshal(data, &index); err != nil {
+			t.Fatalf("decoding `%s`: %v", p, err)
+		}
+		if index.Page != tc.page || len(index.Posts) != tc.posts {
+			t.Errorf(
+				"`%s`: wanted page %d with %d posts; found page %d with %d",
+				p,
+				tc.page,
+				tc.posts,
+				index.Page,
+				len(index.Posts),
+			)
+		}
+	}
+	if _, err := output.Stat("rust/page-001.data.json"); err == nil {
+		t.Error("wanted no data for a page which doesn't exist")
+	}
+}
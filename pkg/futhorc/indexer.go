@@ -1,7 +1,9 @@
 package futhorc
 
 import (
+	"cmp"
 	"context"
+	"net/url"
 	"slices"
 )
 
@@ -11,10 +13,59 @@ type Indexer struct {
 	Indices       map[string]*Index
 	IndexPages    chan *OrderedPage[IndexPage]
 	PageConverter IndexPageConverter
+
+	// FeedPages receives the pages of the main index in feed order; see
+	// `FeedOrder`.
+	FeedPages chan *OrderedPage[IndexPage]
+	FeedOrder FeedOrder
+
+	// FeedLimit, if positive, is the maximum number of posts in the feed
+	// (the most recent, in feed order).
+	FeedLimit int
+
+	// DataPages, if non-nil, receives every index page for
+	// `IndexDataWriter`.
+	DataPages chan *OrderedPage[IndexPage]
+
+	// TagRedirects maps renamed tags' old names to their new names. The
+	// redirects from the old tags' index pages are sent to `Redirects`, which
+	// must be non-nil if there are any.
+	TagRedirects map[string]string
+	Redirects    chan Redirect
+
+	// ExcludeFeatured removes the featured post from the regular list of
+	// posts on the first page of the main index.
+	ExcludeFeatured bool
+
+	// Groups enables the per-tag groups on the first page of the main index
+	// when it's non-nil.
+	Groups *IndexGroups
+
+	// SummaryModes sets each index page's `SummaryMode`.
+	SummaryModes SummaryModes
+
+	// SinglePostTags determines how tags with exactly one post are indexed.
+	SinglePostTags SinglePostTags
+
+	// TagsPages, if non-nil, receives the tags page.
+	TagsPages chan *OrderedPage[TagsPage]
+
+	// tags summarizes the tag indices for each index page's `Tags`.
+	tags []TagSummary
 }
 
 func (indexer *Indexer) Run(ctx context.Context) error {
 	defer close(indexer.IndexPages)
+	defer close(indexer.FeedPages)
+	if indexer.DataPages != nil {
+		defer close(indexer.DataPages)
+	}
+	if indexer.Redirects != nil {
+		defer close(indexer.Redirects)
+	}
+	if indexer.TagsPages != nil {
+		defer close(indexer.TagsPages)
+	}
 
 	// index the posts (NB: there should only be one slice received on the
 	// channel, but the indexer could handle many slices)
@@ -31,7 +82,9 @@ func (indexer *Indexer) Run(ctx context.Context) error {
 				p := &orderedPosts[i]
 				indexer.fetchIndex("").Push(p)
 				for _, tag := range p.Content.Tags {
-					indexer.fetchIndex(tag.Text).Push(p)
+					idx := indexer.fetchIndex(tag.Slug)
+					idx.Name = displayName(idx.Name, tag.Text)
+					idx.Push(p)
 				}
 			}
 		}
@@ -40,7 +93,87 @@ func (indexer *Indexer) Run(ctx context.Context) error {
 	// sort and paginate the indices--this will also flush the paginated pages
 	// out the `IndexPages` channel.
 PAGINATE:
-	return indexer.paginate(ctx)
+	// the main index is always rendered, even if there are no posts
+	indexer.fetchIndex("")
+	if err := indexer.redirect(ctx); err != nil {
+		return err
+	}
+	indexer.locate()
+	indexer.tags = summarizeTags(indexer.Indices)
+	if err := indexer.paginate(ctx); err != nil {
+		return err
+	}
+	return indexer.sendTagsPage(ctx)
+}
+
+// sendTagsPage sends the tags page to `TagsPages` if it's non-nil.
+func (indexer *Indexer) sendTagsPage(ctx context.Context) error {
+	if indexer.TagsPages == nil {
+		return nil
+	}
+	converter := PageConverter[TagsPage]{
+		BaseURL:    indexer.PageConverter.BaseURL,
+		PrettyURLs: indexer.PageConverter.PrettyURLs,
+	}
+	var page OrderedPage[TagsPage]
+	var err error
+	if page.Page, err = converter.Convert(
+		tagsPagePath(converter.PrettyURLs),
+		0,
+		TagsPage{Tags: indexer.tags},
+	); err != nil {
+		return err
+	}
+	select {
+	case <-ctx.Done():
+		return ctx.Err()
+	case indexer.TagsPages <- &page:
+		return nil
+	}
+}
+
+// locate sets the URL of each index: the URL of its first page, or the URL of
+// its post if it's skipped (see `SinglePostTagsLink`).
+func (indexer *Indexer) locate() {
+	for id, idx := range indexer.Indices {
+		if indexer.SinglePostTags.links(idx) {
+			idx.URL = idx.Posts[0].URL
+			continue
+		}
+		idx.URL = indexURL(
+			indexer.PageConverter.BaseURL,
+			id,
+			indexer.PageConverter.PrettyURLs,
+		)
+	}
+}
+
+func (indexer *Indexer) redirect(ctx context.Context) error {
+	redirects, err := tagRedirects(
+		indexer.TagRedirects,
+		func(tag string) bool {
+			_, found := indexer.Indices[tag]
+			return found
+		},
+		func(tag string) int {
+			idx, found := indexer.Indices[tag]
+			if !found {
+				return 0
+			}
+			return (len(idx.Posts) + indexer.PageSize - 1) / indexer.PageSize
+		},
+		indexer.PageConverter.BaseURL,
+		indexer.PageConverter.PrettyURLs,
+	)
+	if err != nil {
+		return err
+	}
+	for _, redirect := range redirects {
+		if err := send(ctx, indexer.Redirects, redirect); err != nil {
+			return err
+		}
+	}
+	return nil
 }
 
 func (indexer *Indexer) fetchIndex(id string) *Index {
@@ -52,9 +185,22 @@ func (indexer *Indexer) fetchIndex(id string) *Index {
 	return idx
 }
 
+// paginate paginates the indices in order by ID (the main index first) so
+// that the pages are produced in the same order from build to build.
 func (indexer *Indexer) paginate(ctx context.Context) error {
-	for _, idx := range indexer.Indices {
-		if err := indexer.paginateIndex(ctx, idx); err != nil {
+	ids := make([]string, 0, len(indexer.Indices))
+	for id := range indexer.Indices {
+		ids = append(ids, id)
+	}
+	slices.Sort(ids)
+	for _, id := range ids {
+		if indexer.SinglePostTags.links(indexer.Indices[id]) {
+			continue
+		}
+		if err := indexer.paginateIndex(
+			ctx,
+			indexer.Indices[id],
+		); err != nil {
 			return err
 		}
 	}
@@ -66,33 +212,155 @@ func (indexer *Indexer) paginateIndex(ctx context.Context, idx *Index) error {
 	if err != nil {
 		return err
 	}
+	mode := indexer.SummaryModes.For(idx.ID)
+	for i := range pages {
+		pages[i].Content.SummaryMode = mode
+		pages[i].Content.Tags = indexer.tags
+		pages[i].Content.Inline = indexer.SinglePostTags.inlines(idx)
+	}
+
+	if idx.ID == "" {
+		// `OrderPages` sorts the pages in descending order by page number, so
+		// the first page is last
+		indexer.decorateFirstPage(&pages[len(pages)-1].Content, idx)
+	}
+
+	if err := sendPages(ctx, indexer.IndexPages, pages); err != nil {
+		return err
+	}
+	if indexer.DataPages != nil {
+		if err := sendPages(ctx, indexer.DataPages, pages); err != nil {
+			return err
+		}
+	}
+
+	if idx.ID == "" {
+		return indexer.paginateFeed(ctx, idx, pages)
+	}
+	return nil
+}
+
+// decorateFirstPage adds the featured post and the tag groups (if enabled) to
+// the first page of the main index.
+func (indexer *Indexer) decorateFirstPage(page *IndexPage, idx *Index) {
+	// `idx.Posts` is sorted from newest to oldest, so this is the most recent
+	// featured post
+	if i := slices.IndexFunc(idx.Posts, isFeatured); i >= 0 {
+		featured := idx.Posts[i]
+		page.Featured = featured
+		if indexer.ExcludeFeatured {
+			// the page's posts are a subslice of `idx.Posts`, so delete from
+			// a copy; other flagged posts stay in the list
+			page.Posts = slices.DeleteFunc(
+				slices.Clone(page.Posts),
+				func(p *OrderedPage[Post]) bool { return p == featured },
+			)
+		}
+	}
+
+	if indexer.Groups != nil {
+		page.Groups = indexer.Groups.Group(indexer.Indices)
+	}
+}
+
+func isFeatured(p *OrderedPage[Post]) bool { return p.Content.Featured }
+
+// paginateFeed sends the feed pages for the main index. These are the main
+// index pages themselves unless some posts are excluded from the feed, the
+// feed is in updated order, the featured post is excluded from the main
+// index, or the feed is limited to fewer posts than the index has. In those
+// cases the feed's posts are paginated separately (so excluded posts don't
+// take up a slot on a feed page).
+func (indexer *Indexer) paginateFeed(
+	ctx context.Context,
+	idx *Index,
+	pages []OrderedPage[IndexPage],
+) error {
+	excluded := slices.ContainsFunc(idx.Posts, excludedFromFeed)
+	limited := indexer.FeedLimit > 0 && len(idx.Posts) > indexer.FeedLimit
+	if excluded || limited || indexer.FeedOrder == FeedOrderUpdated ||
+		indexer.ExcludeFeatured {
+		// the main index pages hold subslices of `idx.Posts`, so paginate a
+		// copy
+		feed := Index{
+			ID: idx.ID,
+			Posts: slices.DeleteFunc(
+				slices.Clone(idx.Posts),
+				excludedFromFeed,
+			),
+		}
+		compare := comparePublished
+		if indexer.FeedOrder == FeedOrderUpdated {
+			compare = compareModified
+		}
+		if limited {
+			slices.SortFunc(feed.Posts, compare)
+			feed.Posts = feed.Posts[:min(len(feed.Posts), indexer.FeedLimit)]
+		}
+		var err error
+		if pages, err = feed.PaginateBy(
+			indexer.PageSize,
+			&indexer.PageConverter,
+			compare,
+		); err != nil {
+			return err
+		}
+	}
+	return sendPages(ctx, indexer.FeedPages, pages)
+}
+
+func excludedFromFeed(p *OrderedPage[Post]) bool {
+	return !p.Content.InFeed()
+}
 
+func sendPages(
+	ctx context.Context,
+	ch chan<- *OrderedPage[IndexPage],
+	pages []OrderedPage[IndexPage],
+) error {
 	for i := range pages {
 		select {
 		case <-ctx.Done():
 			return ctx.Err()
-		case indexer.IndexPages <- &pages[i]:
+		case ch <- &pages[i]:
 		}
 	}
 	return nil
 }
 
 type Index struct {
+	// ID is the slug of the index's tag, or empty for the main index, and
+	// Name is the tag's display name.
 	ID    string
+	Name  string
 	Posts []*OrderedPage[Post]
+
+	// URL is where the index's tag links to, which the indexer sets once
+	// every post is indexed.
+	URL *url.URL
 }
 
 func (idx *Index) Push(p *OrderedPage[Post]) {
 	idx.Posts = append(idx.Posts, p)
 }
 
+// Paginate sorts the index's posts from newest to oldest and divides them
+// into pages.
 func (idx *Index) Paginate(
 	size int,
 	converter *IndexPageConverter,
 ) (pages []OrderedPage[IndexPage], err error) {
-	slices.SortFunc(idx.Posts, func(a, b *OrderedPage[Post]) int {
-		return b.Compare(&a.Page)
-	})
+	return idx.PaginateBy(size, converter, comparePublished)
+}
+
+// PaginateBy sorts the index's posts according to `compare` and divides them
+// into pages.
+func (idx *Index) PaginateBy(
+	size int,
+	converter *IndexPageConverter,
+	compare func(a, b *OrderedPage[Post]) int,
+) (pages []OrderedPage[IndexPage], err error) {
+	slices.SortFunc(idx.Posts, compare)
 	for i := range len(idx.Posts) / size {
 		pages = append(pages, OrderedPage[IndexPage]{})
 		if pages[len(pages)-1].Page, err = converter.Convert(
@@ -120,6 +388,43 @@ func (idx *Index) Paginate(
 			return
 		}
 	}
+	if len(pages) < 1 {
+		// an index without posts still gets an (empty) first page
+		pages = append(pages, OrderedPage[IndexPage]{})
+		if pages[0].Page, err = converter.Convert(idx, 0, 0, 0); err != nil {
+			return
+		}
+	}
 	OrderPages(pages)
+
+	// `OrderPages` sorts the pages in descending order by page number, so
+	// the previous (newer) page is the one that follows in the slice.
+	first, last := indexPageLink(&pages[len(pages)-1]), indexPageLink(&pages[0])
+	for i := range pages {
+		pages[i].Content.TotalPages = len(pages)
+		pages[i].Content.FirstPage = first
+		pages[i].Content.LastPage = last
+		if i+1 < len(pages) {
+			pages[i].Content.PrevPage = indexPageLink(&pages[i+1])
+		}
+		if i > 0 {
+			pages[i].Content.NextPage = indexPageLink(&pages[i-1])
+		}
+	}
 	return
 }
+
+// comparePublished orders posts from newest to oldest.
+func comparePublished(a, b *OrderedPage[Post]) int {
+	return b.Compare(&a.Page)
+}
+
+// compareModified orders posts from most to least recently modified, where a
+// post is modified when it's updated (or published, if it was never updated).
+// Posts modified at the same time are ordered by path, so the order is stable.
+func compareModified(a, b *OrderedPage[Post]) int {
+	return cmp.Or(
+		b.Content.Modified().Compare(a.Content.Modified()),
+		cmp.Compare(a.Path, b.Path),
+	)
+}
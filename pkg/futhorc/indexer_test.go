@@ -0,0 +1,277 @@
+package futhorc
+
+import (
+	"encoding/json"
+	"fmt"
+	"maps"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/go-git/go-billy/v5/util"
+)
+
+func TestReproducibleBuild(t *testing.T) {
+	for _, fixture := range []string{"minimal", "tags", "bundles"} {
+		t.Run(fixture, func(t *testing.T) {
+			dir := filepath.Join("testdata", "golden", fixture)
+			first, second := buildFixture(t, dir), buildFixture(t, dir)
+
+			diff, err := DiffOutputs(first, second)
+			if err != nil {
+				t.Fatal(err)
+			}
+			if !diff.Empty() {
+				t.Fatalf(
+					"builds differ: added %v, removed %v, modified %v",
+					diff.Added,
+					diff.Removed,
+					diff.Modified,
+				)
+			}
+		})
+	}
+}
+
+func TestEmptySite(t *testing.T) {
+	output, err := buildProject(writeProject(t, testTheme, map[string]string{
+		"theme/index.html": "{{ .Content.PageNumber }}/" +
+			"{{ .Content.TotalPages }}: {{ len .Content.Posts }} posts",
+		"posts/notes.txt": "not a post",
+	}))
+	if err != nil {
+		t.Fatalf("wanted a site without posts to build: %v", err)
+	}
+
+	data, err := util.ReadFile(output, "index.html")
+	if err != nil {
+		t.Fatal(err)
+	}
+	if wanted := "1/1: 0 posts"; string(data) != wanted {
+		t.Errorf("wanted the index %q; found %q", wanted, data)
+	}
+	if _, err := output.Stat("page-001.html"); err == nil {
+		t.Error("wanted a single index page")
+	}
+
+	data, err = util.ReadFile(output, "index.json")
+	if err != nil {
+		t.Fatal(err)
+	}
+	var feed struct {
+		Items []json.RawMessage `json:"items"`
+	}
+	if err := json.Unmarshal(data, &feed); err != nil {
+		t.Fatalf("wanted a valid feed: %v", err)
+	}
+	if len(feed.Items) > 0 {
+		t.Errorf("wanted no feed entries; found %d", len(feed.Items))
+	}
+}
+
+func TestFeaturedPost(t *testing.T) {
+	post := func(title, date string, featured bool) string {
+		return fmt.Sprintf(
+			"---\nTitle: %s\nDate: %s\nTags: [go]\nFeatured: %t\n---\n",
+			title,
+			date,
+			featured,
+		)
+	}
+	theme := maps.Clone(testTheme)
+
+	// the featured post is starred ahead of the regular list
+	theme["theme/index.html"] = "{{ with .Content.Featured }}" +
+		"*{{ .Content.Title }}*{{ end }}" +
+		"{{ range .Content.Posts }} {{ .Content.Title }}{{ end }}"
+
+	for _, tc := range []struct {
+		name     string
+		featured []bool
+		exclude  bool
+
+		// main is the first page of the main index
+		main string
+	}{
+		{
+			name:     "one",
+			featured: []bool{false, true, false},
+			main:     "*B* C B A",
+		},
+		{
+			name:     "one-excluded",
+			featured: []bool{false, true, false},
+			exclude:  true,
+			main:     "*B* C A",
+		},
+		{
+			// the most recent flagged post wins, and the others stay in the
+			// list
+			name:     "several-excluded",
+			featured: []bool{true, false, true},
+			exclude:  true,
+			main:     "*C* B A",
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			files := map[string]string{
+				"futhorc.jsonc": fmt.Sprintf(
+					`{"excludeFeatured": %t}`,
+					tc.exclude,
+				),
+			}
+			for i, title := range []string{"A", "B", "C"} {
+				files["posts/"+strings.ToLower(title)+".md"] = post(
+					title,
+					fmt.Sprintf("2024-01-0%d", i+1),
+					tc.featured[i],
+				)
+			}
+			output, err := buildProject(writeProject(t, theme, files))
+			if err != nil {
+				t.Fatal(err)
+			}
+
+			// tag indices ignore the flag
+			for p, wanted := range map[string]string{
+				"index.html":    tc.main,
+				"go/index.html": " C B A",
+			} {
+				data, err := util.ReadFile(output, p)
+				if err != nil {
+					t.Fatal(err)
+				}
+				if string(data) != wanted {
+					t.Errorf("`%s`: wanted %q; found %q", p, wanted, data)
+				}
+			}
+		})
+	}
+}
+
+func TestIndexPageLinks(t *testing.T) {
+	theme := maps.Clone(testTheme)
+	theme["theme/index.html"] = "{{ .Content.PageNumber }}/" +
+		"{{ .Content.TotalPages }} " +
+		"prev={{ with .Content.PrevPage }}" +
+		"{{ .Number }} {{ .IsFirst }} {{ url .URL }}{{ end }} " +
+		"next={{ with .Content.NextPage }}" +
+		"{{ .Number }} {{ .IsFirst }} {{ url .URL }}{{ end }}"
+
+	// 21 posts make three pages of 10
+	posts := map[string]string{}
+	for i := range 21 {
+		posts[fmt.Sprintf("posts/%02d.md", i)] = fmt.Sprintf(
+			"---\nTitle: Post %d\nDate: 2024-01-%02d\n---\n",
+			i,
+			i+1,
+		)
+	}
+
+	for _, tc := range []struct {
+		name   string
+		pretty bool
+
+		// wanted maps each page's output path to its rendering
+		wanted map[string]string
+	}{
+		{
+			name: "plain",
+			wanted: map[string]string{
+				"index.html": "1/3 prev= " +
+					"next=2 false https://example.com/page-001.html",
+				"page-001.html": "2/3 " +
+					"prev=1 true https://example.com/index.html " +
+					"next=3 false https://example.com/page-002.html",
+				"page-002.html": "3/3 " +
+					"prev=2 false https://example.com/page-001.html next=",
+			},
+		},
+		{
+			name:   "pretty",
+			pretty: true,
+			wanted: map[string]string{
+				"index.html": "1/3 prev= " +
+					"next=2 false https://example.com/page-001/",
+				"page-001/index.html": "2/3 " +
+					"prev=1 true https://example.com/ " +
+					"next=3 false https://example.com/page-002/",
+				"page-002/index.html": "3/3 " +
+					"prev=2 false https://example.com/page-001/ next=",
+			},
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			files := maps.Clone(posts)
+			files["futhorc.jsonc"] = fmt.Sprintf(
+				`{"prettyURLs": %t}`,
+				tc.pretty,
+			)
+			output, err := buildProject(writeProject(t, theme, files))
+			if err != nil {
+				t.Fatal(err)
+			}
+			for p, wanted := range tc.wanted {
+				data, err := util.ReadFile(output, p)
+				if err != nil {
+					t.Fatal(err)
+				}
+				if found := strings.TrimSpace(string(data)); found != wanted {
+					t.Errorf("`%s`: wanted %q; found %q", p, wanted, found)
+				}
+			}
+		})
+	}
+}
+
+func TestIndexPageFields(t *testing.T) {
+	// the first page's URL is the index's URL, so it doubles as the tag's
+	theme := maps.Clone(testTheme)
+	theme["theme/index.html"] = "main={{ .Content.IsMain }} " +
+		"tag={{ .Content.IndexName }} " +
+		"{{ .Content.PageNumber }}/{{ .Content.TotalPages }} " +
+		"first={{ url .Content.FirstPage.URL }} " +
+		"last={{ url .Content.LastPage.URL }}"
+
+	// 11 posts tagged `rust` and one tagged `go` make two pages of the main
+	// index and of the `rust` index, and one of the `go` index
+	files := map[string]string{
+		"posts/go.md": "---\nTitle: Go\nDate: 2024-02-01\nTags: [go]\n---\n",
+	}
+	for i := range 11 {
+		files[fmt.Sprintf("posts/%02d.md", i)] = fmt.Sprintf(
+			"---\nTitle: Post %d\nDate: 2024-01-%02d\nTags: [rust]\n---\n",
+			i,
+			i+1,
+		)
+	}
+	output, err := buildProject(writeProject(t, theme, files))
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	const root = goldenSiteRoot
+	for p, wanted := range map[string]string{
+		"index.html": "main=true tag= 1/2 first=" + root + "index.html " +
+			"last=" + root + "page-001.html",
+		"page-001.html": "main=true tag= 2/2 first=" + root + "index.html " +
+			"last=" + root + "page-001.html",
+		"rust/index.html": "main=false tag=rust 1/2 " +
+			"first=" + root + "rust/index.html " +
+			"last=" + root + "rust/page-001.html",
+		"rust/page-001.html": "main=false tag=rust 2/2 " +
+			"first=" + root + "rust/index.html " +
+			"last=" + root + "rust/page-001.html",
+		"go/index.html": "main=false tag=go 1/1 " +
+			"first=" + root + "go/index.html " +
+			"last=" + root + "go/index.html",
+	} {
+		data, err := util.ReadFile(output, p)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if found := string(data); found != wanted {
+			t.Errorf("`%s`: wanted %q; found %q", p, wanted, found)
+		}
+	}
+}
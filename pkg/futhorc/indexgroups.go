@@ -0,0 +1,103 @@
+package futhorc
+
+import (
+	"cmp"
+	"fmt"
+	"net/url"
+	"slices"
+)
+
+// IndexGroups configures the per-tag groups of recent posts on the first
+// page of the main index (see `IndexPage.Groups`).
+type IndexGroups struct {
+	// Posts is the number of recent posts in each group (defaults to 3).
+	Posts int `json:"posts"`
+
+	// MinPosts excludes tags with fewer posts from the groups.
+	MinPosts int `json:"minPosts"`
+
+	// Sort is the order of the groups: `count` (the default) for the tags
+	// with the most posts first, or `name` for alphabetical order.
+	Sort string `json:"sort"`
+}
+
+// IndexGroup is the most recent posts for a tag.
+type IndexGroup struct {
+	// Tag is the tag's display name.
+	Tag string
+
+	// URL is the URL of the tag's index.
+	URL *url.URL
+
+	// Count is the total number of posts with the tag, which may be more
+	// than the number of posts in the group.
+	Count int
+	Posts []*OrderedPage[Post]
+}
+
+// withDefaults validates the settings and fills in the defaults for any unset
+// values.
+func (groups IndexGroups) withDefaults() (IndexGroups, error) {
+	if groups.Posts < 0 || groups.MinPosts < 0 {
+		return groups, fmt.Errorf(
+			"invalid index groups settings: `posts` (%d) and `minPosts` "+
+				"(%d) must not be negative",
+			groups.Posts,
+			groups.MinPosts,
+		)
+	}
+	if groups.Posts == 0 {
+		groups.Posts = defaultGroupPosts
+	}
+	switch groups.Sort {
+	case "":
+		groups.Sort = GroupSortCount
+	case GroupSortCount, GroupSortName:
+	default:
+		return groups, fmt.Errorf(
+			"invalid index groups sort `%s`: expected `%s` or `%s`",
+			groups.Sort,
+			GroupSortCount,
+			GroupSortName,
+		)
+	}
+	return groups, nil
+}
+
+// Group builds the groups from the tag indices in `indices`.
+func (groups *IndexGroups) Group(indices map[string]*Index) []IndexGroup {
+	var out []IndexGroup
+	for id, idx := range indices {
+		if id == "" || len(idx.Posts) < groups.MinPosts {
+			continue
+		}
+
+		// other index pages may hold subslices of `idx.Posts`, so sort a
+		// copy
+		posts := slices.Clone(idx.Posts)
+		slices.SortFunc(posts, comparePublished)
+		out = append(out, IndexGroup{
+			Tag:   idx.Name,
+			URL:   idx.URL,
+			Count: len(posts),
+			Posts: posts[:min(len(posts), groups.Posts)],
+		})
+	}
+
+	slices.SortFunc(out, func(a, b IndexGroup) int {
+		if groups.Sort == GroupSortCount {
+			if c := cmp.Compare(b.Count, a.Count); c != 0 {
+				return c
+			}
+		}
+		return cmp.Compare(a.Tag, b.Tag)
+	})
+	return out
+}
+
+const (
+	GroupSortCount = "count"
+	GroupSortName  = "name"
+
+	defaultGroupPosts = 3
+)
@@ -0,0 +1,60 @@
+package futhorc
+
+import (
+	"fmt"
+	"testing"
+
+	"github.com/go-git/go-billy/v5/util"
+)
+
+func TestIndexGroups(t *testing.T) {
+	files := map[string]string{
+		// each group prints its tag, post count, and posts
+		"theme/index.html": "{{ range .Content.Groups }}[{{ .Tag }} " +
+			"{{ .Count }}:{{ range .Posts }} {{ .Content.Title }}{{ end }}]" +
+			"{{ end }}",
+	}
+	for i, tags := range []string{"go", "go, rust", "go", "rust", "go", "ant"} {
+		files[fmt.Sprintf("posts/%c.md", 'a'+i)] = fmt.Sprintf(
+			"---\nTitle: %c\nDate: 2024-01-0%d\nTags: [%s]\n---\n",
+			'A'+i,
+			i+1,
+			tags,
+		)
+	}
+
+	for _, tc := range []struct {
+		name   string
+		config string
+		wanted string
+	}{
+		{name: "disabled", config: `{}`},
+		{
+			// `ant` has too few posts
+			name:   "count",
+			config: `{"indexGroups": {"posts": 2, "minPosts": 2}}`,
+			wanted: "[go 4: E C][rust 2: D B]",
+		},
+		{
+			name:   "name",
+			config: `{"indexGroups": {"posts": 2, "sort": "name"}}`,
+			wanted: "[ant 1: F][go 4: E C][rust 2: D B]",
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			dir := writeProject(t, testTheme, files)
+			writeFiles(t, dir, map[string]string{"futhorc.jsonc": tc.config})
+			output, err := buildProject(dir)
+			if err != nil {
+				t.Fatal(err)
+			}
+			data, err := util.ReadFile(output, "index.html")
+			if err != nil {
+				t.Fatal(err)
+			}
+			if string(data) != tc.wanted {
+				t.Errorf("wanted the groups %q; found %q", tc.wanted, data)
+			}
+		})
+	}
+}
@@ -3,7 +3,7 @@ package futhorc
 import (
 	"fmt"
 	"net/url"
-	"path/filepath"
+	"path"
 )
 
 type IndexPageConverter PageConverter[IndexPage]
@@ -14,27 +14,98 @@ func (converter *IndexPageConverter) Convert(
 	postsStart int,
 	postsEnd int,
 ) (page Page[IndexPage], err error) {
-	var fileName string
-	if pageNumber == 0 {
-		fileName = "index.html"
-	} else {
-		fileName = fmt.Sprintf("page-%03d.html", pageNumber)
-	}
 	return (*PageConverter[IndexPage])(converter).Convert(
-		filepath.Join(idx.ID, fileName),
+		indexPagePath(idx.ID, pageNumber, converter.PrettyURLs),
 		int64(pageNumber),
 		IndexPage{
-			IndexID: idx.ID,
-			Number:  pageNumber,
-			Posts:   idx.Posts[postsStart:postsEnd],
+			IndexID:   idx.ID,
+			IndexName: idx.Name,
+			Number:    pageNumber,
+			Posts:     idx.Posts[postsStart:postsEnd],
 		},
 	)
 }
 
+// indexPagePath returns the output path of a page of the index with ID `id`.
+// With pretty URLs, every page is an `index.html` in a directory of its own
+// (e.g., `rust/page-001/index.html`).
+func indexPagePath(id string, pageNumber int, pretty bool) string {
+	if pageNumber == 0 {
+		return path.Join(id, indexFile)
+	}
+	page := fmt.Sprintf("page-%03d", pageNumber)
+	if pretty {
+		return path.Join(id, page, indexFile)
+	}
+	return path.Join(id, page+htmlSuffix)
+}
+
 type IndexPage struct {
-	IndexID string
-	Number  int
-	Posts   []*OrderedPage[Post]
+	// IndexID is the slug of the index's tag, or empty for the main index,
+	// and IndexName is the tag's display name.
+	IndexID   string
+	IndexName string
+	Number    int
+	Posts     []*OrderedPage[Post]
+
+	// TotalPages is the number of pages in the index.
+	TotalPages int
+
+	// SummaryMode is how the page's posts should be summarized.
+	SummaryMode SummaryMode
+
+	// PrevPage and NextPage link to the adjacent pages of the index. They're
+	// nil on the first and last pages respectively.
+	PrevPage *IndexPageLink
+	NextPage *IndexPageLink
+
+	// FirstPage and LastPage link to the ends of the index (the URL of the
+	// first page is the index's URL).
+	FirstPage *IndexPageLink
+	LastPage  *IndexPageLink
+
+	// Featured is the most recent post flagged `Featured` in its
+	// frontmatter. It's only set on the first page of the main index.
+	Featured *OrderedPage[Post]
+
+	// Groups holds the most recent posts for each tag. It's only set on the
+	// first page of the main index, and only if index groups are enabled.
+	Groups []IndexGroup
+
+	// Tags lists every tag (as on the tags page), e.g., for a tag cloud.
+	Tags []TagSummary
+
+	// Inline is true for the page of a tag with exactly one post if
+	// `singlePostTags` is `inline`, so the template can render the whole
+	// post (its `Body`) rather than a summary.
+	Inline bool
+}
+
+// IndexPageLink describes a link to a page of an index.
+type IndexPageLink struct {
+	URL *url.URL
+
+	// Number is the 1-based page number (unlike `IndexPage.Number`, which is
+	// 0-based) so templates can render it directly.
+	Number int
+
+	// IsFirst is true for the first page of the index (`index.html`).
+	IsFirst bool
+}
+
+// PageNumber returns the 1-based page number.
+func (page IndexPage) PageNumber() int { return page.Number + 1 }
+
+// IsMain reports whether the page belongs to the main index rather than a
+// tag's index.
+func (page IndexPage) IsMain() bool { return page.IndexID == "" }
+
+func indexPageLink(page *OrderedPage[IndexPage]) *IndexPageLink {
+	return &IndexPageLink{
+		URL:     page.URL,
+		Number:  page.Content.Number + 1,
+		IsFirst: page.Content.Number == 0,
+	}
 }
 
 func (page *IndexPage) PageContent(
@@ -43,7 +114,7 @@ func (page *IndexPage) PageContent(
 	c = Page[IndexPage]{
 		Content: *page,
 		Order:   int64(page.Number),
-		Path: filepath.Join(
+		Path: path.Join(
 			page.IndexID,
 			fmt.Sprintf("page-%03d.html", page.Number),
 		),
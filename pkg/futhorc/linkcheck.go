@@ -0,0 +1,135 @@
+package futhorc
+
+import (
+	"cmp"
+	"fmt"
+	"log/slog"
+	"net/url"
+	"path"
+	"slices"
+	"strings"
+
+	"futhorc/pkg/markdown"
+
+	"github.com/go-git/go-billy/v5"
+)
+
+// LinkCheck determines what happens to links in posts whose targets aren't
+// written by the build (e.g., links to renamed posts).
+type LinkCheck string
+
+const (
+	// LinkCheckOff doesn't check links.
+	LinkCheckOff LinkCheck = "off"
+
+	// LinkCheckWarn logs a warning for each broken link.
+	LinkCheckWarn LinkCheck = "warn"
+
+	// LinkCheckError fails the build with a `BrokenLinksError`.
+	LinkCheckError LinkCheck = "error"
+)
+
+func ParseLinkCheck(s string) (LinkCheck, error) {
+	switch check := LinkCheck(s); check {
+	case "":
+		return LinkCheckOff, nil
+	case LinkCheckOff, LinkCheckWarn, LinkCheckError:
+		return check, nil
+	default:
+		return "", fmt.Errorf(
+			"invalid link check `%s`: expected `%s`, `%s`, or `%s`",
+			s,
+			LinkCheckOff,
+			LinkCheckWarn,
+			LinkCheckError,
+		)
+	}
+}
+
+// BrokenLink is a link in a post to a page or file on the site which the
+// build didn't write.
+type BrokenLink struct {
+	// Source is the source path of the post.
+	Source string
+
+	// Href is the link's destination.
+	Href string
+}
+
+// BrokenLinksError is returned by `Run` for the broken links in posts if
+// links are checked with `LinkCheckError`.
+type BrokenLinksError struct {
+	Links []BrokenLink
+}
+
+func (err *BrokenLinksError) Error() string {
+	var b strings.Builder
+	fmt.Fprintf(&b, "%d broken links:", len(err.Links))
+	for _, link := range err.Links {
+		fmt.Fprintf(&b, "\n%s: link to `%s`", link.Source, link.Href)
+	}
+	return b.String()
+}
+
+// check checks the links recorded in `report` against the files in `output`.
+// It must run once every page and asset has been written. Fragments aren't
+// checked.
+func (check LinkCheck) check(
+	report *BuildReport,
+	output billy.Filesystem,
+	base *url.URL,
+) error {
+	if check == LinkCheckOff || check == "" {
+		return nil
+	}
+	var broken []BrokenLink
+	for source, links := range report.postLinks() {
+		for _, link := range links {
+			if !linkTargetExists(output, base, &link) {
+				broken = append(broken, BrokenLink{
+					Source: source,
+					Href:   link.Href,
+				})
+			}
+		}
+	}
+	if len(broken) < 1 {
+		return nil
+	}
+	slices.SortFunc(broken, func(a, b BrokenLink) int {
+		return cmp.Or(
+			cmp.Compare(a.Source, b.Source),
+			cmp.Compare(a.Href, b.Href),
+		)
+	})
+	if check == LinkCheckError {
+		return &BrokenLinksError{Links: broken}
+	}
+	for _, link := range broken {
+		slog.Warn("broken link", "post", link.Source, "href", link.Href)
+	}
+	return nil
+}
+
+// linkTargetExists reports whether the target of `link` was written to
+// `output`. Links to directories (`foo/` or `foo`) resolve to their
+// `index.html`.
+func linkTargetExists(
+	output billy.Filesystem,
+	base *url.URL,
+	link *markdown.Link,
+) bool {
+	target, err := url.Parse(link.URL)
+	if err != nil {
+		return false
+	}
+	rel := strings.TrimPrefix(target.Path, base.Path)
+	if rel == "" || strings.HasSuffix(rel, "/") {
+		rel = path.Join(rel, indexFile)
+	}
+	info, err := output.Stat(rel)
+	if err == nil && info.IsDir() {
+		info, err = output.Stat(path.Join(rel, indexFile))
+	}
+	return err == nil && !info.IsDir()
+}
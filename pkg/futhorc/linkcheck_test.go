@@ -0,0 +1,100 @@
+package futhorc
+
+import (
+	"errors"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+// testTheme is the smallest theme a project can be built with.
+var testTheme = map[string]string{
+	"theme/theme.jsonc": `{
+	"indexTemplate": ["index.html"],
+	"postTemplate": ["post.html"]
+}`,
+	"theme/index.html":       "{{ range .Content.Posts }}{{ .URL }}{{ end }}",
+	"theme/post.html":        "{{ .Content.Body }}",
+	"theme/assets/style.css": "body {}",
+}
+
+// writeProject writes a project with the files in `theme` and `files` (each
+// mapping slash-separated paths to contents) to a temporary directory, which
+// it returns.
+func writeProject(t *testing.T, theme, files map[string]string) string {
+	t.Helper()
+	dir := t.TempDir()
+	writeFiles(t, dir, theme)
+	writeFiles(t, dir, files)
+	return dir
+}
+
+// writeFiles writes `files` (mapping slash-separated paths to contents) to
+// the directory `dir`, replacing any existing files.
+func writeFiles(t *testing.T, dir string, files map[string]string) {
+	t.Helper()
+	for p, contents := range files {
+		target := filepath.Join(dir, filepath.FromSlash(p))
+		if err := os.MkdirAll(filepath.Dir(target), 0755); err != nil {
+			t.Fatal(err)
+		}
+		if err := os.WriteFile(target, []byte(contents), 0644); err != nil {
+			t.Fatal(err)
+		}
+	}
+}
+
+func TestLinkCheck(t *testing.T) {
+	for _, tc := range []struct {
+		name string
+		link string
+
+		// broken is the href of the broken link, if the link is broken
+		broken string
+	}{
+		{name: "post", link: "[b](b.md)"},
+		{
+			name:   "deleted-post",
+			link:   "[gone](gone.md)",
+			broken: "https://example.com/posts/gone.html",
+		},
+		{name: "loose-asset", link: "![diagram](diagram.png)"},
+		{name: "post-asset", link: "![logo](/assets/posts/logo.png)"},
+		{name: "theme-asset", link: "[style](/assets/theme/style.css)"},
+		{name: "index", link: "[home](/)"},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			a := "---\nTitle: A\nDate: 2024-01-01\n---\n\n" + tc.link + "\n"
+			b := "---\nTitle: B\nDate: 2024-01-02\n---\n"
+			dir := writeProject(t, testTheme, map[string]string{
+				"futhorc.jsonc":         `{"checkLinks": "error"}`,
+				"posts/a.md":            a,
+				"posts/b.md":            b,
+				"posts/diagram.png":     "diagram",
+				"posts/assets/logo.png": "logo",
+			})
+			_, err := buildProject(dir)
+
+			if tc.broken == "" {
+				if err != nil {
+					t.Fatalf("unexpected error: %v", err)
+				}
+				return
+			}
+			var brokenErr *BrokenLinksError
+			if !errors.As(err, &brokenErr) {
+				t.Fatalf("wanted a `*BrokenLinksError`; found `%v`", err)
+			}
+			if len(brokenErr.Links) != 1 ||
+				!strings.HasSuffix(brokenErr.Links[0].Source, "a.md") ||
+				brokenErr.Links[0].Href != tc.broken {
+				t.Errorf(
+					"wanted a broken link to `%s` in `a.md`; found %v",
+					tc.broken,
+					brokenErr.Links,
+				)
+			}
+		})
+	}
+}
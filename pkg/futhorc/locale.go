@@ -0,0 +1,212 @@
+package futhorc
+
+import (
+	"fmt"
+	"log/slog"
+	"strings"
+	"time"
+)
+
+// Locale holds the month and weekday names used to render display dates.
+type Locale struct {
+	Months      [12]string
+	MonthsShort [12]string
+	Days        [7]string
+	DaysShort   [7]string
+
+	// Layout is the Go time layout in which the locale writes dates, which
+	// is used unless the date format is configured.
+	Layout string
+}
+
+// LookupLocale returns the bundled locale for the provided language tag (e.g.,
+// `de` or `de-AT`). English, the empty tag, and unknown tags return nil, which
+// selects Go's built-in (English) names; unknown tags also log a warning.
+func LookupLocale(tag string) *Locale {
+	lang, _, _ := strings.Cut(strings.ToLower(tag), "-")
+	lang, _, _ = strings.Cut(lang, "_")
+	if lang == "" || lang == "en" {
+		return nil
+	}
+	if locale, found := locales[lang]; found {
+		return locale
+	}
+	slog.Warn("unknown date locale; falling back to english", "locale", tag)
+	return nil
+}
+
+// DateFormatter renders display dates according to a Go time layout, using
+// the month and weekday names from `Locale` (or English if `Locale` is nil).
+type DateFormatter struct {
+	Layout string
+	Locale *Locale
+}
+
+// Validate checks that the layout is a Go time layout which formats at least
+// one element of the date. Go layouts never fail to format, so this catches
+// mistakes (e.g., strftime formats like `%B %-d, %Y`) which would otherwise
+// render the layout literally for every post.
+func (f *DateFormatter) Validate() error {
+	if strings.Contains(f.Layout, "%") {
+		return fmt.Errorf(
+			"invalid date format `%s`: date formats are Go time layouts "+
+				"(e.g., `January 2, 2006`), not strftime formats",
+			f.Layout,
+		)
+	}
+	// any date but the layouts' reference date formats differently from
+	// the layout if it has any elements
+	if time.Date(1999, 11, 28, 21, 47, 33, 0, time.UTC).Format(
+		f.Layout,
+	) == f.Layout {
+		return fmt.Errorf(
+			"invalid date format `%s`: it has no date elements (e.g., "+
+				"`January`, `2`, or `2006`)",
+			f.Layout,
+		)
+	}
+	return nil
+}
+
+func (f *DateFormatter) Format(t time.Time) string {
+	if f.Locale == nil {
+		return t.Format(f.Layout)
+	}
+
+	// Go layouts have no way to escape literal text, so the localized names
+	// can't be substituted into the layout (e.g., German `Juni` contains the
+	// `Jun` token). Instead, format the chunks between name tokens with the
+	// standard library and write the localized names ourselves.
+	var sb strings.Builder
+	layout := f.Layout
+	for {
+		i, token := nextNameToken(layout)
+		if i > 0 {
+			sb.WriteString(t.Format(layout[:i]))
+		}
+		if token == "" {
+			return sb.String()
+		}
+		sb.WriteString(f.Locale.name(t, token))
+		layout = layout[i+len(token):]
+	}
+}
+
+// DateParts breaks a date into components for templates (e.g., to render
+// `<time datetime="...">` elements or group posts by month).
+type DateParts struct {
+	// ISO is the date in RFC 3339 format.
+	ISO   string `json:"iso"`
+	Year  int    `json:"year"`
+	Month int    `json:"month"`
+
+	// MonthName is the localized name of the month.
+	MonthName string `json:"monthName"`
+	Day       int    `json:"day"`
+}
+
+// Parts returns the components of `t`, with the month name in the
+// formatter's locale.
+func (f *DateFormatter) Parts(t time.Time) DateParts {
+	month := DateFormatter{Layout: "January", Locale: f.Locale}
+	return DateParts{
+		ISO:       t.Format(time.RFC3339),
+		Year:      t.Year(),
+		Month:     int(t.Month()),
+		MonthName: month.Format(t),
+		Day:       t.Day(),
+	}
+}
+
+func (locale *Locale) name(t time.Time, token string) string {
+	switch token {
+	case "January":
+		return locale.Months[t.Month()-1]
+	case "Jan":
+		return locale.MonthsShort[t.Month()-1]
+	case "Monday":
+		return locale.Days[t.Weekday()]
+	default:
+		return locale.DaysShort[t.Weekday()]
+	}
+}
+
+// nextNameToken finds the first month or weekday name token in `layout`,
+// returning its index and the token itself (or `len(layout)` and the empty
+// string if there are none). Like the standard library, `Jan` and `Mon` only
+// count as tokens if they aren't followed by a lowercase letter.
+func nextNameToken(layout string) (int, string) {
+	for i := range len(layout) {
+		rest := layout[i:]
+		switch {
+		case strings.HasPrefix(rest, "January"):
+			return i, "January"
+		case strings.HasPrefix(rest, "Jan") && !startsWithLower(rest[3:]):
+			return i, "Jan"
+		case strings.HasPrefix(rest, "Monday"):
+			return i, "Monday"
+		case strings.HasPrefix(rest, "Mon") && !startsWithLower(rest[3:]):
+			return i, "Mon"
+		}
+	}
+	return len(layout), ""
+}
+
+func startsWithLower(s string) bool {
+	return len(s) > 0 && 'a' <= s[0] && s[0] <= 'z'
+}
+
+var locales = map[string]*Locale{
+	"de": {
+		Months: [12]string{
+			"Januar", "Februar", "März", "April", "Mai", "Juni", "Juli",
+			"August", "September", "Oktober", "November", "Dezember",
+		},
+		MonthsShort: [12]string{
+			"Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.",
+			"Sept.", "Okt.", "Nov.", "Dez.",
+		},
+		Days: [7]string{
+			"Sonntag", "Montag", "Dienstag", "Mittwoch", "Donnerstag",
+			"Freitag", "Samstag",
+		},
+		DaysShort: [7]string{"So.", "Mo.", "Di.", "Mi.", "Do.", "Fr.", "Sa."},
+		Layout:    "2. January 2006",
+	},
+	"fr": {
+		Months: [12]string{
+			"janvier", "février", "mars", "avril", "mai", "juin", "juillet",
+			"août", "septembre", "octobre", "novembre", "décembre",
+		},
+		MonthsShort: [12]string{
+			"janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août",
+			"sept.", "oct.", "nov.", "déc.",
+		},
+		Days: [7]string{
+			"dimanche", "lundi", "mardi", "mercredi", "jeudi", "vendredi",
+			"samedi",
+		},
+		DaysShort: [7]string{
+			"dim.", "lun.", "mar.", "mer.", "jeu.", "ven.", "sam.",
+		},
+		Layout: "2 January 2006",
+	},
+	"es": {
+		Months: [12]string{
+			"enero", "febrero", "marzo", "abril", "mayo", "junio", "julio",
+			"agosto", "septiembre", "octubre", "noviembre", "diciembre",
+		},
+		MonthsShort: [12]string{
+			"ene.", "feb.", "mar.", "abr.", "may.", "jun.", "jul.", "ago.",
+			"sept.", "oct.", "nov.", "dic.",
+		},
+		Days: [7]string{
+			"domingo", "lunes", "martes", "miércoles", "jueves", "viernes",
+			"sábado",
+		},
+		DaysShort: [7]string{
+			"dom.", "lun.", "mar.", "mié.", "jue.", "vie.", "sáb.",
+		},
+		Layout: "2 de January de 2006",
+	},
+}
@@ -0,0 +1,119 @@
+package futhorc
+
+import (
+	"fmt"
+	"maps"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/go-git/go-billy/v5/util"
+)
+
+func TestDateFormatterLocales(t *testing.T) {
+	// a Friday
+	date := time.Date(2021, 4, 16, 0, 0, 0, 0, time.UTC)
+	june := time.Date(2021, 6, 16, 0, 0, 0, 0, time.UTC)
+	for _, tc := range []struct {
+		name   string
+		locale string
+		layout string
+		date   time.Time
+		wanted string
+	}{
+		{"de", "de", "", date, "16. April 2021"},
+		{"de-region", "de-AT", "", date, "16. April 2021"},
+		{"de-weekday", "de", "Monday, 2. January 2006", date,
+			"Freitag, 16. April 2021"},
+		{"de-short", "de", "Mon, 2 Jan 2006", date, "Fr., 16 Apr. 2021"},
+		// `Juni` contains the `Jun` token, so it mustn't be formatted again
+		{"de-june", "de", "", june, "16. Juni 2021"},
+		{"fr", "fr", "", date, "16 avril 2021"},
+		{"fr-weekday", "fr", "Monday 2 January 2006", date,
+			"vendredi 16 avril 2021"},
+		{"fr-short", "fr", "Mon 2 Jan 2006", date, "ven. 16 avr. 2021"},
+		{"en", "en", "January 2, 2006", date, "April 16, 2021"},
+		{"unknown", "xx", "January 2, 2006", date, "April 16, 2021"},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			locale := LookupLocale(tc.locale)
+			layout := tc.layout
+			if layout == "" {
+				layout = locale.Layout
+			}
+			formatter := DateFormatter{Layout: layout, Locale: locale}
+			if found := formatter.Format(tc.date); found != tc.wanted {
+				t.Errorf("wanted %q; found %q", tc.wanted, found)
+			}
+		})
+	}
+}
+
+func TestDatePartsLocale(t *testing.T) {
+	formatter := DateFormatter{Layout: "2006", Locale: LookupLocale("fr")}
+	parts := formatter.Parts(time.Date(2021, 2, 3, 0, 0, 0, 0, time.UTC))
+	if parts.MonthName != "février" {
+		t.Errorf("wanted the month name `février`; found %q", parts.MonthName)
+	}
+	if parts.Year != 2021 || parts.Month != 2 || parts.Day != 3 {
+		t.Errorf("wanted 2021-02-03; found %+v", parts)
+	}
+}
+
+func TestDateFormat(t *testing.T) {
+	files := map[string]string{
+		"theme/post.html": "{{ .Content.DateDisplay }} ({{ .Content.Date }})",
+		"posts/a.md":      "---\nTitle: A\nDate: 2021-04-16\n---\n",
+	}
+	for _, tc := range []struct {
+		name   string
+		format string
+		wanted string
+		err    string
+	}{
+		{
+			name:   "default",
+			wanted: "April 16, 2021 (2021-04-16)",
+		},
+		{
+			name:   "custom",
+			format: "Monday, 2 Jan 2006",
+			wanted: "Friday, 16 Apr 2021 (2021-04-16)",
+		},
+		{
+			name:   "strftime",
+			format: "%B %-d, %Y",
+			err:    "not strftime formats",
+		},
+		{
+			name:   "no-elements",
+			format: "Posted",
+			err:    "it has no date elements",
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			files := maps.Clone(files)
+			files["futhorc.jsonc"] = fmt.Sprintf(
+				`{"dateFormat": %q}`,
+				tc.format,
+			)
+			output, err := buildProject(writeProject(t, testTheme, files))
+			if tc.err != "" {
+				if err == nil || !strings.Contains(err.Error(), tc.err) {
+					t.Fatalf("wanted an error with %q; found `%v`", tc.err, err)
+				}
+				return
+			}
+			if err != nil {
+				t.Fatal(err)
+			}
+			data, err := util.ReadFile(output, "posts/a.html")
+			if err != nil {
+				t.Fatal(err)
+			}
+			if string(data) != tc.wanted {
+				t.Errorf("wanted %q; found %q", tc.wanted, data)
+			}
+		})
+	}
+}
@@ -0,0 +1,229 @@
+package futhorc
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"io"
+	"io/fs"
+	"log/slog"
+	"os"
+	"path/filepath"
+	"strconv"
+	"strings"
+	"sync/atomic"
+	"time"
+)
+
+// Lock is a lock on an output directory, held by one build at a time.
+type Lock struct {
+	path string
+}
+
+// OutputLockedError is returned when another build holds the lock on the
+// output directory.
+type OutputLockedError struct {
+	// Path is the path of the lock file.
+	Path string
+
+	// PID and Started identify the build holding the lock.
+	PID     int
+	Started string
+}
+
+func (err *OutputLockedError) Error() string {
+	if err.PID == 0 {
+		return fmt.Sprintf(
+			"output directory is locked by another build; "+
+				"remove `%s` if that build is gone",
+			err.Path,
+		)
+	}
+	return fmt.Sprintf(
+		"output directory is locked by another build (pid %d, started %s); "+
+			"remove `%s` if that build is gone",
+		err.PID,
+		err.Started,
+		err.Path,
+	)
+}
+
+// AcquireLock locks the output directory `dir` by creating a lock file beside
+// it (see `lockPath`) holding the PID and start time of this build. A lock
+// held by a process which no longer exists is stale, and it's taken over (see
+// `takeOverLock`). If `wait` is set, it polls until the lock is free (or `ctx`
+// is done) rather than failing with an `OutputLockedError`.
+func AcquireLock(ctx context.Context, dir string, wait bool) (*Lock, error) {
+	path := lockPath(dir)
+	if err := os.MkdirAll(filepath.Dir(path), 0755); err != nil {
+		return nil, fmt.Errorf("locking output directory: %w", err)
+	}
+	for attempt := 0; ; attempt++ {
+		err := createLock(path)
+		if err == nil {
+			return &Lock{path: path}, nil
+		}
+		if !errors.Is(err, fs.ErrExist) {
+			return nil, fmt.Errorf("locking output directory: %w", err)
+		}
+
+		held, info, err := readLock(path)
+		if errors.Is(err, fs.ErrNotExist) {
+			// released since we tried to create it
+			continue
+		}
+		if err != nil {
+			return nil, fmt.Errorf("locking output directory: %w", err)
+		}
+
+		// a lock without a valid pid can't be checked for staleness, so
+		// it's held, but it's retried briefly in case it's still being
+		// written (by a version of futhorc which didn't write it atomically)
+		if held.PID == 0 && !wait && attempt < lockRetries {
+			select {
+			case <-ctx.Done():
+				return nil, ctx.Err()
+			case <-time.After(lockRetryInterval):
+			}
+			continue
+		}
+		if held.PID != 0 && !processAlive(held.PID) {
+			slog.Warn(
+				"taking over stale lock",
+				"path", path,
+				"pid", held.PID,
+				"started", held.Started,
+			)
+			if err := takeOverLock(path, info); err != nil {
+				return nil, fmt.Errorf("locking output directory: %w", err)
+			}
+			continue
+		}
+		if !wait {
+			return nil, held
+		}
+
+		slog.Info("waiting for lock", "path", path, "pid", held.PID)
+		select {
+		case <-ctx.Done():
+			return nil, ctx.Err()
+		case <-time.After(lockPollInterval):
+		}
+	}
+}
+
+// Release releases the lock.
+func (lock *Lock) Release() error {
+	if err := os.Remove(lock.path); err != nil {
+		return fmt.Errorf("releasing output directory lock: %w", err)
+	}
+	return nil
+}
+
+// lockPath returns the path of the lock file for the output directory `dir`:
+// `.{name}.futhorc.lock` in its parent. The lock lives outside of the output
+// directory so that it stays held while the output directory is swapped out
+// (see `swapOutput`).
+func lockPath(dir string) string {
+	dir = filepath.Clean(dir)
+	return filepath.Join(
+		filepath.Dir(dir),
+		"."+filepath.Base(dir)+lockSuffix,
+	)
+}
+
+// createLock creates the lock file `path`, failing with `fs.ErrExist` if it
+// already exists. The lock is written to a temporary file and then linked into
+// place so that other builds never see it without its pid.
+func createLock(path string) error {
+	f, err := os.CreateTemp(filepath.Dir(path), filepath.Base(path)+".*")
+	if err != nil {
+		return err
+	}
+	defer os.Remove(f.Name())
+	_, err = fmt.Fprintf(
+		f,
+		"%d\n%s\n",
+		os.Getpid(),
+		time.Now().Format(time.RFC3339),
+	)
+	if err = errors.Join(err, f.Close()); err != nil {
+		return err
+	}
+	return os.Link(f.Name(), path)
+}
+
+// readLock reads the lock file `path`, returning the build which holds it and
+// the file's info (see `takeOverLock`). If the lock doesn't hold a valid pid,
+// the returned build's `PID` is 0.
+func readLock(path string) (*OutputLockedError, fs.FileInfo, error) {
+	f, err := os.Open(path)
+	if err != nil {
+		return nil, nil, err
+	}
+	defer f.Close()
+	info, err := f.Stat()
+	if err != nil {
+		return nil, nil, err
+	}
+	data, err := io.ReadAll(f)
+	if err != nil {
+		return nil, nil, err
+	}
+
+	pid, started, _ := strings.Cut(strings.TrimSpace(string(data)), "\n")
+	held := &OutputLockedError{Path: path, Started: started}
+	if held.PID, err = strconv.Atoi(pid); err != nil || held.PID <= 0 {
+		held.PID, held.Started = 0, ""
+	}
+	return held, info, nil
+}
+
+// takeOverLock removes the stale lock file `path`, whose info is `stale`. Any
+// number of builds may find the same stale lock, and one of them may already
+// have replaced it with its own by the time another removes it, so the lock is
+// first renamed to a name unique to this attempt. If the renamed file isn't
+// the stale one, it's linked back into place.
+func takeOverLock(path string, stale fs.FileInfo) error {
+	removed := fmt.Sprintf(
+		"%s.%d-%d.stale",
+		path,
+		os.Getpid(),
+		staleLocks.Add(1),
+	)
+	if err := os.Rename(path, removed); err != nil {
+		if errors.Is(err, fs.ErrNotExist) {
+			// another build took it over first
+			return nil
+		}
+		return err
+	}
+	defer os.Remove(removed)
+
+	info, err := os.Stat(removed)
+	if err != nil {
+		return err
+	}
+	if os.SameFile(info, stale) {
+		return nil
+	}
+	if err := os.Link(removed, path); err != nil {
+		return fmt.Errorf(
+			"restoring lock `%s` taken over by mistake: %w",
+			path,
+			err,
+		)
+	}
+	return nil
+}
+
+// staleLocks counts the stale locks which this process has tried to take over,
+// to give each attempt a unique name (see `takeOverLock`).
+var staleLocks atomic.Int64
+
+const (
+	lockSuffix        = ".futhorc.lock"
+	lockPollInterval  = 500 * time.Millisecond
+	lockRetryInterval = 50 * time.Millisecond
+	lockRetries       = 10
+)
@@ -0,0 +1,296 @@
+package futhorc
+
+import (
+	"bufio"
+	"context"
+	"errors"
+	"fmt"
+	"io"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"strconv"
+	"strings"
+	"testing"
+	"time"
+)
+
+// holdLockEnv names the output directory whose lock `TestHoldLockHelper`
+// holds when the test binary is run as a child process.
+const holdLockEnv = "FUTHORC_TEST_HOLD_LOCK"
+
+// TestHoldLockHelper isn't a real test: the lock tests re-run the test binary
+// with only this test and `holdLockEnv` set, so that the lock is held by
+// another process. It acquires the lock, prints `locked`, and releases the
+// lock when its standard input is closed.
+func TestHoldLockHelper(t *testing.T) {
+	dir := os.Getenv(holdLockEnv)
+	if dir == "" {
+		return
+	}
+	lock, err := AcquireLock(context.Background(), dir, false)
+	if err != nil {
+		fmt.Println(err)
+		t.Fatal(err)
+	}
+	fmt.Println("locked")
+	if _, err := io.Copy(io.Discard, os.Stdin); err != nil {
+		t.Error(err)
+	}
+	if err := lock.Release(); err != nil {
+		t.Fatal(err)
+	}
+}
+
+// holdLock locks the output directory `dir` from a child process. The lock is
+// released when the returned function is called (or at the end of the test).
+func holdLock(t *testing.T, dir string) (pid int, release func()) {
+	t.Helper()
+	holder := startLockHolder(t, dir)
+	if line := holder.result(t); line != "locked" {
+		t.Fatalf("child process didn't lock `%s`: %q", dir, line)
+	}
+	return holder.pid, func() {
+		if err := holder.release(); err != nil {
+			t.Error(err)
+		}
+	}
+}
+
+// lockHolder is a child process which tries to lock an output directory (see
+// `TestHoldLockHelper`).
+type lockHolder struct {
+	pid    int
+	stdout *bufio.Reader
+
+	// release closes the child's standard input, releasing the lock, and
+	// waits for it to exit.
+	release func() error
+}
+
+// startLockHolder starts a child process which tries to lock the output
+// directory `dir`. The child is released at the end of the test.
+func startLockHolder(t *testing.T, dir string) *lockHolder {
+	t.Helper()
+	cmd := exec.Command(os.Args[0], "-test.run=^TestHoldLockHelper$")
+	cmd.Env = append(os.Environ(), holdLockEnv+"="+dir)
+	stdin, err := cmd.StdinPipe()
+	if err != nil {
+		t.Fatal(err)
+	}
+	stdout, err := cmd.StdoutPipe()
+	if err != nil {
+		t.Fatal(err)
+	}
+	if err := cmd.Start(); err != nil {
+		t.Fatal(err)
+	}
+
+	var released bool
+	holder := &lockHolder{pid: cmd.Process.Pid, stdout: bufio.NewReader(stdout)}
+	holder.release = func() error {
+		if released {
+			return nil
+		}
+		released = true
+		if err := stdin.Close(); err != nil {
+			return err
+		}
+		// drain the output so the child never blocks writing it
+		rest, _ := io.ReadAll(holder.stdout)
+		if err := cmd.Wait(); err != nil {
+			return fmt.Errorf("lock holder: %w\n%s", err, rest)
+		}
+		return nil
+	}
+	t.Cleanup(func() { _ = holder.release() })
+	return holder
+}
+
+// result waits for the child to report whether it acquired the lock: it
+// returns `locked`, or the error which kept the child from acquiring it.
+func (holder *lockHolder) result(t *testing.T) string {
+	t.Helper()
+	line, err := holder.stdout.ReadString('\n')
+	if err != nil && line == "" {
+		t.Fatalf("reading from the lock holder: %v", err)
+	}
+	return strings.TrimSpace(line)
+}
+
+func TestAcquireLockHeld(t *testing.T) {
+	dir := filepath.Join(t.TempDir(), "_output")
+	pid, _ := holdLock(t, dir)
+
+	_, err := AcquireLock(context.Background(), dir, false)
+	var locked *OutputLockedError
+	if !errors.As(err, &locked) {
+		t.Fatalf("wanted an `*OutputLockedError`; found `%v`", err)
+	}
+	if locked.PID != pid {
+		t.Errorf("wanted the lock holder's pid %d; found %d", pid, locked.PID)
+	}
+	if locked.Path != lockPath(dir) {
+		t.Errorf("wanted lock `%s`; found `%s`", lockPath(dir), locked.Path)
+	}
+}
+
+func TestAcquireLockWait(t *testing.T) {
+	dir := filepath.Join(t.TempDir(), "_output")
+	_, release := holdLock(t, dir)
+
+	type result struct {
+		lock *Lock
+		err  error
+	}
+	acquired := make(chan result, 1)
+	ctx, cancel := context.WithTimeout(context.Background(), time.Minute)
+	defer cancel()
+	go func() {
+		lock, err := AcquireLock(ctx, dir, true)
+		acquired <- result{lock, err}
+	}()
+
+	select {
+	case <-acquired:
+		t.Fatal("acquired the lock while another process held it")
+	case <-time.After(2 * lockPollInterval):
+	}
+
+	release()
+	select {
+	case r := <-acquired:
+		if r.err != nil {
+			t.Fatalf("acquiring the released lock: %v", r.err)
+		}
+		if err := r.lock.Release(); err != nil {
+			t.Fatal(err)
+		}
+	case <-time.After(10 * time.Second):
+		t.Fatal("still waiting for the lock after it was released")
+	}
+}
+
+func TestAcquireLockStale(t *testing.T) {
+	// a child process which has exited leaves behind a dead pid
+	cmd := exec.Command(os.Args[0], "-test.run=^$")
+	if err := cmd.Run(); err != nil {
+		t.Fatal(err)
+	}
+	dead := cmd.Process.Pid
+
+	dir := filepath.Join(t.TempDir(), "_output")
+	if err := os.WriteFile(
+		lockPath(dir),
+		fmt.Appendf(nil, "%d\n2024-01-01T00:00:00Z\n", dead),
+		0644,
+	); err != nil {
+		t.Fatal(err)
+	}
+
+	lock, err := AcquireLock(context.Background(), dir, false)
+	if err != nil {
+		t.Fatalf("wanted the stale lock to be reclaimed; found `%v`", err)
+	}
+	defer func() {
+		if err := lock.Release(); err != nil {
+			t.Error(err)
+		}
+	}()
+	data, err := os.ReadFile(lockPath(dir))
+	if err != nil {
+		t.Fatal(err)
+	}
+	pid, _, _ := strings.Cut(string(data), "\n")
+	if pid != strconv.Itoa(os.Getpid()) {
+		t.Errorf(
+			"wanted the lock to hold this process's pid %d; found %q",
+			os.Getpid(),
+			data,
+		)
+	}
+}
+
+func TestAcquireLockStaleContended(t *testing.T) {
+	// a child process which has exited leaves behind a dead pid
+	cmd := exec.Command(os.Args[0], "-test.run=^$")
+	if err := cmd.Run(); err != nil {
+		t.Fatal(err)
+	}
+	dead := cmd.Process.Pid
+
+	// a race doesn't show itself every time
+	for i := range 5 {
+		dir := filepath.Join(t.TempDir(), "_output")
+		if err := os.WriteFile(
+			lockPath(dir),
+			fmt.Appendf(nil, "%d\n2024-01-01T00:00:00Z\n", dead),
+			0644,
+		); err != nil {
+			t.Fatal(err)
+		}
+
+		// both children find the stale lock, but only one takes it over,
+		// and the other finds it held by the first
+		a, b := startLockHolder(t, dir), startLockHolder(t, dir)
+		aLine, bLine := a.result(t), b.result(t)
+		if bLine == "locked" {
+			a, b = b, a
+			aLine, bLine = bLine, aLine
+		}
+		if aLine != "locked" {
+			t.Fatalf(
+				"round %d: wanted a child to lock `%s`; found %q",
+				i,
+				dir,
+				aLine,
+			)
+		}
+		wanted := fmt.Sprintf("pid %d", a.pid)
+		if !strings.Contains(bLine, wanted) {
+			t.Errorf(
+				"round %d: wanted the other child to find the lock held by "+
+					"%s; found %q",
+				i,
+				wanted,
+				bLine,
+			)
+		}
+		if err := a.release(); err != nil {
+			t.Error(err)
+		}
+		// the child which didn't get the lock exits with an error
+		_ = b.release()
+		checkStagingRemoved(t, filepath.Dir(dir))
+	}
+}
+
+func TestAcquireLockUnparsable(t *testing.T) {
+	for _, contents := range []string{"", "not a pid\n"} {
+		dir := filepath.Join(t.TempDir(), "_output")
+		if err := os.WriteFile(
+			lockPath(dir),
+			[]byte(contents),
+			0644,
+		); err != nil {
+			t.Fatal(err)
+		}
+
+		_, err := AcquireLock(context.Background(), dir, false)
+		var locked *OutputLockedError
+		if !errors.As(err, &locked) {
+			t.Fatalf(
+				"%q: wanted an `*OutputLockedError`; found `%v`",
+				contents,
+				err,
+			)
+		}
+		if locked.PID != 0 {
+			t.Errorf("%q: wanted no pid; found %d", contents, locked.PID)
+		}
+		if data, err := os.ReadFile(lockPath(dir)); err != nil ||
+			string(data) != contents {
+			t.Errorf("%q: wanted the lock untouched; found %q", contents, data)
+		}
+	}
+}
@@ -0,0 +1,21 @@
+//go:build !windows
+
+package futhorc
+
+import (
+	"errors"
+	"os"
+	"syscall"
+)
+
+// processAlive reports whether the process with ID `pid` exists.
+func processAlive(pid int) bool {
+	process, err := os.FindProcess(pid)
+	if err != nil {
+		return false
+	}
+	// signal 0 checks for the process without signaling it; EPERM means the
+	// process exists but belongs to someone else
+	err = process.Signal(syscall.Signal(0))
+	return err == nil || errors.Is(err, syscall.EPERM)
+}
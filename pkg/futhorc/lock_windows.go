@@ -0,0 +1,14 @@
+package futhorc
+
+import "os"
+
+// processAlive reports whether the process with ID `pid` exists. On Windows,
+// finding a process opens a handle to it, which fails if it doesn't exist.
+func processAlive(pid int) bool {
+	process, err := os.FindProcess(pid)
+	if err != nil {
+		return false
+	}
+	_ = process.Release()
+	return true
+}
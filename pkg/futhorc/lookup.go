@@ -0,0 +1,135 @@
+package futhorc
+
+import (
+	"errors"
+	"fmt"
+	"path"
+	"slices"
+	"strings"
+)
+
+// PostLookup resolves references to posts by ID, slug, or title. A post's ID
+// is its path relative to the posts directory without the extension (e.g.,
+// `2024/weeknotes`), its slug is the last element of its ID, and titles are
+// compared case-insensitively. Slugs and titles needn't be unique.
+type PostLookup struct {
+	byID    map[string]*OrderedPage[Post]
+	bySlug  map[string][]*OrderedPage[Post]
+	byTitle map[string][]*OrderedPage[Post]
+}
+
+func NewPostLookup(posts []OrderedPage[Post]) *PostLookup {
+	lookup := PostLookup{
+		byID:    make(map[string]*OrderedPage[Post], len(posts)),
+		bySlug:  make(map[string][]*OrderedPage[Post], len(posts)),
+		byTitle: make(map[string][]*OrderedPage[Post], len(posts)),
+	}
+	for i := range posts {
+		p := &posts[i]
+		id := PostID(&p.Content)
+		lookup.byID[id] = p
+		slug := path.Base(id)
+		lookup.bySlug[slug] = append(lookup.bySlug[slug], p)
+		title := titleKey(p.Content.Title)
+		lookup.byTitle[title] = append(lookup.byTitle[title], p)
+	}
+	return &lookup
+}
+
+// PostID returns the ID of a converted post.
+func PostID(p *Post) string {
+	return strings.TrimSuffix(p.Path, htmlSuffix)
+}
+
+func titleKey(title string) string {
+	return strings.ToLower(strings.TrimSpace(title))
+}
+
+// Resolve finds the post referred to by `ref`, trying it as an ID, then as a
+// slug, and finally as a title. A slug or title shared by several posts is an
+// `*AmbiguousPostError`.
+func (lookup *PostLookup) Resolve(ref string) (*OrderedPage[Post], error) {
+	if p, found := lookup.byID[ref]; found {
+		return p, nil
+	}
+	if posts, found := lookup.bySlug[ref]; found {
+		return unique(ref, "slug", posts)
+	}
+	return lookup.ByTitle(ref)
+}
+
+// ByID finds the post with the ID `id`.
+func (lookup *PostLookup) ByID(id string) (*OrderedPage[Post], bool) {
+	p, found := lookup.byID[id]
+	return p, found
+}
+
+// ByTitle finds the post titled `title`, returning an `*AmbiguousPostError`
+// if several posts share the title.
+func (lookup *PostLookup) ByTitle(title string) (*OrderedPage[Post], error) {
+	posts, found := lookup.byTitle[titleKey(title)]
+	if !found {
+		return nil, fmt.Errorf(
+			"resolving post `%s`: %w",
+			title,
+			ErrPostNotFound,
+		)
+	}
+	return unique(title, "title", posts)
+}
+
+func unique(
+	ref string,
+	kind string,
+	posts []*OrderedPage[Post],
+) (*OrderedPage[Post], error) {
+	if len(posts) == 1 {
+		return posts[0], nil
+	}
+	return nil, &AmbiguousPostError{Ref: ref, Kind: kind, IDs: postIDs(posts)}
+}
+
+// DuplicateTitles returns the IDs of the posts which share each title used by
+// more than one post, keyed by title.
+func (lookup *PostLookup) DuplicateTitles() map[string][]string {
+	duplicates := map[string][]string{}
+	for _, posts := range lookup.byTitle {
+		if len(posts) > 1 {
+			duplicates[posts[0].Content.Title] = postIDs(posts)
+		}
+	}
+	return duplicates
+}
+
+func postIDs(posts []*OrderedPage[Post]) []string {
+	ids := make([]string, len(posts))
+	for i := range posts {
+		ids[i] = PostID(&posts[i].Content)
+	}
+	slices.Sort(ids)
+	return ids
+}
+
+// AmbiguousPostError is returned when a reference matches several posts.
+type AmbiguousPostError struct {
+	Ref string
+
+	// Kind is what the reference matched (`slug` or `title`).
+	Kind string
+
+	// IDs lists the IDs of the matching posts, which are unambiguous.
+	IDs []string
+}
+
+func (err *AmbiguousPostError) Error() string {
+	return fmt.Sprintf(
+		"resolving post `%s`: %d posts have this %s; use one of their IDs "+
+			"instead: %s",
+		err.Ref,
+		len(err.IDs),
+		err.Kind,
+		strings.Join(err.IDs, ", "),
+	)
+}
+
+var ErrPostNotFound = errors.New("post not found")
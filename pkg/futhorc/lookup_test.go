@@ -0,0 +1,84 @@
+package futhorc
+
+import (
+	"errors"
+	"maps"
+	"slices"
+	"testing"
+)
+
+func TestPostLookup(t *testing.T) {
+	posts := make([]OrderedPage[Post], 0, 4)
+	for _, p := range []struct{ path, title string }{
+		{"2024/weeknotes.html", "Weeknotes"},
+		{"2025/weeknotes.html", "weeknotes "},
+		{"hello.html", "Hello, World"},
+		{"drafts/hello.html", "Hello again"},
+	} {
+		var page OrderedPage[Post]
+		page.Content.Path, page.Content.Title = p.path, p.title
+		posts = append(posts, page)
+	}
+	lookup := NewPostLookup(posts)
+
+	for _, tc := range []struct {
+		name string
+		ref  string
+
+		// id is the ID of the resolved post, if it resolves
+		id string
+
+		// ambiguous lists the IDs of the matching posts, if it's ambiguous
+		ambiguous []string
+	}{
+		{name: "unique-title", ref: "hello, world", id: "hello"},
+		{
+			name:      "ambiguous-title",
+			ref:       "Weeknotes",
+			ambiguous: []string{"2024/weeknotes", "2025/weeknotes"},
+		},
+		{name: "id", ref: "2025/weeknotes", id: "2025/weeknotes"},
+		{
+			name:      "ambiguous-slug",
+			ref:       "hello",
+			ambiguous: []string{"drafts/hello", "hello"},
+		},
+		{name: "id-before-slug", ref: "drafts/hello", id: "drafts/hello"},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			p, err := lookup.Resolve(tc.ref)
+			if tc.ambiguous != nil {
+				var ambiguous *AmbiguousPostError
+				if !errors.As(err, &ambiguous) {
+					t.Fatalf("wanted `*AmbiguousPostError`; found `%v`", err)
+				}
+				if !slices.Equal(ambiguous.IDs, tc.ambiguous) {
+					t.Errorf(
+						"wanted the suggestions %v; found %v",
+						tc.ambiguous,
+						ambiguous.IDs,
+					)
+				}
+				return
+			}
+			if err != nil {
+				t.Fatal(err)
+			}
+			if id := PostID(&p.Content); id != tc.id {
+				t.Errorf("wanted the post `%s`; found `%s`", tc.id, id)
+			}
+		})
+	}
+
+	if _, err := lookup.Resolve("missing"); !errors.Is(err, ErrPostNotFound) {
+		t.Errorf("wanted `ErrPostNotFound`; found `%v`", err)
+	}
+
+	wanted := map[string][]string{
+		"Weeknotes": {"2024/weeknotes", "2025/weeknotes"},
+	}
+	found := lookup.DuplicateTitles()
+	if !maps.EqualFunc(found, wanted, slices.Equal[[]string]) {
+		t.Errorf("wanted the duplicate titles %v; found %v", wanted, found)
+	}
+}
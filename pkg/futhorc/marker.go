@@ -0,0 +1,70 @@
+package futhorc
+
+import (
+	"errors"
+	"fmt"
+	"io/fs"
+	"os"
+	"path/filepath"
+	"runtime/debug"
+
+	"github.com/go-git/go-billy/v5"
+)
+
+// UnmarkedOutputError is returned when the output directory isn't empty but
+// lacks the `.futhorc` marker which every successful build writes, i.e.,
+// futhorc didn't create it and building into it could overwrite unrelated
+// files.
+type UnmarkedOutputError struct {
+	Path string
+}
+
+func (err *UnmarkedOutputError) Error() string {
+	return fmt.Sprintf(
+		"refusing to build into `%s`: the directory isn't empty and has no "+
+			"`%s` marker, so it may not be a futhorc output directory; pass "+
+			"`-force` to build into it anyway",
+		err.Path,
+		markerFile,
+	)
+}
+
+// checkOutputMarker returns an `UnmarkedOutputError` if the output directory
+// at `dir` is neither missing, empty, nor marked.
+func checkOutputMarker(dir string) error {
+	entries, err := os.ReadDir(dir)
+	if err != nil {
+		if errors.Is(err, fs.ErrNotExist) {
+			return nil
+		}
+		return fmt.Errorf("checking output directory: %w", err)
+	}
+	if len(entries) < 1 {
+		return nil
+	}
+	for _, entry := range entries {
+		if entry.Name() == markerFile {
+			return nil
+		}
+	}
+	return &UnmarkedOutputError{Path: filepath.Clean(dir)}
+}
+
+// writeOutputMarker marks `output` as a futhorc output directory. The marker
+// records the project directory and the futhorc version for diagnostics.
+func writeOutputMarker(output billy.Filesystem, projectDir string) error {
+	version := "unknown"
+	if info, ok := debug.ReadBuildInfo(); ok {
+		version = info.Main.Version
+	}
+	if _, err := writeFile(
+		output,
+		markerFile,
+		fmt.Appendf(nil, "project: %s\nversion: %s\n", projectDir, version),
+	); err != nil {
+		return fmt.Errorf("marking output directory: %w", err)
+	}
+	return nil
+}
+
+const markerFile = ".futhorc"
@@ -0,0 +1,98 @@
+package futhorc
+
+import (
+	"errors"
+	"io/fs"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+func TestCheckOutputMarker(t *testing.T) {
+	for _, tc := range []struct {
+		name string
+
+		// files are written to the output directory, which isn't created if
+		// it's nil
+		files    map[string]string
+		unmarked bool
+	}{
+		{name: "missing"},
+		{name: "empty", files: map[string]string{}},
+		{
+			name: "marked",
+			files: map[string]string{
+				markerFile:   "project: /blog\n",
+				"index.html": "index",
+			},
+		},
+		{
+			name:     "unmarked",
+			files:    map[string]string{"index.html": "index"},
+			unmarked: true,
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			dir := filepath.Join(t.TempDir(), "_output")
+			if tc.files != nil {
+				if err := os.Mkdir(dir, 0755); err != nil {
+					t.Fatal(err)
+				}
+				writeFiles(t, dir, tc.files)
+			}
+
+			err := checkOutputMarker(dir)
+			if !tc.unmarked {
+				if err != nil {
+					t.Fatalf("unexpected error: %v", err)
+				}
+				return
+			}
+			var unmarked *UnmarkedOutputError
+			if !errors.As(err, &unmarked) {
+				t.Fatalf("wanted an `*UnmarkedOutputError`; found `%v`", err)
+			}
+			if unmarked.Path != dir {
+				t.Errorf("wanted the path `%s`; found `%s`", dir, unmarked.Path)
+			}
+			if !strings.Contains(err.Error(), "`"+markerFile+"` marker") {
+				t.Errorf("wanted the error to name the marker: %v", err)
+			}
+		})
+	}
+}
+
+func TestRunStagedForce(t *testing.T) {
+	dir := writeProject(t, testTheme, map[string]string{
+		"posts/a.md":           "---\nTitle: A\nDate: 2024-01-01\n---\n",
+		"_output/unrelated.md": "not built by futhorc",
+	})
+	output := filepath.Join(dir, "_output")
+
+	var unmarked *UnmarkedOutputError
+	if err := buildStaged(t, dir, nil); !errors.As(err, &unmarked) {
+		t.Fatalf("wanted an `*UnmarkedOutputError`; found `%v`", err)
+	}
+	if _, err := os.Stat(
+		filepath.Join(output, "index.html"),
+	); !errors.Is(err, fs.ErrNotExist) {
+		t.Errorf("wanted nothing to be built: %v", err)
+	}
+
+	if err := buildStaged(t, dir, func(pipeline *Pipeline) {
+		pipeline.Force = true
+	}); err != nil {
+		t.Fatalf("wanted `Force` to build anyway: %v", err)
+	}
+	for _, p := range []string{"index.html", markerFile} {
+		if _, err := os.Stat(filepath.Join(output, p)); err != nil {
+			t.Errorf("wanted `%s` in the output: %v", p, err)
+		}
+	}
+
+	// the directory is marked now, so it's built into without `Force`
+	if err := buildStaged(t, dir, nil); err != nil {
+		t.Fatalf("rebuilding the marked output directory: %v", err)
+	}
+}
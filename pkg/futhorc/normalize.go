@@ -0,0 +1,151 @@
+package futhorc
+
+import (
+	"bytes"
+	"fmt"
+)
+
+// Normalization selects the whitespace normalization applied to rendered
+// pages before they're written to the output directory.
+type Normalization string
+
+const (
+	// NormalizeOff writes pages exactly as the templates render them.
+	NormalizeOff Normalization = "off"
+
+	// NormalizeTrailingNewline guarantees that each page ends with exactly
+	// one newline.
+	NormalizeTrailingNewline Normalization = "trailing-newline"
+
+	// NormalizeCollapseBlankLines implies NormalizeTrailingNewline and also
+	// collapses runs of three or more blank lines into a single blank line.
+	// Content inside `<pre>` and `<textarea>` elements is never modified.
+	NormalizeCollapseBlankLines Normalization = "collapse-blank-lines"
+)
+
+func ParseNormalization(s string) (Normalization, error) {
+	switch n := Normalization(s); n {
+	case "":
+		return NormalizeOff, nil
+	case NormalizeOff, NormalizeTrailingNewline, NormalizeCollapseBlankLines:
+		return n, nil
+	default:
+		return "", fmt.Errorf(
+			"invalid output normalization `%s`: expected one of `%s`, `%s`, "+
+				"or `%s`",
+			s,
+			NormalizeOff,
+			NormalizeTrailingNewline,
+			NormalizeCollapseBlankLines,
+		)
+	}
+}
+
+// Normalize applies the normalization to a rendered page.
+func (n Normalization) Normalize(page []byte) []byte {
+	switch n {
+	case NormalizeTrailingNewline:
+		return trailingNewline(page)
+	case NormalizeCollapseBlankLines:
+		return trailingNewline(collapseBlankLines(page))
+	default:
+		return page
+	}
+}
+
+func trailingNewline(page []byte) []byte {
+	page = bytes.TrimRight(page, "\r\n")
+	return append(page, '\n')
+}
+
+func collapseBlankLines(page []byte) []byte {
+	out := make([]byte, 0, len(page))
+	var scanner rawTextScanner
+	var blanks [][]byte
+	flush := func() {
+		if len(blanks) >= 3 {
+			out = append(out, '\n')
+		} else {
+			for _, blank := range blanks {
+				out = append(out, blank...)
+			}
+		}
+		blanks = blanks[:0]
+	}
+
+	for len(page) > 0 {
+		var line []byte
+		if i := bytes.IndexByte(page, '\n'); i >= 0 {
+			line, page = page[:i+1], page[i+1:]
+		} else {
+			line, page = page, nil
+		}
+
+		// a blank line can't open or close a raw text element, so if we're
+		// outside of one at the start of the line then the line is eligible
+		// for collapsing.
+		if !scanner.inRawText() && len(bytes.TrimSpace(line)) < 1 {
+			blanks = append(blanks, line)
+			continue
+		}
+		flush()
+		out = append(out, line...)
+		scanner.scan(line)
+	}
+	flush()
+	return out
+}
+
+// rawTextScanner tracks whether a stream of HTML is inside of an element whose
+// whitespace is significant (`<pre>` and `<textarea>`). It only needs to
+// recognize the opening and closing tags of those elements.
+type rawTextScanner struct {
+	closer []byte
+}
+
+func (s *rawTextScanner) inRawText() bool { return s.closer != nil }
+
+func (s *rawTextScanner) scan(data []byte) {
+	for {
+		i := bytes.IndexByte(data, '<')
+		if i < 0 {
+			return
+		}
+		data = data[i:]
+		if s.closer != nil {
+			if hasTagPrefix(data, s.closer) {
+				s.closer = nil
+			}
+		} else {
+			for _, tag := range rawTextTags {
+				if hasTagPrefix(data, tag.opener) {
+					s.closer = tag.closer
+					break
+				}
+			}
+		}
+		data = data[1:]
+	}
+}
+
+// hasTagPrefix reports whether `data` begins with `prefix` (compared
+// case-insensitively) followed by the end of the tag name.
+func hasTagPrefix(data, prefix []byte) bool {
+	if len(data) < len(prefix) || !bytes.EqualFold(data[:len(prefix)], prefix) {
+		return false
+	}
+	if len(data) == len(prefix) {
+		return true
+	}
+	switch data[len(prefix)] {
+	case '>', '/', ' ', '\t', '\r', '\n', '\f':
+		return true
+	default:
+		return false
+	}
+}
+
+var rawTextTags = []struct{ opener, closer []byte }{
+	{opener: []byte("<pre"), closer: []byte("</pre")},
+	{opener: []byte("<textarea"), closer: []byte("</textarea")},
+}
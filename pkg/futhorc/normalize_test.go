@@ -0,0 +1,80 @@
+package futhorc
+
+import "testing"
+
+func TestNormalize(t *testing.T) {
+	for _, tc := range []struct {
+		name          string
+		normalization Normalization
+		page          string
+		wanted        string
+	}{
+		{
+			name:          "off",
+			normalization: NormalizeOff,
+			page:          "<p>a</p>\n\n\n\n<p>b</p>",
+			wanted:        "<p>a</p>\n\n\n\n<p>b</p>",
+		},
+		{
+			name:          "missing-trailing-newline",
+			normalization: NormalizeTrailingNewline,
+			page:          "<p>a</p>",
+			wanted:        "<p>a</p>\n",
+		},
+		{
+			name:          "extra-trailing-newlines",
+			normalization: NormalizeTrailingNewline,
+			page:          "<p>a</p>\r\n\n\n",
+			wanted:        "<p>a</p>\n",
+		},
+		{
+			name:          "trailing-newline-keeps-blank-lines",
+			normalization: NormalizeTrailingNewline,
+			page:          "<p>a</p>\n\n\n\n<p>b</p>\n",
+			wanted:        "<p>a</p>\n\n\n\n<p>b</p>\n",
+		},
+		{
+			name:          "collapse",
+			normalization: NormalizeCollapseBlankLines,
+			page:          "<p>a</p>\n\n \n\t\n<p>b</p>",
+			wanted:        "<p>a</p>\n\n<p>b</p>\n",
+		},
+		{
+			name:          "two-blank-lines",
+			normalization: NormalizeCollapseBlankLines,
+			page:          "<p>a</p>\n\n\n<p>b</p>\n",
+			wanted:        "<p>a</p>\n\n\n<p>b</p>\n",
+		},
+		{
+			name:          "pre",
+			normalization: NormalizeCollapseBlankLines,
+			page:          "<pre>a\n\n\n\nb</pre>\n\n\n\n<p>c</p>\n",
+			wanted:        "<pre>a\n\n\n\nb</pre>\n\n<p>c</p>\n",
+		},
+		{
+			name:          "pre-attributes-and-case",
+			normalization: NormalizeCollapseBlankLines,
+			page:          "<PRE class=\"x\">\n\n\n\n</Pre>\n",
+			wanted:        "<PRE class=\"x\">\n\n\n\n</Pre>\n",
+		},
+		{
+			name:          "textarea",
+			normalization: NormalizeCollapseBlankLines,
+			page:          "<textarea>\n\n\n\n</textarea>\n\n\n\n",
+			wanted:        "<textarea>\n\n\n\n</textarea>\n",
+		},
+		{
+			name:          "not-pre",
+			normalization: NormalizeCollapseBlankLines,
+			page:          "<preview>\n\n\n\n</preview>\n",
+			wanted:        "<preview>\n\n</preview>\n",
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			found := string(tc.normalization.Normalize([]byte(tc.page)))
+			if found != tc.wanted {
+				t.Errorf("wanted %q; found %q", tc.wanted, found)
+			}
+		})
+	}
+}
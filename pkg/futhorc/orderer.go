@@ -13,9 +13,13 @@ type Orderer[T any] struct {
 	OrderedPageSlices chan []OrderedPage[T]
 }
 
+// NewOrderer creates an orderer. Once every page has been received and
+// ordered, `preflight` (if non-nil) can inspect the complete set and abort the
+// pipeline before any of the pages are sent on.
 func NewOrderer[T any](
 	name string,
 	pages <-chan Page[T],
+	preflight func([]OrderedPage[T]) error,
 ) (orderer Orderer[T]) {
 	var orderedPages []OrderedPage[T]
 	orderer.OrderedPages = make(chan *OrderedPage[T])
@@ -33,6 +37,11 @@ func NewOrderer[T any](
 		},
 		func(ctx context.Context) error {
 			OrderPages(orderedPages)
+			if preflight != nil {
+				if err := preflight(orderedPages); err != nil {
+					return err
+				}
+			}
 
 			orderer.OrderedPageSlices <- orderedPages
 
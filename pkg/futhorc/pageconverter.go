@@ -2,25 +2,63 @@ package futhorc
 
 import (
 	"net/url"
+	"path"
 	"path/filepath"
+	"strings"
 )
 
 type PageConverter[T any] struct {
 	BaseURL   *url.URL
 	Directory string
+
+	// PrettyURLs drops the trailing `index.html` from the URLs of pages
+	// written to `index.html` files (e.g., `/posts/foo/` rather than
+	// `/posts/foo/index.html`).
+	PrettyURLs bool
 }
 
+// Convert creates the page for `content` at `source`, a path relative to the
+// converter's directory. Page paths are slash-separated whatever the platform,
+// since they double as URL paths.
 func (converter *PageConverter[T]) Convert(
-	path string,
+	source string,
 	order int64,
 	content T,
 ) (p Page[T], err error) {
 	p.Content = content
 	p.Order = order
-	p.Path = filepath.Join(converter.Directory, path)
+	p.Path = path.Join(
+		filepath.ToSlash(converter.Directory),
+		filepath.ToSlash(source),
+	)
 	if p.URL, err = url.Parse(p.Path); err != nil {
 		return
 	}
 	p.URL = converter.BaseURL.ResolveReference(p.URL)
+	if converter.PrettyURLs {
+		p.URL = prettyURL(p.URL)
+	}
 	return
 }
+
+// prettyURL drops the trailing `index.html` from `u`, leaving the trailing
+// slash.
+func prettyURL(u *url.URL) *url.URL {
+	if path.Base(u.Path) != indexFile {
+		return u
+	}
+	pretty := *u
+	pretty.Path = strings.TrimSuffix(u.Path, indexFile)
+	pretty.RawPath = ""
+	return &pretty
+}
+
+// indexURL returns the URL of the first page of the index with ID `id`.
+func indexURL(base *url.URL, id string, pretty bool) *url.URL {
+	if pretty {
+		return base.JoinPath(id + "/")
+	}
+	return base.JoinPath(id, indexFile)
+}
+
+const indexFile = "index.html"
@@ -0,0 +1,122 @@
+package futhorc
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+// TestPrettyURLs builds the same project with and without pretty URLs and
+// checks that the pages' paths, their URLs, the links between them, and the
+// feed all agree.
+func TestPrettyURLs(t *testing.T) {
+	files := map[string]string{
+		"theme/post.html": "{{ .Content.URL }} " +
+			"prev={{ with .Prev }}{{ . }}{{ end }} " +
+			"next={{ with .Next }}{{ . }}{{ end }}\n{{ .Content.Body }}",
+		"theme/index.html": "{{ range .Content.Posts }}{{ .URL }} {{ end }}" +
+			"next={{ with .Content.NextPage }}{{ .URL }}{{ end }}",
+		"posts/trip/index.md": "---\nTitle: Trip\nDate: 2024-02-01\n---\n",
+		"posts/post-01.md": "---\nTitle: Post 1\nDate: 2024-01-02\n---\n\n" +
+			"[older](post-00.md) [trip](trip/index.md)\n",
+	}
+	for i := range 11 {
+		if i == 1 {
+			continue
+		}
+		files[fmt.Sprintf("posts/post-%02d.md", i)] = fmt.Sprintf(
+			"---\nTitle: Post %d\nDate: 2024-01-%02d\n---\n",
+			i,
+			i+1,
+		)
+	}
+
+	// the 12 posts fill the first page of the index, leaving the oldest two
+	// for the second
+	const root = goldenSiteRoot
+	for _, tc := range []struct {
+		name   string
+		pretty bool
+		wanted map[string][]string
+	}{
+		{
+			name: "plain",
+			wanted: map[string][]string{
+				"posts/post-01.html": {
+					root + "posts/post-01.html " +
+						"prev=" + root + "posts/post-00.html " +
+						"next=" + root + "posts/post-02.html\n",
+					`href="` + root + `posts/post-00.html"`,
+					`href="` + root + `posts/trip/index.html"`,
+				},
+				"posts/trip/index.html": {
+					root + "posts/trip/index.html " +
+						"prev=" + root + "posts/post-10.html next=\n",
+				},
+				"index.html": {
+					root + "posts/trip/index.html ",
+					"next=" + root + "page-001.html",
+				},
+				"page-001.html": {
+					root + "posts/post-01.html " +
+						root + "posts/post-00.html next=",
+				},
+				"index.json": {
+					`"` + root + `posts/post-01.html"`,
+					`"` + root + `posts/trip/index.html"`,
+				},
+			},
+		},
+		{
+			name:   "pretty",
+			pretty: true,
+			wanted: map[string][]string{
+				"posts/post-01/index.html": {
+					root + "posts/post-01/ " +
+						"prev=" + root + "posts/post-00/ " +
+						"next=" + root + "posts/post-02/\n",
+					`href="` + root + `posts/post-00/"`,
+					`href="` + root + `posts/trip/"`,
+				},
+				"posts/trip/index.html": {
+					root + "posts/trip/ " +
+						"prev=" + root + "posts/post-10/ next=\n",
+				},
+				"index.html": {
+					root + "posts/trip/ ",
+					"next=" + root + "page-001/",
+				},
+				"page-001/index.html": {
+					root + "posts/post-01/ " +
+						root + "posts/post-00/ next=",
+				},
+				"index.json": {
+					`"` + root + `posts/post-01/"`,
+					`"` + root + `posts/trip/"`,
+				},
+			},
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			files["futhorc.jsonc"] = fmt.Sprintf(
+				`{"prettyURLs": %t, "feeds": ["json"]}`,
+				tc.pretty,
+			)
+			output := buildOnDisk(t, writeProject(t, testTheme, files))
+			for p, wanted := range tc.wanted {
+				data, err := os.ReadFile(filepath.Join(output, p))
+				if err != nil {
+					t.Errorf("wanted output file `%s`: %v", p, err)
+					continue
+				}
+				for _, w := range wanted {
+					if !strings.Contains(string(data), w) {
+						t.Errorf("`%s`: wanted %q in %q", p, w, data)
+					}
+				}
+			}
+		})
+	}
+}
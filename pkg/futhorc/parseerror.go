@@ -0,0 +1,115 @@
+package futhorc
+
+import (
+	"errors"
+	"fmt"
+	"regexp"
+	"strconv"
+
+	"gopkg.in/yaml.v3"
+)
+
+// ParsePostError is returned when a post file can't be parsed. Its message is
+// formatted as `path:line:column: message` (e.g., `foo.md:4:7: missing field
+// ...`) so editors can jump to the problem; the line and column are omitted
+// when they're unknown.
+type ParsePostError struct {
+	// Path is the source path of the post.
+	Path string
+
+	// Line and Column locate the problem in the post file. They're 1-based,
+	// and 0 if unknown (YAML syntax errors have a line but no column).
+	Line   int
+	Column int
+
+	Err error
+}
+
+func newParsePostError(path string, err error) *ParsePostError {
+	line, column, err := locate(err)
+	return &ParsePostError{Path: path, Line: line, Column: column, Err: err}
+}
+
+func (err *ParsePostError) Error() string {
+	switch {
+	case err.Line < 1:
+		return fmt.Sprintf("%s: %v", err.Path, err.Err)
+	case err.Column < 1:
+		return fmt.Sprintf("%s:%d: %v", err.Path, err.Line, err.Err)
+	}
+	return fmt.Sprintf("%s:%d:%d: %v", err.Path, err.Line, err.Column, err.Err)
+}
+
+func (err *ParsePostError) Unwrap() error { return err.Err }
+
+// positioner is implemented by errors which know their position in the post
+// file.
+type positioner interface {
+	position() (line, column int)
+}
+
+// frontmatterError is an error at a position in the post file.
+type frontmatterError struct {
+	Line   int
+	Column int
+	Err    error
+}
+
+// newFrontmatterError returns an error positioned at `node`.
+func newFrontmatterError(node *yaml.Node, err error) *frontmatterError {
+	return &frontmatterError{
+		Line:   frontmatterLine(node),
+		Column: node.Column,
+		Err:    err,
+	}
+}
+
+func (err *frontmatterError) Error() string { return err.Err.Error() }
+
+func (err *frontmatterError) Unwrap() error { return err.Err }
+
+func (err *frontmatterError) position() (line, column int) {
+	return err.Line, err.Column
+}
+
+// locate returns the position of `err` in the post file along with the error
+// to report there. The YAML decoder only reports lines, which are relative to
+// the frontmatter and are embedded in its messages, so they're parsed out and
+// translated; errors from the frontmatter's own types carry their positions.
+func locate(err error) (line, column int, located error) {
+	var p positioner
+	if errors.As(err, &p) {
+		line, column = p.position()
+		return line, column, err
+	}
+
+	var typeErr *yaml.TypeError
+	if errors.As(err, &typeErr) {
+		// with several errors, each keeps its own line in the message
+		if len(typeErr.Errors) != 1 {
+			return 0, 0, err
+		}
+		if n, msg, ok := parseYAMLLine(typeErr.Errors[0]); ok {
+			return n + 1, 0, errors.New(msg)
+		}
+		return 0, 0, err
+	}
+
+	if n, msg, ok := parseYAMLLine(err.Error()); ok {
+		return n + 1, 0, errors.New(msg)
+	}
+	return 0, 0, err
+}
+
+// parseYAMLLine parses the line number out of a YAML decoder message like
+// `yaml: line 3: did not find expected key`.
+func parseYAMLLine(msg string) (line int, rest string, ok bool) {
+	m := yamlLinePattern.FindStringSubmatch(msg)
+	if m == nil {
+		return 0, "", false
+	}
+	line, err := strconv.Atoi(m[1])
+	return line, m[2], err == nil
+}
+
+var yamlLinePattern = regexp.MustCompile(`^(?:yaml: )?line (\d+): (.*)$`)
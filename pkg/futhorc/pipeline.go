@@ -7,10 +7,14 @@ import (
 	"futhorc/pkg/markdown"
 	"html/template"
 	"io/fs"
+	"log/slog"
 	"net/url"
 	"os"
+	"path"
 	"path/filepath"
 	"runtime/trace"
+	"slices"
+	"strings"
 	"time"
 
 	"github.com/go-git/go-billy/v5"
@@ -19,13 +23,125 @@ import (
 )
 
 type Pipeline struct {
-	PostSources     fs.FS
-	ThemeAssets     fs.FS
-	BaseURL         *url.URL
-	SiteData        SiteData
-	PostTemplate    *template.Template
-	IndexTemplate   *template.Template
+	PostSources   fs.FS
+	ThemeAssets   fs.FS
+	BaseURL       *url.URL
+	SiteData      SiteData
+	PostTemplate  *template.Template
+	IndexTemplate *template.Template
+
+	// TagsTemplate renders the tags page, which is skipped if it's nil.
+	TagsTemplate *template.Template
+
+	// OutputDirectory receives every file the pipeline writes: pages, feeds,
+	// and copied assets. `LoadPipeline` points it at the project's `_output`
+	// directory, but library users may substitute any `billy.Filesystem`
+	// (e.g., `memfs.New()` to build the site in memory and inspect the
+	// results without touching the disk).
 	OutputDirectory billy.Filesystem
+
+	// OutputPath is the path of the output directory on disk, which
+	// `RunStaged` stages the build next to. `LoadPipeline` sets it along
+	// with `OutputDirectory`.
+	OutputPath string
+
+	// ProjectDir is the path of the project directory, which `RunStaged`
+	// records in the output directory's marker.
+	ProjectDir string
+
+	// Force makes `RunStaged` build into a non-empty output directory even
+	// if it lacks the marker of a previous build.
+	Force bool
+
+	// Title, Description, and Author describe the site in its feeds. Author
+	// may be nil.
+	Title       string
+	Description string
+	Author      *Author
+
+	Dates         DateFormatter
+	Normalization Normalization
+	ReadingTime   ReadingTime
+	PostLimits    PostLimits
+	FeedOrder     FeedOrder
+	FeedFormats   []FeedFormat
+	FeedLimit     int
+	PreviewOutput bool
+	MaxPageSize   int
+	IndexGroups   *IndexGroups
+
+	// UncategorizedTag is the implicit tag for posts without any tags (empty
+	// to disable it).
+	UncategorizedTag string
+
+	// IndexJSON writes each index page's data as JSON alongside the HTML.
+	IndexJSON bool
+
+	// LossyEncoding replaces invalid UTF-8 in post files instead of failing.
+	LossyEncoding bool
+
+	// AssetFilter selects the theme and post assets which are copied.
+	AssetFilter AssetFilter
+
+	// TagRedirects maps renamed tags' old names to their new names.
+	TagRedirects map[string]string
+
+	// CopyCheck determines when asset copies are skipped as up to date.
+	CopyCheck CopyCheck
+
+	// ExcludeFeatured removes the featured post from the regular list on the
+	// first page of the main index.
+	ExcludeFeatured bool
+
+	// PrettyURLs writes pages to `index.html` files in directories of their
+	// own and links to them by the directories.
+	PrettyURLs bool
+
+	// SummaryModes selects how the posts on each index's pages are
+	// summarized.
+	SummaryModes SummaryModes
+
+	// SinglePostTags determines how tags with exactly one post are indexed.
+	SinglePostTags SinglePostTags
+
+	// SummarySeparator is the HTML comment which ends each post's snippet
+	// (empty for `<!-- more -->`).
+	SummarySeparator string
+
+	// HeadingShift is the number of levels by which post headings are
+	// demoted.
+	HeadingShift uint8
+
+	// ExternalLinksNewTab opens links in posts to other hosts in a new tab.
+	ExternalLinksNewTab bool
+
+	// CheckLinks determines what happens to links in posts to pages and
+	// files which the build doesn't write.
+	CheckLinks LinkCheck
+
+	// DebugTemplates warns about template accesses to missing values, which
+	// otherwise render silently as empty. StrictTemplates makes them errors.
+	DebugTemplates  bool
+	StrictTemplates bool
+
+	// Drafts publishes drafts as if they were regular posts. Themes can
+	// identify them by `.Content.Draft`.
+	Drafts bool
+
+	// WaitForLock makes `RunStaged` wait for another build to release the
+	// lock on the output directory rather than failing.
+	WaitForLock bool
+
+	// Postprocessors transform each rendered page, in order, before it's
+	// written. The normalization is always applied after them.
+	Postprocessors []PagePostprocessor
+
+	// Report lists the pages and feeds written by the most recent `Run`.
+	Report *BuildReport
+
+	// debugTemplates maps each template to its copy for `DebugTemplates`
+	// (see `cloneDebugTemplates`).
+	debugTemplates map[*template.Template]*template.Template
 }
 
 func LoadPipeline(dir, siteRoot string) (pipeline Pipeline, err error) {
@@ -41,14 +157,157 @@ func LoadPipeline(dir, siteRoot string) (pipeline Pipeline, err error) {
 		siteRoot = "file://" + outputDirectory
 	}
 
-	if pipeline.BaseURL, err = url.Parse(siteRoot); err != nil {
+	if pipeline.BaseURL, err = parseSiteRoot(siteRoot); err != nil {
 		err = fmt.Errorf("loading pipeline: %w", err)
 		return
 	}
 
+	var config Config
+	if config, err = LoadConfig(os.DirFS(dir)); err != nil {
+		err = fmt.Errorf("loading pipeline: %w", err)
+		return
+	}
+	pipeline.Title = config.Title
+	if pipeline.Title == "" {
+		pipeline.Title = pipeline.BaseURL.Host
+	}
+	if pipeline.Title == "" {
+		// `file://` site roots have no host
+		pipeline.Title = filepath.Base(dir)
+	}
+	pipeline.Description = config.Description
+	pipeline.Author = config.Author
+	pipeline.Dates = config.DateFormatter()
+	if err = pipeline.Dates.Validate(); err != nil {
+		err = fmt.Errorf("loading pipeline: %w", err)
+		return
+	}
+	if pipeline.Normalization, err = ParseNormalization(
+		config.OutputNormalization,
+	); err != nil {
+		err = fmt.Errorf("loading pipeline: %w", err)
+		return
+	}
+	pipeline.ReadingTime, err = config.ReadingTime.withDefaults()
+	if err != nil {
+		err = fmt.Errorf("loading pipeline: %w", err)
+		return
+	}
+	if pipeline.PostLimits, err = config.PostLimits.withDefaults(); err != nil {
+		err = fmt.Errorf("loading pipeline: %w", err)
+		return
+	}
+	if pipeline.FeedOrder, err = ParseFeedOrder(config.FeedOrder); err != nil {
+		err = fmt.Errorf("loading pipeline: %w", err)
+		return
+	}
+	if pipeline.FeedFormats, err = ParseFeedFormats(config.Feeds); err != nil {
+		err = fmt.Errorf("loading pipeline: %w", err)
+		return
+	}
+	if config.FeedEntryLimit < 0 {
+		err = fmt.Errorf(
+			"loading pipeline: invalid feed entry limit %d: must not be "+
+				"negative",
+			config.FeedEntryLimit,
+		)
+		return
+	}
+	pipeline.FeedLimit = config.FeedEntryLimit
+	pipeline.HeadingShift = defaultHeadingShift
+	if config.HeadingShift != nil {
+		if shift := *config.HeadingShift; shift < 0 || shift > maxHeadingShift {
+			err = fmt.Errorf(
+				"loading pipeline: invalid heading shift %d: must be between "+
+					"0 and %d",
+				shift,
+				maxHeadingShift,
+			)
+			return
+		}
+		pipeline.HeadingShift = uint8(*config.HeadingShift)
+	}
+	pipeline.PreviewOutput = config.PreviewOutput
+	pipeline.MaxPageSize = config.MaxPageSizeKB * 1024
+	if config.IndexGroups != nil {
+		var groups IndexGroups
+		if groups, err = config.IndexGroups.withDefaults(); err != nil {
+			err = fmt.Errorf("loading pipeline: %w", err)
+			return
+		}
+		pipeline.IndexGroups = &groups
+	}
+	pipeline.UncategorizedTag = strings.TrimSpace(config.UncategorizedTag)
+	if pipeline.UncategorizedTag != "" &&
+		normalizeTag(pipeline.UncategorizedTag) == "" {
+		err = fmt.Errorf(
+			"loading pipeline: uncategorized tag `%s` has no letters or "+
+				"digits",
+			pipeline.UncategorizedTag,
+		)
+		return
+	}
+	pipeline.IndexJSON = config.IndexJSON
+	pipeline.LossyEncoding = config.LossyEncoding
+	pipeline.ExcludeFeatured = config.ExcludeFeatured
+	pipeline.PrettyURLs = config.PrettyURLs
+	pipeline.ExternalLinksNewTab = config.ExternalLinksNewTab
+	if pipeline.SummaryModes.Default, err = ParseSummaryMode(
+		config.IndexSummaryMode,
+	); err != nil {
+		err = fmt.Errorf("loading pipeline: %w", err)
+		return
+	}
+	if len(config.TagSummaryModes) > 0 {
+		pipeline.SummaryModes.Tags = make(
+			map[string]SummaryMode,
+			len(config.TagSummaryModes),
+		)
+		for tag, s := range config.TagSummaryModes {
+			var mode SummaryMode
+			if mode, err = ParseSummaryMode(s); err != nil {
+				err = fmt.Errorf(
+					"loading pipeline: tag `%s`: %w",
+					tag,
+					err,
+				)
+				return
+			}
+			pipeline.SummaryModes.Tags[normalizeTag(tag)] = mode
+		}
+	}
+	pipeline.SummarySeparator = config.SummarySeparator
+	if pipeline.SinglePostTags, err = ParseSinglePostTags(
+		config.SinglePostTags,
+	); err != nil {
+		err = fmt.Errorf("loading pipeline: %w", err)
+		return
+	}
+	if pipeline.CheckLinks, err = ParseLinkCheck(
+		config.CheckLinks,
+	); err != nil {
+		err = fmt.Errorf("loading pipeline: %w", err)
+		return
+	}
+	if pipeline.CopyCheck, err = ParseCopyCheck(config.CopyCheck); err != nil {
+		err = fmt.Errorf("loading pipeline: %w", err)
+		return
+	}
+	if len(config.TagRedirects) > 0 {
+		pipeline.TagRedirects = make(
+			map[string]string,
+			len(config.TagRedirects),
+		)
+		for old, tag := range config.TagRedirects {
+			pipeline.TagRedirects[normalizeTag(old)] = normalizeTag(tag)
+		}
+	}
+
 	pipeline.PostSources = os.DirFS(filepath.Join(dir, "posts"))
 	pipeline.ThemeAssets = os.DirFS(filepath.Join(dir, "theme/assets"))
 	pipeline.OutputDirectory = osfs.New(outputDirectory)
+	pipeline.OutputPath = outputDirectory
+	pipeline.ProjectDir = dir
 
 	var theme Theme
 	if theme, err = LoadTheme(os.DirFS(filepath.Join(
@@ -61,19 +320,48 @@ func LoadPipeline(dir, siteRoot string) (pipeline Pipeline, err error) {
 
 	pipeline.PostTemplate = theme.PostTemplate
 	pipeline.IndexTemplate = theme.IndexTemplate
+	pipeline.TagsTemplate = theme.TagsTemplate
+	if err = pipeline.cloneDebugTemplates(); err != nil {
+		err = fmt.Errorf("loading pipeline: %w", err)
+		return
+	}
+	pipeline.AssetFilter = AssetFilter{
+		Ignore:  append(theme.AssetFilter.Ignore, config.StaticIgnore...),
+		Include: append(theme.AssetFilter.Include, config.StaticInclude...),
+	}
+	if err = pipeline.AssetFilter.Validate(); err != nil {
+		err = fmt.Errorf("loading pipeline: %w", err)
+		return
+	}
 
 	pipeline.SiteData = SiteData{
-		BaseURL: template.URL(pipeline.BaseURL.String()),
+		Title:       pipeline.Title,
+		Description: pipeline.Description,
+		BaseURL:     template.URL(pipeline.BaseURL.String()),
 		HomePage: template.URL(
-			pipeline.BaseURL.JoinPath("index.html").String(),
+			indexURL(pipeline.BaseURL, "", pipeline.PrettyURLs).String(),
 		),
 		ThemeAssets: template.URL(
 			pipeline.BaseURL.JoinPath("assets/theme/").String(),
 		),
-		FeedURL: template.URL(
-			pipeline.BaseURL.JoinPath("index.json").String(),
-		),
-		FeedType: "application/json",
+		MetaTags: config.MetaTags,
+	}
+	for _, format := range pipeline.FeedFormats {
+		pipeline.SiteData.Feeds = append(pipeline.SiteData.Feeds, FeedLink{
+			URL: template.URL(pipeline.BaseURL.JoinPath(
+				feedPath(indexFile, format),
+			).String()),
+			Type: format.ContentType(),
+		})
+	}
+	pipeline.SiteData.FeedURL = pipeline.SiteData.Feeds[0].URL
+	pipeline.SiteData.FeedType = pipeline.SiteData.Feeds[0].Type
+	if config.Webmention != nil {
+		if err = config.Webmention.Validate(); err != nil {
+			err = fmt.Errorf("loading pipeline: %w", err)
+			return
+		}
+		pipeline.SiteData.Webmention = config.Webmention
 	}
 
 	return
@@ -85,7 +373,25 @@ func (pipeline *Pipeline) Run(ctx context.Context) error {
 	ctx, task := trace.NewTask(ctx, "pipeline")
 	defer task.End()
 
-	postAssets, err := fs.Sub(pipeline.PostSources, "assets")
+	start := time.Now()
+	pipeline.Report = &BuildReport{}
+
+	postprocessors := append(
+		slices.Clone(pipeline.Postprocessors),
+		pipeline.Normalization,
+	)
+
+	var debugPost, debugIndex, debugTags *template.Template
+	if pipeline.DebugTemplates {
+		if err := pipeline.cloneDebugTemplates(); err != nil {
+			return err
+		}
+		debugPost = pipeline.debugTemplates[pipeline.PostTemplate]
+		debugIndex = pipeline.debugTemplates[pipeline.IndexTemplate]
+		debugTags = pipeline.debugTemplates[pipeline.TagsTemplate]
+	}
+
+	postAssets, err := fs.Sub(pipeline.PostSources, postAssetsDirectory)
 	if err != nil {
 		return fmt.Errorf(
 			"creating post assets subdirectory filesystem: %w",
@@ -96,43 +402,90 @@ func (pipeline *Pipeline) Run(ctx context.Context) error {
 	postAssetsFinder := actor.NewOutput(
 		"FileFinder::PostAssets",
 		1,
-		FileFinder(postAssets, ""),
+		FilterAssets(
+			"FileFinder::PostAssets",
+			FileFinder(postAssets, ""),
+			&pipeline.AssetFilter,
+		),
+	)
+
+	// nothing is copied until the output plan has been checked (see the
+	// orderer's preflight)
+	checked := make(chan struct{})
+	postAssetsGate := NewGate(
+		"Gate::PostAssets",
+		postAssetsFinder.OutputChan(),
+		checked,
 	)
 
+	var postAssetsStats CopyStats
 	postAssetsCopier := actor.NewInput(
 		"FileCopier::PostAssets",
 		4,
-		postAssetsFinder.OutputChan(),
+		postAssetsGate.Output,
 		FileCopier(
 			pipeline.OutputDirectory,
 			postAssets,
 			"/assets/posts/",
+			pipeline.CopyCheck,
+			&postAssetsStats,
 		),
-		nil,
+		postAssetsStats.Report("FileCopier::PostAssets"),
+	)
+
+	// the post sources and their assets are found by the same walk
+	bundleAssets := make(chan string)
+	bundleAssetsGate := NewGate("Gate::BundleAssets", bundleAssets, checked)
+	var bundleAssetsStats CopyStats
+	bundleAssetsCopier := actor.NewInput(
+		"FileCopier::BundleAssets",
+		4,
+		bundleAssetsGate.Output,
+		FileCopier(
+			pipeline.OutputDirectory,
+			pipeline.PostSources,
+			"/posts/",
+			pipeline.CopyCheck,
+			&bundleAssetsStats,
+		),
+		bundleAssetsStats.Report("FileCopier::BundleAssets"),
 	)
 
 	themeAssetsFinder := actor.NewOutput(
 		"FileFinder::ThemeAssets",
 		1,
-		FileFinder(pipeline.ThemeAssets, ""),
+		FilterAssets(
+			"FileFinder::ThemeAssets",
+			FileFinder(pipeline.ThemeAssets, ""),
+			&pipeline.AssetFilter,
+		),
 	)
 
+	themeAssetsGate := NewGate(
+		"Gate::ThemeAssets",
+		themeAssetsFinder.OutputChan(),
+		checked,
+	)
+
+	var themeAssetsStats CopyStats
 	themeAssetsCopier := actor.NewInput(
 		"FileCopier::ThemeAssets",
 		4,
-		themeAssetsFinder.OutputChan(),
+		themeAssetsGate.Output,
 		FileCopier(
 			pipeline.OutputDirectory,
 			pipeline.ThemeAssets,
 			"/assets/theme/",
+			pipeline.CopyCheck,
+			&themeAssetsStats,
 		),
-		nil,
+		themeAssetsStats.Report("FileCopier::ThemeAssets"),
 	)
 
 	sourceFinder := actor.NewOutput(
 		"FileFinder::PostSources",
 		1,
-		FileFinder(pipeline.PostSources, markdownSuffix),
+		PostFinder(pipeline.PostSources, &pipeline.AssetFilter, bundleAssets),
 	)
 
 	sourceReader := NewFileReader(
@@ -142,24 +495,100 @@ func (pipeline *Pipeline) Run(ctx context.Context) error {
 		sourceFinder.OutputChan(),
 	)
 
+	postConverter := PostPageConverter{
+		Markdown: markdown.Config{
+			BaseURL:           pipeline.BaseURL,
+			ParserExtensions:  markdown.CommonExtensions,
+			DeprecateHeadings: pipeline.HeadingShift,
+			PrettyURLs:        pipeline.PrettyURLs,
+			MaxNesting:        pipeline.PostLimits.MaxNesting,
+			MoreMarker:        pipeline.SummarySeparator,
+
+			ExternalLinksNewTab: pipeline.ExternalLinksNewTab,
+		},
+		Dates:       pipeline.Dates,
+		ReadingTime: pipeline.ReadingTime,
+		PageConverter: PageConverter[Post]{
+			BaseURL:    pipeline.BaseURL,
+			Directory:  "posts",
+			PrettyURLs: pipeline.PrettyURLs,
+		},
+		UncategorizedTag: pipeline.UncategorizedTag,
+	}
+
+	// links to slugged posts must be rewritten while any post is converted,
+	// so the slugs are scanned up front
+	if postConverter.Markdown.Aliases, err = postConverter.SlugAliases(
+		pipeline.PostSources,
+		pipeline.LossyEncoding,
+		&pipeline.PostLimits,
+	); err != nil {
+		return err
+	}
+
 	parser := NewPostParser(
 		"PostParser",
 		8,
 		sourceReader.Output(),
-		&PostPageConverter{
-			Markdown: markdown.Config{
-				BaseURL:           pipeline.BaseURL,
-				ParserExtensions:  markdown.CommonExtensions,
-				DeprecateHeadings: 2,
-			},
-			PageConverter: PageConverter[Post]{
-				BaseURL:   pipeline.BaseURL,
-				Directory: "posts",
-			},
-		},
+		&postConverter,
+		pipeline.LossyEncoding,
+		&pipeline.PostLimits,
 	)
 
-	orderer := NewOrderer("Orderer", parser.Output)
+	var previews *PostPageConverter
+	if pipeline.PreviewOutput {
+		preview := postConverter
+		preview.Directory = path.Join(previewDirectory, "posts")
+		preview.Sources = postConverter.Directory
+		previews = &preview
+	}
+	publisher := NewPublisher(
+		"Publisher",
+		parser.Output,
+		time.Now(),
+		pipeline.Drafts,
+		previews,
+	)
+	var previewGate *Gate[*OrderedPage[Post]]
+	if publisher.Previews != nil {
+		gate := NewGate("Gate::Previews", publisher.Previews, checked)
+		previewGate = &gate
+	}
+
+	const pageSize = 10
+	orderer := NewOrderer(
+		"Orderer",
+		publisher.Published,
+		func(posts []OrderedPage[Post]) error {
+			warnDuplicateTitles(NewPostLookup(posts))
+			if pipeline.SinglePostTags == SinglePostTagsLink {
+				linkSinglePostTags(posts)
+			}
+			assets, err := bundleAssetsGate.Held()
+			if err != nil {
+				return err
+			}
+			var previews []*OrderedPage[Post]
+			if previewGate != nil {
+				if previews, err = previewGate.Held(); err != nil {
+					return err
+				}
+			}
+			if err := CheckOutputConflicts(
+				posts,
+				previews,
+				pageSize,
+				pipeline.PrettyURLs,
+				pipeline.FeedFormats,
+				pipeline.TagsTemplate != nil,
+				assets,
+			); err != nil {
+				return err
+			}
+			close(checked)
+			return nil
+		},
+	)
 
 	postTemplater := NewTemplater(&TemplaterParams[Post]{
 		Name:        "Templater[Post]",
@@ -168,66 +597,203 @@ func (pipeline *Pipeline) Run(ctx context.Context) error {
 		Output:      pipeline.OutputDirectory,
 		Template:    pipeline.PostTemplate,
 		SiteData:    &pipeline.SiteData,
+		MaxPageSize: pipeline.MaxPageSize,
+		Kind:        PageKindPost,
+
+		DebugTemplate:   debugPost,
+		StrictTemplates: pipeline.StrictTemplates,
+		Report:          pipeline.Report,
+		Postprocessors:  postprocessors,
 	})
 
 	indexer := Indexer{
-		PageConverter: IndexPageConverter{BaseURL: pipeline.BaseURL},
+		PageConverter: IndexPageConverter{
+			BaseURL:    pipeline.BaseURL,
+			PrettyURLs: pipeline.PrettyURLs,
+		},
 		OrderedPosts:  orderer.OrderedPageSlices,
 		IndexPages:    make(chan *OrderedPage[IndexPage]),
-		PageSize:      10,
+		PageSize:      pageSize,
 		Indices:       make(map[string]*Index),
-	}
+		FeedPages:     make(chan *OrderedPage[IndexPage]),
+		FeedOrder:     pipeline.FeedOrder,
+		FeedLimit:     pipeline.FeedLimit,
+		Groups:        pipeline.IndexGroups,
 
-	indexPages := MultiChan[*OrderedPage[IndexPage]]{
-		Input: indexer.IndexPages,
-		Outputs: []chan *OrderedPage[IndexPage]{
-			make(chan *OrderedPage[IndexPage]),
-			make(chan *OrderedPage[IndexPage]),
-		},
+		ExcludeFeatured: pipeline.ExcludeFeatured,
+		SummaryModes:    pipeline.SummaryModes,
+		SinglePostTags:  pipeline.SinglePostTags,
+	}
+	if pipeline.IndexJSON {
+		indexer.DataPages = make(chan *OrderedPage[IndexPage])
+	}
+	if pipeline.TagsTemplate != nil {
+		indexer.TagsPages = make(chan *OrderedPage[TagsPage])
+	}
+	if len(pipeline.TagRedirects) > 0 {
+		indexer.TagRedirects = pipeline.TagRedirects
+		indexer.Redirects = make(chan Redirect)
 	}
 
 	indexTemplater := NewTemplater(&TemplaterParams[IndexPage]{
 		Name:        "Templater[IndexPage]",
 		Concurrency: 8,
-		Pages:       indexPages.Output(0),
+		Pages:       indexer.IndexPages,
 		Output:      pipeline.OutputDirectory,
 		Template:    pipeline.IndexTemplate,
 		SiteData:    &pipeline.SiteData,
+		MaxPageSize: pipeline.MaxPageSize,
+		Kind:        PageKindIndex,
+
+		DebugTemplate:   debugIndex,
+		StrictTemplates: pipeline.StrictTemplates,
+		Report:          pipeline.Report,
+		Postprocessors:  postprocessors,
 	})
 
 	feedBuilder := actor.NewInput(
 		"FeedBuilder",
 		8,
-		indexPages.Output(1),
+		indexer.FeedPages,
 		FeedBuilder(
-			&feeds.Feed{
-				Title:       "Craig Weber",
-				Link:        &feeds.Link{Href: pipeline.BaseURL.String()},
-				Description: "Craig Weber's blog",
-				Author: &feeds.Author{
-					Name:  "Craig Weber",
-					Email: "weberc2@gmail.com",
-				},
-				Created: time.Date(2016, 1, 1, 0, 0, 0, 0, time.UTC),
-			},
+			pipeline.feedHeader(),
 			pipeline.OutputDirectory,
+			pipeline.FeedFormats,
+			pipeline.Report,
 		),
 		nil,
 	)
 
-	return actor.Multi{
+	actors := actor.Multi{
 		&postAssetsFinder,
+		&postAssetsGate,
 		&postAssetsCopier,
+		&bundleAssetsGate,
+		&bundleAssetsCopier,
 		&themeAssetsFinder,
+		&themeAssetsGate,
 		&themeAssetsCopier,
 		&sourceFinder,
 		&sourceReader,
 		&parser,
+		&publisher,
 		&orderer,
 		&indexer,
 		&postTemplater,
-		&indexPages,
 		&indexTemplater,
 		&feedBuilder,
-	}.Run(ctx)
+	}
+
+	if previewGate != nil {
+		previewTemplater := NewTemplater(&TemplaterParams[Post]{
+			Name:        "Templater[Preview]",
+			Concurrency: 8,
+			Pages:       previewGate.Output,
+			Output:      pipeline.OutputDirectory,
+			Template:    pipeline.PostTemplate,
+			SiteData:    &pipeline.SiteData,
+			MaxPageSize: pipeline.MaxPageSize,
+			Kind:        PageKindPost,
+
+			DebugTemplate:   debugPost,
+			StrictTemplates: pipeline.StrictTemplates,
+			Report:          pipeline.Report,
+			Postprocessors:  postprocessors,
+		})
+		actors = append(actors, previewGate, &previewTemplater)
+	}
+
+	if indexer.TagsPages != nil {
+		tagsTemplater := NewTemplater(&TemplaterParams[TagsPage]{
+			Name:        "Templater[TagsPage]",
+			Concurrency: 1,
+			Pages:       indexer.TagsPages,
+			Output:      pipeline.OutputDirectory,
+			Template:    pipeline.TagsTemplate,
+			SiteData:    &pipeline.SiteData,
+			MaxPageSize: pipeline.MaxPageSize,
+			Kind:        PageKindTags,
+
+			DebugTemplate:   debugTags,
+			StrictTemplates: pipeline.StrictTemplates,
+			Report:          pipeline.Report,
+			Postprocessors:  postprocessors,
+		})
+		actors = append(actors, &tagsTemplater)
+	}
+
+	if indexer.Redirects != nil {
+		redirectWriter := actor.NewInput(
+			"RedirectWriter",
+			4,
+			indexer.Redirects,
+			RedirectWriter(pipeline.OutputDirectory),
+			nil,
+		)
+		actors = append(actors, &redirectWriter)
+	}
+
+	if indexer.DataPages != nil {
+		indexDataWriter := actor.NewInput(
+			"IndexDataWriter",
+			8,
+			indexer.DataPages,
+			IndexDataWriter(pipeline.OutputDirectory),
+			nil,
+		)
+		actors = append(actors, &indexDataWriter)
+	}
+
+	if err := actors.Run(ctx); err != nil {
+		return err
+	}
+	if err := pipeline.CheckLinks.check(
+		pipeline.Report,
+		pipeline.OutputDirectory,
+		pipeline.BaseURL,
+	); err != nil {
+		return err
+	}
+	pipeline.Report.Assets = postAssetsStats.total() +
+		bundleAssetsStats.total() +
+		themeAssetsStats.total()
+	pipeline.Report.Elapsed = time.Since(start)
+	pipeline.Report.Log()
+	return nil
+}
+
+// feedHeader returns the feed's header: the site's title, description, and
+// author. Each page of the feed has its own link and entries.
+func (pipeline *Pipeline) feedHeader() *feeds.Feed {
+	header := feeds.Feed{
+		Title:       pipeline.Title,
+		Link:        &feeds.Link{Href: pipeline.BaseURL.String()},
+		Description: pipeline.Description,
+		Created:     time.Date(2016, 1, 1, 0, 0, 0, 0, time.UTC),
+	}
+	if pipeline.Author != nil {
+		header.Author = &feeds.Author{
+			Name:  pipeline.Author.Name,
+			Email: pipeline.Author.Email,
+		}
+	}
+	return &header
+}
+
+// warnDuplicateTitles logs a warning for each title shared by several posts,
+// since that's usually unintentional.
+func warnDuplicateTitles(lookup *PostLookup) {
+	duplicates := lookup.DuplicateTitles()
+	titles := make([]string, 0, len(duplicates))
+	for title := range duplicates {
+		titles = append(titles, title)
+	}
+	slices.Sort(titles)
+	for _, title := range titles {
+		slog.Warn(
+			"several posts have the same title",
+			"title", title,
+			"posts", duplicates[title],
+		)
+	}
 }
@@ -4,7 +4,11 @@ import (
 	"bytes"
 	"errors"
 	"fmt"
+	"futhorc/pkg/markdown"
 	"html/template"
+	"net/url"
+	"reflect"
+	"slices"
 	"strings"
 	"time"
 	"unsafe"
@@ -19,39 +23,79 @@ type Post struct {
 	// Path is the relative path to the file from the put posts directory.
 	Path string
 
+	// Source is the path of the post's source file relative to the posts
+	// directory. Unlike `Path`, it's unchanged by conversion.
+	Source string
+
 	// Body is the post body source.
 	Body template.HTML
 
 	// Snippet is the body text before the first `<!-- more -->` tag.
 	Snippet template.HTML
+
+	// DateDisplay is the post date rendered with the configured date format
+	// and locale.
+	DateDisplay string
+
+	// DateParts holds the components of the post date.
+	DateParts DateParts
+
+	// TOC is the post's table of contents, built from its headings.
+	TOC []TOCEntry
+
+	// WordCount is the number of words in the post's prose.
+	WordCount int
+
+	// CharacterCount is the number of characters in the same prose.
+	CharacterCount int
+
+	// ReadingTime is the estimated reading time in minutes.
+	ReadingTime int
+
+	// Links lists the post's links to pages and files on the site, which are
+	// checked once the site is built (see `LinkCheck`).
+	Links []markdown.Link
+
+	// Preview is true if the post is unpublished and is being rendered into
+	// the preview subtree (so the template can display a banner).
+	Preview bool
+
+	// markdown is the body source once `Body` has been rendered.
+	markdown template.HTML
 }
 
 func ParsePost(data []byte, sourcePath string) (p Post, err error) {
-	var idx int
-	if !bytes.HasPrefix(data, startFence) {
-		err = ErrFrontmatterMissingStartFence
+	var frontmatter, body []byte
+	if frontmatter, body, err = splitFrontmatter(data); err != nil {
 		goto ERROR
 	}
-
-	idx = bytes.Index(data, endFence)
-	if idx < 1 {
-		err = ErrFrontmatterMissingEndFence
+	if err = yaml.Unmarshal(frontmatter, &p.Frontmatter); err != nil {
 		goto ERROR
 	}
-
-	if err = yaml.Unmarshal(
-		data[len(startFence):idx],
-		&p.Frontmatter,
-	); err != nil {
+	if err = checkRequiredKeys(&p.Frontmatter); err != nil {
+		goto ERROR
+	}
+	if err = checkExtraKeys(p.Extra); err != nil {
+		goto ERROR
+	}
+	if err = validateSlug(p.Slug); err != nil {
 		goto ERROR
 	}
+	if p.Updated != nil && time.Time(*p.Updated).Before(time.Time(p.Date)) {
+		err = fmt.Errorf(
+			"`Updated` (%s) is earlier than `Date` (%s)",
+			*p.Updated,
+			p.Date,
+		)
+		goto ERROR
+	}
+	p.Tags = dedupeTags(p.Tags)
 
-	data = data[idx+len(endFence):] // calculate the body and save it as `data`
-	p.Body = *(*template.HTML)(unsafe.Pointer(&data))
+	p.Body = *(*template.HTML)(unsafe.Pointer(&body))
 	p.Path = sourcePath
 	return
 ERROR:
-	err = fmt.Errorf("parsing post `%s`: %w", sourcePath, err)
+	err = newParsePostError(sourcePath, err)
 	return
 }
 
@@ -60,11 +104,126 @@ type Frontmatter struct {
 	Author string `yaml:"Author"`
 	Date   Date   `yaml:"Date"`
 	Tags   []Link `yaml:"Tags"`
+
+	// Updated is the date the post was last revised (nil if it never was).
+	// It must not be earlier than `Date`.
+	Updated *Date `yaml:"Updated,omitempty"`
+
+	// Slug, if set, replaces the post's file name in its output path and URL
+	// (see `PostPageConverter`).
+	Slug string `yaml:"Slug,omitempty"`
+
+	// Feed may be set to false to exclude the post from the feed. The post
+	// still appears on the site and in its indices.
+	Feed *bool `yaml:"Feed,omitempty"`
+
+	// Draft marks a work-in-progress post, which isn't published.
+	Draft bool `yaml:"Draft,omitempty"`
+
+	// Featured marks a post for special placement on the first page of the
+	// main index (see `IndexPage.Featured`).
+	Featured bool `yaml:"Featured,omitempty"`
+
+	// Discussion is the URL of a discussion of the post elsewhere (e.g., a
+	// Mastodon thread).
+	Discussion AbsoluteURL `yaml:"Discussion,omitempty"`
+
+	// ContentWarning, if set, describes sensitive content in the post. The
+	// post's snippet is collapsed behind it (see `PostPageConverter`), and
+	// it replaces the snippet as the feed entry's summary.
+	ContentWarning string `yaml:"ContentWarning,omitempty"`
+
+	// Extra holds the frontmatter keys which aren't built in (e.g.,
+	// `Subtitle`) so templates can use them (as `.Content.Extra.Subtitle`).
+	// Nested maps and lists are decoded as `map[string]any` and `[]any`.
+	Extra map[string]any `yaml:",inline"`
+}
+
+// checkRequiredKeys returns an error if the `Title` or `Date` key is missing
+// from the frontmatter.
+func checkRequiredKeys(frontmatter *Frontmatter) error {
+	var missing error
+	switch {
+	case frontmatter.Title == "":
+		missing = errors.New("missing field `Title`")
+	case time.Time(frontmatter.Date).IsZero():
+		missing = errors.New("missing field `Date`")
+	default:
+		return nil
+	}
+	// point at the start fence, since there's no line to point at
+	return &frontmatterError{Line: 1, Err: missing}
+}
+
+// checkExtraKeys returns an error for any extra frontmatter key which differs
+// from a built-in key only in case (e.g., `title`), since it's almost
+// certainly a misspelling of the built-in key rather than a custom one.
+func checkExtraKeys(extra map[string]any) error {
+	var errs []error
+	for key := range extra {
+		for _, builtin := range builtinKeys {
+			if strings.EqualFold(key, builtin) {
+				errs = append(errs, fmt.Errorf(
+					"frontmatter key `%s` collides with the built-in key `%s`",
+					key,
+					builtin,
+				))
+			}
+		}
+	}
+	slices.SortFunc(errs, func(a, b error) int {
+		return strings.Compare(a.Error(), b.Error())
+	})
+	return errors.Join(errs...)
+}
+
+// builtinKeys lists the keys of the built-in frontmatter fields.
+var builtinKeys = func() []string {
+	t := reflect.TypeFor[Frontmatter]()
+	keys := make([]string, 0, t.NumField())
+	for i := range t.NumField() {
+		name, _, _ := strings.Cut(t.Field(i).Tag.Get("yaml"), ",")
+		if name != "" {
+			keys = append(keys, name)
+		}
+	}
+	return keys
+}()
+
+// unconverted returns the post as it was before `PostPageConverter.Convert`
+// rendered its body, so it can be converted again (e.g., as a preview).
+func (p *Post) unconverted() *Post {
+	source := *p
+	source.Path = p.Source
+	source.Body = p.markdown
+	return &source
+}
+
+// InFeed reports whether the post is included in the feed.
+func (p *Post) InFeed() bool {
+	return p.Feed == nil || *p.Feed
+}
+
+// Modified returns the date the post was last updated, or the date it was
+// published if it was never updated.
+func (p *Post) Modified() time.Time {
+	if p.Updated != nil {
+		return time.Time(*p.Updated)
+	}
+	return time.Time(p.Date)
 }
 
 type Link struct {
+	// Text is the tag's name as it's displayed (e.g., `C++`), and Slug is
+	// the normalized name which identifies it in URLs and output paths
+	// (e.g., `c-plus-plus`).
 	Text string
+	Slug string
 	URL  template.URL
+
+	// Implicit is true for the uncategorized tag, which is given to posts
+	// without any tags rather than listed in their frontmatter.
+	Implicit bool
 }
 
 func (l Link) MarshalYAML() (interface{}, error) {
@@ -73,9 +232,39 @@ func (l Link) MarshalYAML() (interface{}, error) {
 
 func (l *Link) UnmarshalYAML(value *yaml.Node) error {
 	// we're only going to unmarshal the text in frontmatter
-	err := value.Decode(&l.Text)
-	l.Text = strings.ToLower(l.Text)
-	return err
+	if err := value.Decode(&l.Text); err != nil {
+		return err
+	}
+	if l.Text = strings.TrimSpace(l.Text); l.Text == "" {
+		return newFrontmatterError(value, ErrEmptyTag)
+	}
+	if l.Slug = normalizeTag(l.Text); l.Slug == "" {
+		return newFrontmatterError(value, fmt.Errorf(
+			"tag `%s` has no letters or digits",
+			l.Text,
+		))
+	}
+	return nil
+}
+
+// AbsoluteURL is a frontmatter URL which must be absolute (with a scheme and
+// host).
+type AbsoluteURL string
+
+func (u *AbsoluteURL) UnmarshalYAML(value *yaml.Node) error {
+	var s string
+	if err := value.Decode(&s); err != nil {
+		return err
+	}
+	if parsed, err := url.Parse(s); err != nil || !parsed.IsAbs() ||
+		parsed.Host == "" {
+		return newFrontmatterError(
+			value,
+			fmt.Errorf("url `%s` is not absolute", s),
+		)
+	}
+	*u = AbsoluteURL(s)
+	return nil
 }
 
 type Date time.Time
@@ -84,8 +273,14 @@ func (d Date) Before(other Date) bool {
 	return time.Time(d).Before(time.Time(other))
 }
 
+// String formats the date as it's written in frontmatter: just the day if it
+// has no time of day (or offset), or RFC 3339 otherwise.
 func (d Date) String() string {
-	return time.Time(d).Format(dateLayout)
+	t := time.Time(d)
+	if t.Equal(t.Truncate(24*time.Hour)) && t.Location() == time.UTC {
+		return t.Format(dateLayout)
+	}
+	return t.Format(time.RFC3339)
 }
 
 func (d *Date) UnmarshalYAML(value *yaml.Node) error {
@@ -94,17 +289,93 @@ func (d *Date) UnmarshalYAML(value *yaml.Node) error {
 	if err = value.Decode(&s); err != nil {
 		return fmt.Errorf("unmarshaling time: %w", err)
 	}
-	if *(*time.Time)(d), err = time.Parse(dateLayout, s); err != nil {
-		return fmt.Errorf("unmarshaling time: %w", err)
+	for _, layout := range dateLayouts {
+		if *(*time.Time)(d), err = time.Parse(layout, s); err == nil {
+			return nil
+		}
 	}
-	return nil
+	return newInvalidDateError(s, value)
+}
+
+// InvalidDateError is returned when a frontmatter date can't be parsed.
+type InvalidDateError struct {
+	// Value is the offending date.
+	Value string
+
+	// Line and Column locate the date in the post file.
+	Line   int
+	Column int
+
+	// Accepted lists the accepted date formats.
+	Accepted []string
+
+	// Hint suggests a fix for common mistakes (it may be empty).
+	Hint string
+}
+
+func newInvalidDateError(value string, node *yaml.Node) *InvalidDateError {
+	err := InvalidDateError{
+		Value:    value,
+		Line:     frontmatterLine(node),
+		Column:   node.Column,
+		Accepted: acceptedDateFormats,
+	}
+	if t, e := time.Parse(dayFirstDateLayout, value); e == nil {
+		err.Hint = fmt.Sprintf(
+			"dates are written year first; did you mean `%s`?",
+			t.Format(dateLayout),
+		)
+	}
+	return &err
+}
+
+func (err *InvalidDateError) Error() string {
+	msg := fmt.Sprintf(
+		"invalid date `%s` (accepted formats: %s)",
+		err.Value,
+		strings.Join(err.Accepted, ", "),
+	)
+	if err.Hint != "" {
+		msg += ": " + err.Hint
+	}
+	return msg
+}
+
+func (err *InvalidDateError) position() (line, column int) {
+	return err.Line, err.Column
+}
+
+// frontmatterLine returns the line of the post file containing `node`. The
+// node's own line number is relative to the frontmatter, which begins after
+// the start fence on the first line of the file.
+func frontmatterLine(node *yaml.Node) int {
+	return node.Line + 1
 }
 
 func (d Date) MarshalYAML() (interface{}, error) {
-	return time.Time(d).Format(dateLayout), nil
+	return d.String(), nil
 }
 
-const dateLayout = "2006-01-02"
+const (
+	dateLayout         = "2006-01-02"
+	dayFirstDateLayout = "02-01-2006"
+)
+
+// dateLayouts lists the layouts of frontmatter dates, which are parsed as UTC
+// unless they have an offset. They correspond to `acceptedDateFormats`.
+var dateLayouts = []string{
+	dateLayout,
+	"2006-01-02 15:04",
+	"2006-01-02T15:04:05",
+	time.RFC3339,
+}
+
+var acceptedDateFormats = []string{
+	"YYYY-MM-DD",
+	"YYYY-MM-DD HH:MM",
+	"YYYY-MM-DDTHH:MM:SS",
+	"RFC 3339 (YYYY-MM-DDTHH:MM:SS+HH:MM)",
+}
 
 var (
 	ErrFrontmatterMissingStartFence = errors.New(
@@ -114,9 +385,28 @@ var (
 		"scanning frontmatter: missing end fence",
 	)
 
-	startFence = []byte(startFenceString)
-	endFence   = []byte(endFenceString)
+	fence = []byte("---")
 )
 
-const startFenceString = "---\n"
-const endFenceString = "\n---\n"
+// splitFrontmatter splits a post file into its frontmatter and its body. The
+// frontmatter's fences must be lines of their own (so `---` within a value
+// doesn't end it): the first line of the file opens it, and the next `---`
+// line closes it. Trailing whitespace after a fence and `\r\n` line endings
+// are allowed.
+func splitFrontmatter(data []byte) (frontmatter, body []byte, err error) {
+	line, rest, _ := bytes.Cut(data, []byte("\n"))
+	if !isFence(line) {
+		return nil, nil, ErrFrontmatterMissingStartFence
+	}
+	start := len(data) - len(rest)
+	for offset := start; len(rest) > 0; offset = len(data) - len(rest) {
+		if line, rest, _ = bytes.Cut(rest, []byte("\n")); isFence(line) {
+			return data[start:offset], rest, nil
+		}
+	}
+	return nil, nil, ErrFrontmatterMissingEndFence
+}
+
+func isFence(line []byte) bool {
+	return bytes.Equal(bytes.TrimRight(line, " \t\r"), fence)
+}
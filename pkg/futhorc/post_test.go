@@ -0,0 +1,360 @@
+package futhorc
+
+import (
+	"errors"
+	"slices"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/go-git/go-billy/v5/util"
+)
+
+func TestSplitFrontmatter(t *testing.T) {
+	for _, tc := range []struct {
+		name        string
+		data        string
+		frontmatter string
+		body        string
+		err         error
+	}{
+		{
+			name:        "simple",
+			data:        "---\nTitle: x\n---\nbody\n",
+			frontmatter: "Title: x\n",
+			body:        "body\n",
+		},
+		{
+			name:        "hyphens-in-value",
+			data:        "---\nTitle: foo --- bar\n---\nbody\n",
+			frontmatter: "Title: foo --- bar\n",
+			body:        "body\n",
+		},
+		{
+			name:        "hyphens-at-line-start",
+			data:        "---\nTitle: x\n---x\n---\nbody\n",
+			frontmatter: "Title: x\n---x\n",
+			body:        "body\n",
+		},
+		{
+			name:        "crlf",
+			data:        "---\r\nTitle: x\r\n---\r\nbody\r\n",
+			frontmatter: "Title: x\r\n",
+			body:        "body\r\n",
+		},
+		{
+			name:        "fence-trailing-whitespace",
+			data:        "--- \nTitle: x\n---\t \nbody\n",
+			frontmatter: "Title: x\n",
+			body:        "body\n",
+		},
+		{
+			name:        "end-fence-at-eof",
+			data:        "---\nTitle: x\n---",
+			frontmatter: "Title: x\n",
+		},
+		{
+			name: "empty-frontmatter",
+			data: "---\n---\nbody\n",
+			body: "body\n",
+		},
+		{
+			name: "missing-start-fence",
+			data: "Title: x\n---\nbody\n",
+			err:  ErrFrontmatterMissingStartFence,
+		},
+		{
+			name: "long-start-fence",
+			data: "----\nTitle: x\n---\nbody\n",
+			err:  ErrFrontmatterMissingStartFence,
+		},
+		{
+			name: "missing-end-fence",
+			data: "---\nTitle: foo --- bar\nbody\n",
+			err:  ErrFrontmatterMissingEndFence,
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			frontmatter, body, err := splitFrontmatter([]byte(tc.data))
+			if !errors.Is(err, tc.err) {
+				t.Fatalf("wanted error `%v`; found `%v`", tc.err, err)
+			}
+			if string(frontmatter) != tc.frontmatter {
+				t.Errorf(
+					"wanted frontmatter %q; found %q",
+					tc.frontmatter,
+					frontmatter,
+				)
+			}
+			if string(body) != tc.body {
+				t.Errorf("wanted body %q; found %q", tc.body, body)
+			}
+		})
+	}
+}
+
+func TestParsePostError(t *testing.T) {
+	for _, tc := range []struct {
+		name string
+		data string
+
+		// prefix is the start of the wanted message, and contains lists
+		// fragments found in the rest of it
+		prefix   string
+		contains []string
+	}{
+		{
+			name:   "missing-date",
+			data:   "---\nTitle: x\n---\nbody\n",
+			prefix: "posts/foo.md:1: missing field `Date`",
+		},
+		{
+			name:   "missing-title",
+			data:   "---\nDate: 2024-01-01\n---\nbody\n",
+			prefix: "posts/foo.md:1: missing field `Title`",
+		},
+		{
+			name:   "wrong-type",
+			data:   "---\nDate: 2024-01-01\nTitle: [a, b]\n---\nbody\n",
+			prefix: "posts/foo.md:3: cannot unmarshal",
+		},
+		{
+			name:     "invalid-date",
+			data:     "---\nTitle: x\nDate: someday\n---\nbody\n",
+			prefix:   "posts/foo.md:3:7: invalid date `someday`",
+			contains: []string{"accepted formats"},
+		},
+		{
+			name:     "day-first-date",
+			data:     "---\nTitle: x\nDate: 15-01-2024\n---\nbody\n",
+			prefix:   "posts/foo.md:3:7: invalid date `15-01-2024`",
+			contains: []string{"did you mean `2024-01-15`?"},
+		},
+		{
+			name:     "invalid-slug",
+			data:     "---\nTitle: x\nDate: 2024-01-01\nSlug: a/b\n---\n",
+			prefix:   "posts/foo.md: invalid slug `a/b`",
+			contains: []string{"slugs must be file names"},
+		},
+		{
+			name: "early-update",
+			data: "---\nTitle: x\nDate: 2024-01-02\n" +
+				"Updated: 2024-01-01\n---\n",
+			prefix: "posts/foo.md: `Updated` (2024-01-01) is earlier than " +
+				"`Date` (2024-01-02)",
+		},
+		{
+			name: "colliding-key",
+			data: "---\nTitle: x\nDate: 2024-01-01\ntags: [a]\n---\n",
+			prefix: "posts/foo.md: frontmatter key `tags` collides with " +
+				"the built-in key `Tags`",
+		},
+		{
+			name: "relative-discussion",
+			data: "---\nTitle: x\nDate: 2024-01-01\n" +
+				"Discussion: /thread\n---\n",
+			prefix: "posts/foo.md:4:13: url `/thread` is not absolute",
+		},
+		{
+			name:   "missing-fence",
+			data:   "Title: x\n",
+			prefix: "posts/foo.md: scanning frontmatter: missing start fence",
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			_, err := ParsePost([]byte(tc.data), "posts/foo.md")
+			var parseErr *ParsePostError
+			if !errors.As(err, &parseErr) {
+				t.Fatalf("wanted a `*ParsePostError`; found `%v`", err)
+			}
+			msg := err.Error()
+			if !strings.HasPrefix(msg, tc.prefix) {
+				t.Errorf("wanted the prefix %q; found %q", tc.prefix, msg)
+			}
+			for _, fragment := range tc.contains {
+				if !strings.Contains(msg, fragment) {
+					t.Errorf("wanted %q in %q", fragment, msg)
+				}
+			}
+		})
+	}
+}
+
+func TestInvalidDateError(t *testing.T) {
+	for _, tc := range []struct {
+		name  string
+		value string
+		hint  string
+	}{
+		{name: "unparseable", value: "someday"},
+		{
+			name:  "day-first",
+			value: "16-04-2021",
+			hint:  "dates are written year first; did you mean `2021-04-16`?",
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			_, err := ParsePost(
+				[]byte("---\nTitle: x\nDate: "+tc.value+"\n---\nbody\n"),
+				"posts/foo.md",
+			)
+			var dateErr *InvalidDateError
+			if !errors.As(err, &dateErr) {
+				t.Fatalf("wanted an `*InvalidDateError`; found `%v`", err)
+			}
+			if dateErr.Value != tc.value ||
+				dateErr.Line != 3 ||
+				dateErr.Column != 7 {
+				t.Errorf(
+					"wanted `%s` at 3:7; found `%s` at %d:%d",
+					tc.value,
+					dateErr.Value,
+					dateErr.Line,
+					dateErr.Column,
+				)
+			}
+			if dateErr.Hint != tc.hint {
+				t.Errorf("wanted the hint %q; found %q", tc.hint, dateErr.Hint)
+			}
+
+			// the message echoes the value and lists every accepted format
+			msg := err.Error()
+			for _, fragment := range append(
+				[]string{"`" + tc.value + "`", tc.hint},
+				acceptedDateFormats...,
+			) {
+				if !strings.Contains(msg, fragment) {
+					t.Errorf("wanted %q in %q", fragment, msg)
+				}
+			}
+		})
+	}
+}
+
+func TestExtraFrontmatter(t *testing.T) {
+	output, err := buildProject(writeProject(t, testTheme, map[string]string{
+		"theme/post.html": "{{ with .Content.Extra }}{{ .Subtitle }}|" +
+			"{{ .Rating }}|{{ .Pinned }}|" +
+			"{{ range .Images }}{{ . }};{{ end }}|{{ .Social.Handle }}" +
+			"{{ end }}",
+		"theme/index.html": "{{ range .Content.Posts }}" +
+			"{{ .Content.Extra.Subtitle }}{{ end }}",
+		"posts/a.md": "---\nTitle: A\nDate: 2024-01-01\n" +
+			"Subtitle: An introduction\nRating: 4.5\nPinned: true\n" +
+			"Images: [a.png, b.png]\nSocial:\n  Handle: me\n---\n",
+	}))
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	// strings, numbers, booleans, lists, and maps are all usable, and the
+	// index's posts have them too
+	for p, wanted := range map[string]string{
+		"posts/a.html": "An introduction|4.5|true|a.png;b.png;|me",
+		"index.html":   "An introduction",
+	} {
+		data, err := util.ReadFile(output, p)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if string(data) != wanted {
+			t.Errorf("`%s`: wanted %q; found %q", p, wanted, data)
+		}
+	}
+}
+
+func TestDates(t *testing.T) {
+	offset := time.FixedZone("", 2*60*60)
+	for _, tc := range []struct {
+		value  string
+		wanted time.Time
+
+		// written is the date as it's written back (e.g., by `import`)
+		written string
+	}{
+		{
+			value:   "2024-01-02",
+			wanted:  time.Date(2024, 1, 2, 0, 0, 0, 0, time.UTC),
+			written: "2024-01-02",
+		},
+		{
+			value:   "2024-01-02 09:30",
+			wanted:  time.Date(2024, 1, 2, 9, 30, 0, 0, time.UTC),
+			written: "2024-01-02T09:30:00Z",
+		},
+		{
+			value:   "2024-01-02T09:30:15",
+			wanted:  time.Date(2024, 1, 2, 9, 30, 15, 0, time.UTC),
+			written: "2024-01-02T09:30:15Z",
+		},
+		{
+			value:   "2024-01-02T09:30:00+02:00",
+			wanted:  time.Date(2024, 1, 2, 9, 30, 0, 0, offset),
+			written: "2024-01-02T09:30:00+02:00",
+		},
+	} {
+		t.Run(tc.value, func(t *testing.T) {
+			p, err := ParsePost(
+				[]byte("---\nTitle: x\nDate: "+tc.value+"\n---\n"),
+				"posts/foo.md",
+			)
+			if err != nil {
+				t.Fatal(err)
+			}
+			if found := time.Time(p.Date); !found.Equal(tc.wanted) {
+				t.Errorf("wanted %s; found %s", tc.wanted, found)
+			}
+			if found := p.Date.String(); found != tc.written {
+				t.Errorf(
+					"wanted it written `%s`; found `%s`",
+					tc.written,
+					found,
+				)
+			}
+		})
+	}
+}
+
+func TestPostsOrderedByTime(t *testing.T) {
+	// posts on the same day are ordered by time, and posts published at the
+	// same moment by their paths
+	output, err := buildProject(writeProject(t, testTheme, map[string]string{
+		"futhorc.jsonc": `{"feeds": ["atom"]}`,
+		"posts/a.md":    "---\nTitle: A\nDate: 2024-01-02 18:00\n---\n",
+		"posts/b.md":    "---\nTitle: B\nDate: 2024-01-02 09:00\n---\n",
+		"posts/c.md": "---\nTitle: C\n" +
+			"Date: 2024-01-02T12:00:00+02:00\n---\n",
+		"posts/d.md": "---\nTitle: D\nDate: 2024-01-02 10:00\n---\n",
+	}))
+	if err != nil {
+		t.Fatal(err)
+	}
+	data, err := util.ReadFile(output, "index.html")
+	if err != nil {
+		t.Fatal(err)
+	}
+	if wanted := postURLs("a", "d", "c", "b"); string(data) != wanted {
+		t.Errorf("wanted the index %q; found %q", wanted, data)
+	}
+
+	// the feed uses the parsed dates as they are
+	var atom struct {
+		Entries []struct {
+			Published string `xml:"published"`
+		} `xml:"entry"`
+	}
+	readXML(t, output, "index.atom", &atom)
+	var published []string
+	for _, entry := range atom.Entries {
+		published = append(published, entry.Published)
+	}
+	wanted := []string{
+		"2024-01-02T18:00:00Z",
+		"2024-01-02T10:00:00Z",
+		"2024-01-02T12:00:00+02:00",
+		"2024-01-02T09:00:00Z",
+	}
+	if !slices.Equal(published, wanted) {
+		t.Errorf("wanted the entries published %v; found %v", wanted, published)
+	}
+}
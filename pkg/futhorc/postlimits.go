@@ -0,0 +1,83 @@
+package futhorc
+
+import (
+	"fmt"
+	"time"
+)
+
+// PostLimits guards the build against very large posts and pathological
+// markdown. None of the defaults should trigger for normal content.
+type PostLimits struct {
+	// MaxSourceKB is the size in kilobytes above which a post's source is an
+	// error (defaults to 4096).
+	MaxSourceKB int `json:"maxSourceKB"`
+
+	// MaxNesting is the depth of nested markdown elements (e.g., blockquotes
+	// within lists within blockquotes) above which a post is an error
+	// (defaults to 64).
+	MaxNesting int `json:"maxNesting"`
+
+	// SlowRenderMS is the time in milliseconds above which converting a
+	// post logs a warning (defaults to 2000).
+	SlowRenderMS int `json:"slowRenderMS"`
+}
+
+// withDefaults validates the settings and fills in the defaults for any unset
+// values.
+func (limits PostLimits) withDefaults() (PostLimits, error) {
+	if limits.MaxSourceKB < 0 || limits.MaxNesting < 0 ||
+		limits.SlowRenderMS < 0 {
+		return limits, fmt.Errorf(
+			"invalid post limits: `maxSourceKB` (%d), `maxNesting` (%d), "+
+				"and `slowRenderMS` (%d) must not be negative",
+			limits.MaxSourceKB,
+			limits.MaxNesting,
+			limits.SlowRenderMS,
+		)
+	}
+	if limits.MaxSourceKB == 0 {
+		limits.MaxSourceKB = defaultMaxSourceKB
+	}
+	if limits.MaxNesting == 0 {
+		limits.MaxNesting = defaultMaxNesting
+	}
+	if limits.SlowRenderMS == 0 {
+		limits.SlowRenderMS = defaultSlowRenderMS
+	}
+	return limits, nil
+}
+
+// SlowRender returns the conversion time above which a post is slow.
+func (limits *PostLimits) SlowRender() time.Duration {
+	return time.Duration(limits.SlowRenderMS) * time.Millisecond
+}
+
+// slow reports whether a post which took `elapsed` to convert is slow.
+func (limits *PostLimits) slow(elapsed time.Duration) bool {
+	return elapsed > limits.SlowRender()
+}
+
+// PostTooLargeError is returned when a post's source exceeds
+// `PostLimits.MaxSourceKB`.
+type PostTooLargeError struct {
+	Path string
+
+	// Size and Limit are in bytes.
+	Size  int
+	Limit int
+}
+
+func (err *PostTooLargeError) Error() string {
+	return fmt.Sprintf(
+		"post `%s` is too large: %d bytes exceeds the limit of %d bytes",
+		err.Path,
+		err.Size,
+		err.Limit,
+	)
+}
+
+const (
+	defaultMaxSourceKB  = 4096
+	defaultMaxNesting   = 64
+	defaultSlowRenderMS = 2000
+)
@@ -0,0 +1,83 @@
+package futhorc
+
+import (
+	"errors"
+	"strings"
+	"testing"
+	"time"
+)
+
+func TestMaxSourceKB(t *testing.T) {
+	limits := PostLimits{MaxSourceKB: 1}
+	const frontmatter = "---\nTitle: x\nDate: 2024-01-01\n---\n"
+	for _, tc := range []struct {
+		name    string
+		size    int
+		exceeds bool
+	}{
+		{name: "below", size: 1023},
+		{name: "at", size: 1024},
+		{name: "above", size: 1025, exceeds: true},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			data := frontmatter +
+				strings.Repeat("x", tc.size-len(frontmatter))
+			_, err := readPost(
+				FileData{Path: "big.md", Data: []byte(data)},
+				false,
+				&limits,
+			)
+			var tooLarge *PostTooLargeError
+			if errors.As(err, &tooLarge) != tc.exceeds {
+				t.Fatalf("exceeds: wanted %t; found `%v`", tc.exceeds, err)
+			}
+			if !tc.exceeds && err != nil {
+				t.Fatalf("unexpected error: %v", err)
+			}
+			if tc.exceeds && (tooLarge.Path != "big.md" ||
+				tooLarge.Size != tc.size ||
+				tooLarge.Limit != 1024) {
+				t.Errorf(
+					"wanted `big.md` with %d bytes over 1024; found `%v`",
+					tc.size,
+					err,
+				)
+			}
+		})
+	}
+}
+
+func TestSlowRender(t *testing.T) {
+	limits := PostLimits{SlowRenderMS: 100}
+	for _, tc := range []struct {
+		name    string
+		elapsed time.Duration
+		slow    bool
+	}{
+		{name: "below", elapsed: 99 * time.Millisecond},
+		{name: "at", elapsed: 100 * time.Millisecond},
+		{name: "above", elapsed: 101 * time.Millisecond, slow: true},
+	} {
+		if found := limits.slow(tc.elapsed); found != tc.slow {
+			t.Errorf("%s: wanted slow %t; found %t", tc.name, tc.slow, found)
+		}
+	}
+}
+
+func TestPostLimitsDefaults(t *testing.T) {
+	limits, err := PostLimits{MaxNesting: 8}.withDefaults()
+	if err != nil {
+		t.Fatal(err)
+	}
+	wanted := PostLimits{
+		MaxSourceKB:  defaultMaxSourceKB,
+		MaxNesting:   8,
+		SlowRenderMS: defaultSlowRenderMS,
+	}
+	if limits != wanted {
+		t.Errorf("wanted %+v; found %+v", wanted, limits)
+	}
+	if _, err := (PostLimits{MaxSourceKB: -1}).withDefaults(); err == nil {
+		t.Error("wanted an error for a negative limit")
+	}
+}
@@ -1,23 +1,47 @@
 package futhorc
 
 import (
+	"cmp"
+	"fmt"
 	"futhorc/pkg/markdown"
 	"html/template"
 	"net/url"
+	"path"
+	"path/filepath"
 	"strings"
 	"time"
 )
 
 type PostPageConverter struct {
-	Markdown markdown.Config
+	Markdown    markdown.Config
+	Dates       DateFormatter
+	ReadingTime ReadingTime
+
+	// UncategorizedTag, if set, is the implicit tag given to posts without
+	// any tags.
+	UncategorizedTag string
+
+	// Sources is the directory of the posts' source files, against which
+	// their relative links are resolved, if it isn't `Directory` (e.g., for
+	// previews, which are written elsewhere).
+	Sources string
 	PageConverter[Post]
 }
 
 func (converter *PostPageConverter) Convert(
 	p *Post,
 ) (content Page[Post], err error) {
+	if len(p.Tags) < 1 && converter.UncategorizedTag != "" {
+		p.Tags = []Link{{
+			Text:     converter.UncategorizedTag,
+			Slug:     normalizeTag(converter.UncategorizedTag),
+			Implicit: true,
+		}}
+	}
+
 	content.Content = *p
-	content.Content.Path = convertPath(p.Path)
+	content.Content.Source = p.Path
+	content.Content.Path = converter.outputPath(p)
 	if content, err = converter.PageConverter.Convert(
 		content.Content.Path,
 		time.Time(p.Date).UnixNano(),
@@ -27,40 +51,79 @@ func (converter *PostPageConverter) Convert(
 	}
 
 	for i := range p.Tags {
-		p.Tags[i].URL = template.URL(converter.tagURL(p.Tags[i].Text).String())
+		p.Tags[i].URL = template.URL(converter.tagURL(p.Tags[i].Slug).String())
 	}
 
-	content.Content.Body = markdown.Convert(
-		&converter.Markdown,
+	// relative links are relative to the post's source, which is in a
+	// different directory than a pretty post's output (or a preview)
+	source := content.URL
+	if converter.PrettyURLs || converter.Sources != "" {
+		source = converter.BaseURL.JoinPath(
+			cmp.Or(converter.Sources, converter.Directory),
+			convertPath(p.Path, false),
+		)
+	}
+	doc, err := markdown.Convert(&converter.Markdown, source, p.Body)
+	if err != nil {
+		err = fmt.Errorf("converting post `%s`: %w", p.Path, err)
+		return
+	}
+	content.Content.markdown = p.Body
+	content.Content.Body = doc.HTML
+	content.Content.WordCount = doc.Words
+	content.Content.CharacterCount = doc.Characters
+	content.Content.TOC = buildTOC(content.URL, doc.Headings)
+	content.Content.Links = doc.Links
+	content.Content.ReadingTime = converter.ReadingTime.Minutes(
+		doc.Words,
+		doc.CodeLines,
+	)
+	content.Content.Snippet = markdown.AbsoluteFootnotes(
+		doc.Snippet,
 		content.URL,
-		p.Body,
 	)
-	content.Content.Snippet = snippet(content.Content.Body)
+	if p.ContentWarning != "" {
+		content.Content.Snippet = collapse(
+			p.ContentWarning,
+			content.Content.Snippet,
+		)
+	}
+	content.Content.DateDisplay = converter.Dates.Format(time.Time(p.Date))
+	content.Content.DateParts = converter.Dates.Parts(time.Time(p.Date))
 	return
 }
 
 func (converter *PostPageConverter) tagURL(tag string) *url.URL {
-	return converter.BaseURL.JoinPath(tag, "index.html")
+	return indexURL(converter.BaseURL, tag, converter.PrettyURLs)
 }
 
-func snippet(data template.HTML) template.HTML {
-	if idx := strings.Index(string(data), "<!-- more -->"); idx >= 0 {
-		return data[:idx]
-	} else if idx := strings.Index(string(data), "</p>"); idx >= 0 {
-		const max = 1024
-		if idx > max {
-			idx = max
-		}
-		return data[:idx]
+// collapse wraps `snippet` in a collapsed `<details>` element whose summary is
+// the content warning `warning`. An empty snippet is left empty.
+func collapse(warning string, snippet template.HTML) template.HTML {
+	if snippet == "" {
+		return snippet
 	}
-	return ""
+	return template.HTML(fmt.Sprintf(
+		`<details class="content-warning"><summary>%s</summary>%s</details>`,
+		template.HTMLEscapeString(warning),
+		snippet,
+	))
 }
 
-func convertPath(p string) string {
-	if strings.HasSuffix(p, markdownSuffix) {
-		return p[:len(p)-len(markdownSuffix)] + htmlSuffix
+// convertPath returns the output path of the post at source path `p`. With
+// pretty URLs, each post is written to an `index.html` in a directory of its
+// own (`foo.md` becomes `foo/index.html`), so a bundle's `foo/index.md` and a
+// plain `foo.md` both become `foo/index.html`.
+func convertPath(p string, pretty bool) string {
+	p = filepath.ToSlash(p)
+	if !strings.HasSuffix(p, markdownSuffix) {
+		return p
 	}
-	return p
+	p = p[:len(p)-len(markdownSuffix)]
+	if pretty && path.Base(p) != "index" {
+		return path.Join(p, indexFile)
+	}
+	return p + htmlSuffix
 }
 
 type Page[T any] struct {
@@ -70,14 +133,13 @@ type Page[T any] struct {
 	URL     *url.URL
 }
 
+// Compare orders pages by `Order`, breaking ties by path so that the order is
+// stable (e.g., for posts published at the same time).
 func (c *Page[T]) Compare(other *Page[T]) int {
-	if c.Order < other.Order {
-		return -1
-	} else if c.Order == other.Order {
-		return 0
-	} else {
-		return 1
-	}
+	return cmp.Or(
+		cmp.Compare(c.Order, other.Order),
+		cmp.Compare(c.Path, other.Path),
+	)
 }
 
 const markdownSuffix = ".md"
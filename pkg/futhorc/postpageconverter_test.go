@@ -0,0 +1,177 @@
+package futhorc
+
+import (
+	"encoding/json"
+	"strings"
+	"testing"
+
+	"github.com/go-git/go-billy/v5/util"
+)
+
+func TestFootnoteLinks(t *testing.T) {
+	output, err := buildProject(writeProject(t, testTheme, map[string]string{
+		"theme/index.html": "{{ range .Content.Posts }}" +
+			"{{ .Content.Snippet }}{{ end }}",
+		"posts/a.md": "---\nTitle: A\nDate: 2024-01-01\n---\n\n" +
+			"Alpha[^1].\n\n[^1]: First.\n",
+	}))
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	// the post page links to its own footnotes by fragment, while the index
+	// and the feed link to the footnotes on the post page
+	const absolute = "https://example.com/posts/a.html#fn:1"
+	for _, tc := range []struct {
+		path   string
+		wanted string
+		absent string
+	}{
+		{path: "posts/a.html", wanted: `href="#fn:1"`, absent: absolute},
+		{path: "index.html", wanted: `href="` + absolute + `"`},
+		{path: "index.json", wanted: absolute},
+	} {
+		data, err := util.ReadFile(output, tc.path)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if !strings.Contains(string(data), tc.wanted) {
+			t.Errorf("`%s`: wanted %q in %q", tc.path, tc.wanted, data)
+		}
+		if tc.absent != "" && strings.Contains(string(data), tc.absent) {
+			t.Errorf("`%s`: wanted no %q in %q", tc.path, tc.absent, data)
+		}
+	}
+}
+
+func TestRootRelativeLinks(t *testing.T) {
+	const links = "[post](/posts/other.md) [slugged](/posts/2021-hello.md) " +
+		"[asset](/posts/trip/photo.png) [static](/static/logo.png)\n"
+	output, err := buildProject(writeProject(t, testTheme, map[string]string{
+		"posts/loose.md": "---\nTitle: Loose\nDate: 2024-01-01\n---\n\n" +
+			links,
+		"posts/trip/index.md": "---\nTitle: Trip\nDate: 2024-01-02\n" +
+			"---\n\n" + links,
+		"posts/trip/photo.png": "photo",
+		"posts/other.md":       "---\nTitle: Other\nDate: 2024-01-03\n---\n",
+		"posts/2021-hello.md": "---\nTitle: Hello\nDate: 2024-01-04\n" +
+			"Slug: hello\n---\n",
+	}))
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	// the links resolve against the site root whether or not the linking
+	// post is in a bundle (a directory of its own)
+	for _, p := range []string{"posts/loose.html", "posts/trip/index.html"} {
+		data, err := util.ReadFile(output, p)
+		if err != nil {
+			t.Fatal(err)
+		}
+		for _, target := range []string{
+			"posts/other.html",
+			"posts/hello.html",
+			"posts/trip/photo.png",
+			"static/logo.png",
+		} {
+			href := `href="` + goldenSiteRoot + target + `"`
+			if !strings.Contains(string(data), href) {
+				t.Errorf("`%s`: wanted %q in %q", p, href, data)
+			}
+		}
+	}
+}
+
+func TestContentWarning(t *testing.T) {
+	output, err := buildProject(writeProject(t, testTheme, map[string]string{
+		"futhorc.jsonc": `{"indexJSON": true}`,
+		"theme/index.html": "{{ range .Content.Posts }}" +
+			"[{{ .Content.Snippet }}]{{ end }}",
+		"theme/post.html": "{{ .Content.Snippet }}",
+		"posts/a.md": "---\nTitle: A\nDate: 2024-01-02\n" +
+			"ContentWarning: Spoilers & gore\n---\n\n" +
+			"Above the fold.\n\n<!-- more -->\n\nBelow the fold.\n",
+		"posts/b.md": "---\nTitle: B\nDate: 2024-01-01\n" +
+			"ContentWarning: Nothing\n---\n",
+	}))
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	// a's snippet is collapsed behind its warning, while b's empty snippet
+	// is left empty rather than collapsed
+	const collapsed = `<details class="content-warning">` +
+		"<summary>Spoilers &amp; gore</summary><p>Above the fold.</p>"
+	for _, tc := range []struct {
+		path   string
+		wanted string
+	}{
+		{path: "posts/a.html", wanted: collapsed},
+		{path: "posts/b.html", wanted: ""},
+		{path: "index.html", wanted: "[" + collapsed},
+	} {
+		data, err := util.ReadFile(output, tc.path)
+		if err != nil {
+			t.Fatal(err)
+		}
+		page := string(data)
+		if !strings.Contains(page, tc.wanted) {
+			t.Errorf("`%s`: wanted %q in %q", tc.path, tc.wanted, page)
+		}
+		wanted := strings.Count(tc.wanted, "<details")
+		if found := strings.Count(page, "<details"); found != wanted {
+			t.Errorf(
+				"`%s`: wanted %d collapsed snippets; found %d in %q",
+				tc.path,
+				wanted,
+				found,
+				page,
+			)
+		}
+		if strings.Contains(page, "Nothing") {
+			t.Errorf("`%s`: wanted no empty details in %q", tc.path, page)
+		}
+	}
+	if data, err := util.ReadFile(output, "index.html"); err != nil ||
+		!strings.Contains(string(data), "[]") {
+		t.Errorf("`index.html`: wanted b's empty snippet; found %q", data)
+	}
+
+	// the feed entries' summaries are the warnings, and the index data has
+	// each post's warning beside its (collapsed) snippet
+	var feed struct {
+		Items []struct {
+			Summary string `json:"summary"`
+		} `json:"items"`
+	}
+	var index struct {
+		Posts []struct {
+			Snippet string `json:"snippet"`
+			Warning string `json:"contentWarning"`
+		} `json:"posts"`
+	}
+	for p, v := range map[string]any{
+		"index.json":                &feed,
+		indexDataPath("index.html"): &index,
+	} {
+		data, err := util.ReadFile(output, p)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if err := json.Unmarshal(data, v); err != nil {
+			t.Fatalf("decoding `%s`: %v", p, err)
+		}
+	}
+	if len(feed.Items) != 2 ||
+		feed.Items[0].Summary != "Spoilers & gore" ||
+		feed.Items[1].Summary != "Nothing" {
+		t.Errorf("wanted the warnings as the feed's summaries; found %+v", feed)
+	}
+	if len(index.Posts) != 2 ||
+		index.Posts[0].Warning != "Spoilers & gore" ||
+		!strings.HasPrefix(index.Posts[0].Snippet, collapsed) ||
+		index.Posts[1].Warning != "Nothing" ||
+		index.Posts[1].Snippet != "" {
+		t.Errorf("wanted each post's warning and snippet; found %+v", index)
+	}
+}
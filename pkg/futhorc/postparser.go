@@ -1,12 +1,25 @@
 package futhorc
 
 import (
+	"cmp"
 	"context"
+	"errors"
+	"fmt"
 	"futhorc/pkg/actor"
+	"log/slog"
+	"slices"
+	"sync"
+	"time"
 )
 
+// PostParser parses and converts post files. A post which fails doesn't stop
+// the others; instead, the parser fails once every post has been attempted,
+// with an error listing each failed post (see `PostErrors`).
 type PostParser struct {
-	actor.Map[FileData, Page[Post]]
+	actor.Input[FileData]
+	Output chan Page[Post]
+
+	errs *PostErrors
 }
 
 func NewPostParser(
@@ -14,20 +27,131 @@ func NewPostParser(
 	concurrency int,
 	files <-chan FileData,
 	converter *PostPageConverter,
+	lossyEncoding bool,
+	limits *PostLimits,
 ) (parser PostParser) {
-	parser.Map = actor.NewMap(
+	parser.Output = make(chan Page[Post])
+	parser.errs = &PostErrors{}
+	output, errs := parser.Output, parser.errs
+	parser.Input = actor.NewInput(
 		name,
 		concurrency,
 		files,
-		func(
-			ctx context.Context,
-			file FileData,
-		) (page Page[Post], err error) {
-			if page.Content, err = ParsePost(file.Data, file.Path); err != nil {
-				return
+		func(ctx context.Context, file FileData) error {
+			page, err := parsePost(file, converter, lossyEncoding, limits)
+			if err != nil {
+				errs.add(file.Path, err)
+				return nil
+			}
+			select {
+			case <-ctx.Done():
+				return ctx.Err()
+			case output <- page:
+				return nil
 			}
-			return converter.Convert(&page.Content)
 		},
+		nil,
 	)
 	return
 }
+
+// Run parses every post. If any fail, it returns their `PostErrors` without
+// closing `Output`, so nothing downstream proceeds with a partial set of
+// posts before the pipeline is canceled.
+func (parser *PostParser) Run(ctx context.Context) error {
+	if err := parser.Input.Run(ctx); err != nil {
+		close(parser.Output)
+		return err
+	}
+	if len(parser.errs.Errs) > 0 {
+		parser.errs.sort()
+		return parser.errs
+	}
+	close(parser.Output)
+	return nil
+}
+
+func parsePost(
+	file FileData,
+	converter *PostPageConverter,
+	lossyEncoding bool,
+	limits *PostLimits,
+) (page Page[Post], err error) {
+	if page.Content, err = readPost(file, lossyEncoding, limits); err != nil {
+		return
+	}
+	start := time.Now()
+	page, err = converter.Convert(&page.Content)
+	if elapsed := time.Since(start); limits.slow(elapsed) {
+		slog.Warn(
+			"slow post",
+			"post", file.Path,
+			"elapsed", elapsed,
+		)
+	}
+	return
+}
+
+// readPost checks the post file against `limits` and its encoding (see
+// `checkEncoding`) and parses it.
+func readPost(
+	file FileData,
+	lossyEncoding bool,
+	limits *PostLimits,
+) (Post, error) {
+	if limit := limits.MaxSourceKB * 1024; len(file.Data) > limit {
+		return Post{}, &PostTooLargeError{
+			Path:  file.Path,
+			Size:  len(file.Data),
+			Limit: limit,
+		}
+	}
+	if err := checkEncoding(&file, lossyEncoding); err != nil {
+		return Post{}, err
+	}
+	return ParsePost(file.Data, file.Path)
+}
+
+// PostErrors is returned when any posts fail to parse or convert. It lists
+// each post's error, which names the post, on its own line.
+type PostErrors struct {
+	mu   sync.Mutex
+	Errs []error
+
+	// paths are the source paths of the posts whose errors are in `Errs`
+	paths []string
+}
+
+func (errs *PostErrors) add(path string, err error) {
+	errs.mu.Lock()
+	defer errs.mu.Unlock()
+	errs.Errs = append(errs.Errs, err)
+	errs.paths = append(errs.paths, path)
+}
+
+// sort orders the errors by their posts' paths, since the posts are parsed
+// concurrently.
+func (errs *PostErrors) sort() {
+	order := make([]int, len(errs.Errs))
+	for i := range order {
+		order[i] = i
+	}
+	slices.SortStableFunc(order, func(a, b int) int {
+		return cmp.Compare(errs.paths[a], errs.paths[b])
+	})
+	sorted, paths := make([]error, len(order)), make([]string, len(order))
+	for i, j := range order {
+		sorted[i], paths[i] = errs.Errs[j], errs.paths[j]
+	}
+	errs.Errs, errs.paths = sorted, paths
+}
+
+func (errs *PostErrors) Error() string {
+	return fmt.Sprintf(
+		"%d posts failed:\n%s",
+		len(errs.Errs),
+		errors.Join(errs.Errs...),
+	)
+}
+
+func (errs *PostErrors) Unwrap() []error { return errs.Errs }
@@ -0,0 +1,61 @@
+package futhorc
+
+import (
+	"context"
+	"errors"
+	"strings"
+	"testing"
+
+	"github.com/go-git/go-billy/v5/memfs"
+)
+
+func TestPostErrors(t *testing.T) {
+	// the errors' messages sort in the opposite order to their posts' paths
+	// (`b.md:...` before `post `a.md`...`), so they must be ordered by path
+	dir := writeProject(t, testTheme, map[string]string{
+		"posts/a.md": "---\nTitle: A\nDate: 2024-01-01\n---\n\n" +
+			strings.Repeat("word ", 300),
+		"posts/b.md": "Title: B\n",
+		"posts/c.md": "---\nTitle: C\nDate: 2024-01-03\n---\n",
+	})
+	pipeline, err := LoadPipeline(dir, goldenSiteRoot)
+	if err != nil {
+		t.Fatal(err)
+	}
+	pipeline.OutputPath = ""
+	pipeline.OutputDirectory = memfs.New()
+	pipeline.PostLimits.MaxSourceKB = 1
+	err = pipeline.Run(context.Background())
+
+	var postErrs *PostErrors
+	if !errors.As(err, &postErrs) {
+		t.Fatalf("wanted `*PostErrors`; found `%v`", err)
+	}
+	if len(postErrs.Errs) != 2 {
+		t.Fatalf("wanted 2 errors; found %d: %v", len(postErrs.Errs), err)
+	}
+	var tooLarge *PostTooLargeError
+	if !errors.As(postErrs.Errs[0], &tooLarge) || tooLarge.Path != "a.md" {
+		t.Errorf(
+			"wanted `a.md` to be too large first; found `%v`",
+			postErrs.Errs[0],
+		)
+	}
+	var parseErr *ParsePostError
+	if !errors.As(postErrs.Errs[1], &parseErr) || parseErr.Path != "b.md" {
+		t.Errorf(
+			"wanted `b.md` to fail to parse second; found `%v`",
+			postErrs.Errs[1],
+		)
+	}
+
+	lines := strings.Split(postErrs.Error(), "\n")
+	if len(lines) != 3 || !strings.HasPrefix(lines[0], "2 posts failed") {
+		t.Fatalf("wanted a header and one line per post; found %q", lines)
+	}
+	for i, wanted := range postErrs.Errs {
+		if lines[i+1] != wanted.Error() {
+			t.Errorf("line %d: wanted %q; found %q", i+1, wanted, lines[i+1])
+		}
+	}
+}
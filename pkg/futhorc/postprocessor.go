@@ -0,0 +1,53 @@
+package futhorc
+
+import "net/url"
+
+// PagePostprocessor transforms each rendered page before it's written (e.g.,
+// to inject a script or rewrite legacy markup). Library users register them
+// on `Pipeline.Postprocessors`; the CLI doesn't expose them.
+type PagePostprocessor interface {
+	Process(page *PageMeta, html []byte) ([]byte, error)
+}
+
+// PagePostprocessorFunc adapts a function to a `PagePostprocessor`.
+type PagePostprocessorFunc func(page *PageMeta, html []byte) ([]byte, error)
+
+func (f PagePostprocessorFunc) Process(
+	page *PageMeta,
+	html []byte,
+) ([]byte, error) {
+	return f(page, html)
+}
+
+// PageMeta describes a rendered page so postprocessors can target pages by
+// kind, location, or source.
+type PageMeta struct {
+	Kind PageKind
+
+	// Path is the page's output path, and URL is its URL.
+	Path string
+	URL  *url.URL
+
+	// Source is the source path of a post (empty for other pages).
+	Source string
+}
+
+// Process applies the normalization, so it can terminate a chain of
+// postprocessors.
+func (n Normalization) Process(page *PageMeta, html []byte) ([]byte, error) {
+	return n.Normalize(html), nil
+}
+
+// postprocess passes `html` through each of the postprocessors in order.
+func postprocess(
+	chain []PagePostprocessor,
+	page *PageMeta,
+	html []byte,
+) (_ []byte, err error) {
+	for _, postprocessor := range chain {
+		if html, err = postprocessor.Process(page, html); err != nil {
+			return nil, err
+		}
+	}
+	return html, nil
+}
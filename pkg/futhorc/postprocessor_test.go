@@ -0,0 +1,65 @@
+package futhorc
+
+import (
+	"context"
+	"sync"
+	"testing"
+
+	"github.com/go-git/go-billy/v5/memfs"
+	"github.com/go-git/go-billy/v5/util"
+)
+
+func TestPostprocessors(t *testing.T) {
+	pipeline, err := LoadPipeline(
+		writeProject(t, testTheme, map[string]string{
+			"futhorc.jsonc": `{"outputNormalization": "trailing-newline"}`,
+			"posts/a.md":    "---\nTitle: A\nDate: 2024-01-01\n---\n",
+		}),
+		goldenSiteRoot,
+	)
+	if err != nil {
+		t.Fatal(err)
+	}
+	output := memfs.New()
+	pipeline.OutputPath = ""
+	pipeline.OutputDirectory = output
+
+	// the marker is appended to post pages only, with trailing blank lines
+	// for the normalization (which runs last) to trim
+	var mu sync.Mutex
+	var posts []PageMeta
+	pipeline.Postprocessors = []PagePostprocessor{PagePostprocessorFunc(
+		func(page *PageMeta, html []byte) ([]byte, error) {
+			if page.Kind != PageKindPost {
+				return html, nil
+			}
+			mu.Lock()
+			posts = append(posts, *page)
+			mu.Unlock()
+			return append(html, "<!-- marker -->\n\n\n"...), nil
+		},
+	)}
+	if err := pipeline.Run(context.Background()); err != nil {
+		t.Fatal(err)
+	}
+
+	wantedPost := postURLs("a")
+	for p, wanted := range map[string]string{
+		"posts/a.html": "<!-- marker -->\n",
+		"index.html":   wantedPost + "\n",
+	} {
+		data, err := util.ReadFile(output, p)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if string(data) != wanted {
+			t.Errorf("`%s`: wanted %q; found %q", p, wanted, data)
+		}
+	}
+	if len(posts) != 1 ||
+		posts[0].Path != "posts/a.html" ||
+		posts[0].Source != "a.md" ||
+		posts[0].URL.String() != wantedPost {
+		t.Errorf("wanted the metadata of `a.md`; found %+v", posts)
+	}
+}
@@ -0,0 +1,98 @@
+package futhorc
+
+import (
+	"context"
+	"fmt"
+	"futhorc/pkg/actor"
+	"log/slog"
+	"time"
+)
+
+// Publisher separates published posts from unpublished ones (drafts and, if
+// previews are enabled, posts dated in the future). Published posts continue
+// on to the rest of the pipeline, while unpublished posts are either dropped
+// or, if previews are enabled, converted into the preview subtree and sent to
+// `Previews`.
+type Publisher struct {
+	actor.Input[Page[Post]]
+	Published chan Page[Post]
+	Previews  chan *OrderedPage[Post]
+}
+
+// NewPublisher creates a publisher. `previews` converts unpublished posts into
+// preview pages; if it's nil, drafts are dropped, posts dated in the future
+// are published as usual, and `Previews` is nil. If `drafts` is true, drafts
+// are published like any other post.
+func NewPublisher(
+	name string,
+	pages <-chan Page[Post],
+	now time.Time,
+	drafts bool,
+	previews *PostPageConverter,
+) (publisher Publisher) {
+	publisher.Published = make(chan Page[Post])
+	if previews != nil {
+		publisher.Previews = make(chan *OrderedPage[Post])
+	}
+	publisher.Input = actor.NewInput(
+		name,
+		1,
+		pages,
+		func(ctx context.Context, page Page[Post]) error {
+			published := drafts || !page.Content.Draft
+			if previews != nil && time.Time(page.Content.Date).After(now) {
+				published = false
+			}
+			if published {
+				return send(ctx, publisher.Published, page)
+			}
+			if previews == nil {
+				slog.Debug(
+					"skipping unpublished post",
+					"post", page.Content.Path,
+				)
+				return nil
+			}
+
+			// the preview is converted again so that its page-relative
+			// links (e.g., its table of contents) point at the preview
+			preview, err := previews.Convert(page.Content.unconverted())
+			if err != nil {
+				return fmt.Errorf(
+					"creating preview for post `%s`: %w",
+					page.Content.Path,
+					err,
+				)
+			}
+			preview.Content.Preview = true
+			return send(
+				ctx,
+				publisher.Previews,
+				&OrderedPage[Post]{Page: preview},
+			)
+		},
+		nil,
+	)
+	return
+}
+
+func (publisher *Publisher) Run(ctx context.Context) error {
+	defer close(publisher.Published)
+	if publisher.Previews != nil {
+		defer close(publisher.Previews)
+	}
+	return publisher.Input.Run(ctx)
+}
+
+func send[T any](ctx context.Context, ch chan<- T, elt T) error {
+	select {
+	case <-ctx.Done():
+		return ctx.Err()
+	case ch <- elt:
+		return nil
+	}
+}
+
+// previewDirectory is the output directory for previews of unpublished posts.
+// Nothing in the published site links to it.
+const previewDirectory = "_preview"
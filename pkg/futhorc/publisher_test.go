@@ -0,0 +1,162 @@
+package futhorc
+
+import (
+	"bytes"
+	"context"
+	"io/fs"
+	"strings"
+	"testing"
+
+	"github.com/go-git/go-billy/v5"
+	"github.com/go-git/go-billy/v5/memfs"
+	"github.com/go-git/go-billy/v5/util"
+)
+
+func TestPreviewOutput(t *testing.T) {
+	output, err := buildProject(writeProject(t, testTheme, map[string]string{
+		"futhorc.jsonc": `{"previewOutput": true}`,
+		"posts/published.md": "---\nTitle: Published\n" +
+			"Date: 2024-01-01\n---\n",
+		"posts/work-in-progress.md": "---\nTitle: Work In Progress\n" +
+			"Date: 2024-01-02\nDraft: true\n---\n",
+		"posts/from-the-future.md": "---\nTitle: From The Future\n" +
+			"Date: 2999-01-01\n---\n",
+	}))
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	for _, p := range []string{
+		"posts/published.html",
+		"_preview/posts/work-in-progress.html",
+		"_preview/posts/from-the-future.html",
+	} {
+		if _, err := output.Stat(p); err != nil {
+			t.Errorf("wanted output file `%s`: %v", p, err)
+		}
+	}
+	checkNotMentioned(
+		t,
+		output,
+		previewDirectory,
+		"work-in-progress",
+		"Work In Progress",
+		"from-the-future",
+		"From The Future",
+	)
+}
+
+func TestDrafts(t *testing.T) {
+	files := map[string]string{
+		// each post page prints its draft flag and its neighbours' URLs
+		"theme/post.html": "draft={{ .Content.Draft }} " +
+			"prev={{ with .Prev }}{{ .Path }}{{ end }} " +
+			"next={{ with .Next }}{{ .Path }}{{ end }}",
+		"posts/a.md": "---\nTitle: A\nDate: 2024-01-01\n---\n",
+		"posts/b.md": "---\nTitle: B\nDate: 2024-01-02\nDraft: true\n---\n",
+		"posts/c.md": "---\nTitle: C\nDate: 2024-01-03\n---\n",
+	}
+	for _, tc := range []struct {
+		name   string
+		drafts bool
+		wanted map[string]string
+	}{
+		{
+			// the draft leaves no gap between its neighbours
+			name: "excluded",
+			wanted: map[string]string{
+				"index.html":   postURLs("c", "a"),
+				"posts/a.html": "draft=false prev= next=/posts/c.html",
+				"posts/c.html": "draft=false prev=/posts/a.html next=",
+			},
+		},
+		{
+			name:   "included",
+			drafts: true,
+			wanted: map[string]string{
+				"index.html":   postURLs("c", "b", "a"),
+				"posts/a.html": "draft=false prev= next=/posts/b.html",
+				"posts/b.html": "draft=true prev=/posts/a.html " +
+					"next=/posts/c.html",
+				"posts/c.html": "draft=false prev=/posts/b.html next=",
+			},
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			pipeline, err := LoadPipeline(
+				writeProject(t, testTheme, files),
+				goldenSiteRoot,
+			)
+			if err != nil {
+				t.Fatal(err)
+			}
+			output := memfs.New()
+			pipeline.OutputPath = ""
+			pipeline.OutputDirectory = output
+			pipeline.Drafts = tc.drafts
+			if err := pipeline.Run(context.Background()); err != nil {
+				t.Fatal(err)
+			}
+			for p, wanted := range tc.wanted {
+				data, err := util.ReadFile(output, p)
+				if err != nil {
+					t.Fatal(err)
+				}
+				if string(data) != wanted {
+					t.Errorf("`%s`: wanted %q; found %q", p, wanted, data)
+				}
+			}
+			if !tc.drafts {
+				checkNotMentioned(t, output, "", "posts/b.html")
+			}
+		})
+	}
+}
+
+// postURLs returns the concatenated URLs of the posts named `names`, as
+// listed by the test theme's index template.
+func postURLs(names ...string) string {
+	var urls strings.Builder
+	for _, name := range names {
+		urls.WriteString(goldenSiteRoot + "posts/" + name + ".html")
+	}
+	return urls.String()
+}
+
+// checkNotMentioned reports each file in `output` (except for those in the
+// directory `except`, if it isn't empty) whose path or contents mention any
+// of `mentions`.
+func checkNotMentioned(
+	t *testing.T,
+	output billy.Filesystem,
+	except string,
+	mentions ...string,
+) {
+	t.Helper()
+	if err := util.Walk(
+		output,
+		"/",
+		func(p string, info fs.FileInfo, err error) error {
+			if err != nil || info.IsDir() {
+				return err
+			}
+			p = strings.TrimPrefix(p, "/")
+			if except != "" && strings.HasPrefix(p, except+"/") {
+				return nil
+			}
+			data, err := util.ReadFile(output, p)
+			if err != nil {
+				return err
+			}
+			for _, mention := range mentions {
+				if strings.Contains(p, mention) ||
+					bytes.Contains(data, []byte(mention)) {
+					t.Errorf("output file `%s` mentions `%s`", p, mention)
+				}
+			}
+			return nil
+		},
+	); err != nil {
+		t.Fatal(err)
+	}
+}
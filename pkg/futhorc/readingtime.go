@@ -0,0 +1,45 @@
+package futhorc
+
+import "fmt"
+
+// ReadingTime estimates how long a post takes to read.
+type ReadingTime struct {
+	// WordsPerMinute is the reading speed for prose (defaults to 200).
+	WordsPerMinute int `json:"wordsPerMinute"`
+
+	// CodeLineWords is the number of words each line of a code block counts
+	// for, since code reads more slowly than prose (defaults to 5).
+	CodeLineWords int `json:"codeLineWords"`
+}
+
+// withDefaults validates the settings and fills in the defaults for any unset
+// values.
+func (rt ReadingTime) withDefaults() (ReadingTime, error) {
+	if rt.WordsPerMinute < 0 || rt.CodeLineWords < 0 {
+		return rt, fmt.Errorf(
+			"invalid reading time settings: `wordsPerMinute` (%d) and "+
+				"`codeLineWords` (%d) must not be negative",
+			rt.WordsPerMinute,
+			rt.CodeLineWords,
+		)
+	}
+	if rt.WordsPerMinute == 0 {
+		rt.WordsPerMinute = defaultWordsPerMinute
+	}
+	if rt.CodeLineWords == 0 {
+		rt.CodeLineWords = defaultCodeLineWords
+	}
+	return rt, nil
+}
+
+// Minutes returns the estimated reading time in whole minutes, rounding up so
+// that any non-empty post takes at least one minute.
+func (rt *ReadingTime) Minutes(words, codeLines int) int {
+	total := words + codeLines*rt.CodeLineWords
+	return (total + rt.WordsPerMinute - 1) / rt.WordsPerMinute
+}
+
+const (
+	defaultWordsPerMinute = 200
+	defaultCodeLineWords  = 5
+)
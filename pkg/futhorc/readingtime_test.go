@@ -0,0 +1,79 @@
+package futhorc
+
+import (
+	"fmt"
+	"strings"
+	"testing"
+
+	"github.com/go-git/go-billy/v5/util"
+)
+
+func TestReadingTime(t *testing.T) {
+	post := func(title string, words int, code string) string {
+		return fmt.Sprintf(
+			"---\nTitle: %s\nDate: 2024-01-01\n---\n\n%s\n%s",
+			title,
+			strings.TrimSpace(strings.Repeat("word ", words)),
+			code,
+		)
+	}
+	files := map[string]string{
+		// each post page prints its reading time and word count
+		"theme/post.html": "{{ .Content.ReadingTime }} " +
+			"{{ .Content.WordCount }}",
+		"posts/prose.md": post("Prose", 250, ""),
+		"posts/code.md": post(
+			"Code",
+			50,
+			"\n```go\n"+strings.Repeat("x := 1\n", 30)+"```\n",
+		),
+	}
+
+	for _, tc := range []struct {
+		name   string
+		config string
+
+		// wanted maps each post page to its reading time and word count; the
+		// code's lines aren't words
+		wanted map[string]string
+	}{
+		{
+			name:   "defaults",
+			config: `{}`,
+			wanted: map[string]string{
+				// 250 / 200 and (50 + 30*5) / 200 minutes, rounded up
+				"posts/prose.html": "2 250",
+				"posts/code.html":  "1 50",
+			},
+		},
+		{
+			name: "configured",
+			config: `{
+				"readingTime": {"wordsPerMinute": 100, "codeLineWords": 10}
+			}`,
+			wanted: map[string]string{
+				// 250 / 100 and (50 + 30*10) / 100 minutes, rounded up
+				"posts/prose.html": "3 250",
+				"posts/code.html":  "4 50",
+			},
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			dir := writeProject(t, testTheme, files)
+			writeFiles(t, dir, map[string]string{"futhorc.jsonc": tc.config})
+			output, err := buildProject(dir)
+			if err != nil {
+				t.Fatal(err)
+			}
+			for p, wanted := range tc.wanted {
+				data, err := util.ReadFile(output, p)
+				if err != nil {
+					t.Fatal(err)
+				}
+				if string(data) != wanted {
+					t.Errorf("`%s`: wanted %q; found %q", p, wanted, data)
+				}
+			}
+		})
+	}
+}
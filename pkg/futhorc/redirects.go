@@ -0,0 +1,130 @@
+package futhorc
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"html/template"
+	"net/url"
+	"slices"
+
+	"futhorc/pkg/actor"
+
+	"github.com/go-git/go-billy/v5"
+)
+
+// Redirect is a stub page at `Path` which redirects to `Target`.
+type Redirect struct {
+	Path   string
+	Target *url.URL
+}
+
+// TagRedirectCollisionError is returned when a renamed tag's old name is
+// still in use, so its index can't be replaced with redirects.
+type TagRedirectCollisionError struct {
+	Old string
+	New string
+}
+
+func (err *TagRedirectCollisionError) Error() string {
+	return fmt.Sprintf(
+		"redirecting tag `%s` to `%s`: `%s` is still a tag on some posts",
+		err.Old,
+		err.New,
+		err.Old,
+	)
+}
+
+// TagRedirectTargetError is returned when a renamed tag's new name isn't a
+// tag on any posts, so its redirects would lead nowhere.
+type TagRedirectTargetError struct {
+	Old string
+	New string
+}
+
+func (err *TagRedirectTargetError) Error() string {
+	return fmt.Sprintf(
+		"redirecting tag `%s` to `%s`: `%s` isn't a tag on any posts",
+		err.Old,
+		err.New,
+		err.New,
+	)
+}
+
+// tagRedirects plans the redirects from every page of each old tag's index to
+// the corresponding page of the new tag's index. The old tag's index has as
+// many pages as the new one (for which `pageCount` returns the count), and
+// always at least its first page. Each old tag must no longer be in use and
+// each new tag must be (according to `live`).
+func tagRedirects(
+	renames map[string]string,
+	live func(tag string) bool,
+	pageCount func(tag string) int,
+	base *url.URL,
+	pretty bool,
+) ([]Redirect, error) {
+	olds := make([]string, 0, len(renames))
+	for old := range renames {
+		olds = append(olds, old)
+	}
+	slices.Sort(olds)
+
+	var redirects []Redirect
+	var errs []error
+	for _, old := range olds {
+		tag := renames[old]
+		if live(old) {
+			errs = append(errs, &TagRedirectCollisionError{Old: old, New: tag})
+			continue
+		}
+		if !live(tag) {
+			errs = append(errs, &TagRedirectTargetError{Old: old, New: tag})
+			continue
+		}
+		for page := range max(pageCount(tag), 1) {
+			target := base.JoinPath(
+				indexPagePath(tag, page, pretty),
+			)
+			if pretty {
+				target = prettyURL(target)
+			}
+			redirects = append(redirects, Redirect{
+				Path:   indexPagePath(old, page, pretty),
+				Target: target,
+			})
+		}
+	}
+	return redirects, errors.Join(errs...)
+}
+
+// RedirectWriter writes a stub page for each redirect which sends browsers on
+// to the target with a meta refresh.
+func RedirectWriter(output billy.Filesystem) actor.InputCallback[Redirect] {
+	return func(ctx context.Context, redirect Redirect) error {
+		target := template.HTMLEscapeString(redirect.Target.String())
+		if _, err := writeFile(
+			output,
+			redirect.Path,
+			fmt.Appendf(nil, redirectStub, target),
+		); err != nil {
+			return fmt.Errorf(
+				"writing redirect `%s`: %w",
+				redirect.Path,
+				err,
+			)
+		}
+		return nil
+	}
+}
+
+const redirectStub = `<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Redirecting…</title>
+<link rel="canonical" href="%[1]s">
+<meta http-equiv="refresh" content="0; url=%[1]s">
+</head>
+<body><a href="%[1]s">This page has moved.</a></body>
+</html>
+`
@@ -0,0 +1,110 @@
+package futhorc
+
+import (
+	"errors"
+	"net/url"
+	"slices"
+	"testing"
+)
+
+func TestTagRedirects(t *testing.T) {
+	base, err := url.Parse("https://example.com/")
+	if err != nil {
+		t.Fatal(err)
+	}
+	pages := map[string]int{"go": 3, "rust": 1, "golang": 2}
+	live := func(tag string) bool {
+		_, found := pages[tag]
+		return found
+	}
+	pageCount := func(tag string) int { return pages[tag] }
+
+	type redirect struct{ path, target string }
+	for _, tc := range []struct {
+		name      string
+		renames   map[string]string
+		pretty    bool
+		want      []redirect
+		collision bool
+		missing   bool
+	}{
+		{
+			name:    "paginated",
+			renames: map[string]string{"go-lang": "go"},
+			want: []redirect{
+				{"go-lang/index.html", "https://example.com/go/index.html"},
+				{
+					"go-lang/page-001.html",
+					"https://example.com/go/page-001.html",
+				},
+				{
+					"go-lang/page-002.html",
+					"https://example.com/go/page-002.html",
+				},
+			},
+		},
+		{
+			name:    "pretty",
+			renames: map[string]string{"go-lang": "go"},
+			pretty:  true,
+			want: []redirect{
+				{"go-lang/index.html", "https://example.com/go/"},
+				{
+					"go-lang/page-001/index.html",
+					"https://example.com/go/page-001/",
+				},
+				{
+					"go-lang/page-002/index.html",
+					"https://example.com/go/page-002/",
+				},
+			},
+		},
+		{
+			name:    "single-page",
+			renames: map[string]string{"rustlang": "rust"},
+			want: []redirect{
+				{"rustlang/index.html", "https://example.com/rust/index.html"},
+			},
+		},
+		{
+			name:      "collision",
+			renames:   map[string]string{"golang": "go"},
+			collision: true,
+		},
+		{
+			name:    "missing-target",
+			renames: map[string]string{"zig-lang": "zig"},
+			missing: true,
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			redirects, err := tagRedirects(
+				tc.renames,
+				live,
+				pageCount,
+				base,
+				tc.pretty,
+			)
+
+			var collision *TagRedirectCollisionError
+			if errors.As(err, &collision) != tc.collision {
+				t.Errorf("collision: wanted %t; found `%v`", tc.collision, err)
+			}
+			var missing *TagRedirectTargetError
+			if errors.As(err, &missing) != tc.missing {
+				t.Errorf("missing: wanted %t; found `%v`", tc.missing, err)
+			}
+			if !tc.collision && !tc.missing && err != nil {
+				t.Fatalf("unexpected error: %v", err)
+			}
+
+			got := make([]redirect, len(redirects))
+			for i, r := range redirects {
+				got[i] = redirect{r.Path, r.Target.String()}
+			}
+			if !slices.Equal(got, tc.want) {
+				t.Errorf("wanted redirects %v; found %v", tc.want, got)
+			}
+		})
+	}
+}
@@ -0,0 +1,76 @@
+package futhorc
+
+import (
+	"fmt"
+	"html/template"
+)
+
+// SinglePostTags determines how tags with exactly one post are indexed.
+type SinglePostTags string
+
+const (
+	// SinglePostTagsList gives single-post tags an index like any other
+	// tag's.
+	SinglePostTagsList SinglePostTags = "list"
+
+	// SinglePostTagsInline gives single-post tags an index whose page is
+	// flagged `Inline`, so the index template can render the whole post.
+	SinglePostTagsInline SinglePostTags = "inline"
+
+	// SinglePostTagsLink skips the indices of single-post tags and links
+	// the tags straight to their posts.
+	SinglePostTagsLink SinglePostTags = "link"
+)
+
+func ParseSinglePostTags(s string) (SinglePostTags, error) {
+	switch mode := SinglePostTags(s); mode {
+	case "":
+		return SinglePostTagsList, nil
+	case SinglePostTagsList, SinglePostTagsInline, SinglePostTagsLink:
+		return mode, nil
+	default:
+		return "", fmt.Errorf(
+			"invalid single post tags mode `%s`: expected `%s`, `%s`, or "+
+				"`%s`",
+			s,
+			SinglePostTagsList,
+			SinglePostTagsInline,
+			SinglePostTagsLink,
+		)
+	}
+}
+
+// inlines reports whether the index `idx`'s page is inline.
+func (mode SinglePostTags) inlines(idx *Index) bool {
+	return mode == SinglePostTagsInline && isSinglePostTag(idx)
+}
+
+// links reports whether the index `idx` is skipped in favor of linking to its
+// post.
+func (mode SinglePostTags) links(idx *Index) bool {
+	return mode == SinglePostTagsLink && isSinglePostTag(idx)
+}
+
+func isSinglePostTag(idx *Index) bool {
+	return idx.ID != "" && len(idx.Posts) == 1
+}
+
+// linkSinglePostTags points the URLs of the tags with exactly one post at
+// their posts, for `SinglePostTagsLink`. It must run once every post's tags
+// are known and before any page is rendered.
+func linkSinglePostTags(posts []OrderedPage[Post]) {
+	counts := map[string]int{}
+	for i := range posts {
+		for _, tag := range posts[i].Content.Tags {
+			counts[tag.Slug]++
+		}
+	}
+	for i := range posts {
+		tags := posts[i].Content.Tags
+		for j := range tags {
+			if counts[tags[j].Slug] == 1 {
+				tags[j].URL = template.URL(posts[i].URL.String())
+			}
+		}
+	}
+}
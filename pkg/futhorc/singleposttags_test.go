@@ -0,0 +1,101 @@
+package futhorc
+
+import (
+	"fmt"
+	"maps"
+	"strings"
+	"testing"
+
+	"github.com/go-git/go-billy/v5/util"
+)
+
+func TestSinglePostTags(t *testing.T) {
+	// `solo` has one post and `pair` has two
+	files := map[string]string{
+		"theme/post.html": "{{ range .Content.Tags }}" +
+			"{{ .Text }}={{ .URL }} {{ end }}",
+		"theme/index.html": "inline={{ .Content.Inline }} " +
+			"{{ range .Content.Posts }}{{ if $.Content.Inline }}" +
+			"{{ .Content.Body }}{{ else }}{{ .URL }}{{ end }}{{ end }}",
+		"posts/a.md": "---\nTitle: A\nDate: 2024-01-01\n" +
+			"Tags: [solo, pair]\n---\n\nAlpha.\n",
+		"posts/b.md": "---\nTitle: B\nDate: 2024-01-02\nTags: [pair]\n---\n",
+	}
+	const root = goldenSiteRoot
+	for _, tc := range []struct {
+		mode SinglePostTags
+
+		// wanted maps each index page to its rendering, or to the empty
+		// string if it mustn't exist
+		wanted map[string]string
+
+		// soloURL is the URL of the `solo` tag on the post page
+		soloURL string
+	}{
+		{
+			mode: SinglePostTagsList,
+			wanted: map[string]string{
+				"solo/index.html": "inline=false " + postURLs("a"),
+				"pair/index.html": "inline=false " + postURLs("b", "a"),
+			},
+			soloURL: root + "solo/index.html",
+		},
+		{
+			mode: SinglePostTagsInline,
+			wanted: map[string]string{
+				"solo/index.html": "inline=true <p>Alpha.</p>\n",
+				"pair/index.html": "inline=false " + postURLs("b", "a"),
+			},
+			soloURL: root + "solo/index.html",
+		},
+		{
+			mode: SinglePostTagsLink,
+			wanted: map[string]string{
+				"solo/index.html": "",
+				"pair/index.html": "inline=false " + postURLs("b", "a"),
+			},
+			soloURL: root + "posts/a.html",
+		},
+	} {
+		t.Run(string(tc.mode), func(t *testing.T) {
+			files := maps.Clone(files)
+			files["futhorc.jsonc"] = fmt.Sprintf(
+				`{"singlePostTags": "%s"}`,
+				tc.mode,
+			)
+			output, err := buildProject(writeProject(t, testTheme, files))
+			if err != nil {
+				t.Fatal(err)
+			}
+			for p, wanted := range tc.wanted {
+				data, err := util.ReadFile(output, p)
+				if wanted == "" {
+					if err == nil {
+						t.Errorf("wanted no index page `%s`", p)
+					}
+					continue
+				}
+				if err != nil {
+					t.Fatal(err)
+				}
+				if string(data) != wanted {
+					t.Errorf("`%s`: wanted %q; found %q", p, wanted, data)
+				}
+			}
+
+			// the post's tag links agree with the mode
+			data, err := util.ReadFile(output, "posts/a.html")
+			if err != nil {
+				t.Fatal(err)
+			}
+			for _, link := range []string{
+				"solo=" + tc.soloURL + " ",
+				"pair=" + root + "pair/index.html ",
+			} {
+				if !strings.Contains(string(data), link) {
+					t.Errorf("wanted %q in %q", link, data)
+				}
+			}
+		})
+	}
+}
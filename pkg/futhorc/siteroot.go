@@ -0,0 +1,58 @@
+package futhorc
+
+import (
+	"fmt"
+	"log/slog"
+	"net/url"
+	"strings"
+)
+
+// InvalidSiteRootError is returned when the site root can't be used as the
+// base of the site's URLs.
+type InvalidSiteRootError struct {
+	// Value is the offending site root.
+	Value string
+
+	// Reason describes the problem.
+	Reason string
+}
+
+func (err *InvalidSiteRootError) Error() string {
+	return fmt.Sprintf("invalid site root `%s`: %s", err.Value, err.Reason)
+}
+
+// parseSiteRoot parses and validates the site root. A missing trailing slash
+// is added, since without it `url.URL.ResolveReference()` silently drops the
+// last segment of the root's path.
+func parseSiteRoot(siteRoot string) (*url.URL, error) {
+	u, err := url.Parse(siteRoot)
+	if err != nil {
+		return nil, &InvalidSiteRootError{siteRoot, err.Error()}
+	}
+	switch u.Scheme {
+	case "http", "https":
+		if u.Host == "" {
+			return nil, &InvalidSiteRootError{siteRoot, "missing host"}
+		}
+	case "file":
+	default:
+		return nil, &InvalidSiteRootError{
+			siteRoot,
+			"expected an `http`, `https`, or `file` url",
+		}
+	}
+	if u.Fragment != "" || strings.HasSuffix(siteRoot, "#") {
+		return nil, &InvalidSiteRootError{siteRoot, "unexpected fragment"}
+	}
+	if u.RawQuery != "" || u.ForceQuery {
+		return nil, &InvalidSiteRootError{siteRoot, "unexpected query"}
+	}
+	if !strings.HasSuffix(u.Path, "/") {
+		u.Path += "/"
+		if u.RawPath != "" {
+			u.RawPath += "/"
+		}
+		slog.Info("adding trailing slash to site root", "siteRoot", u.String())
+	}
+	return u, nil
+}
@@ -0,0 +1,126 @@
+package futhorc
+
+import (
+	"errors"
+	"net/url"
+	"path/filepath"
+	"testing"
+)
+
+func TestParseSiteRoot(t *testing.T) {
+	for _, tc := range []struct {
+		siteRoot string
+
+		// wanted is the resolved URL of the home page and of a post
+		home, post string
+	}{
+		{
+			siteRoot: "https://example.com",
+			home:     "https://example.com/index.html",
+			post:     "https://example.com/posts/a.html",
+		},
+		{
+			siteRoot: "https://example.com/",
+			home:     "https://example.com/index.html",
+			post:     "https://example.com/posts/a.html",
+		},
+		{
+			siteRoot: "https://example.com/blog",
+			home:     "https://example.com/blog/index.html",
+			post:     "https://example.com/blog/posts/a.html",
+		},
+		{
+			siteRoot: "https://example.com/blog/",
+			home:     "https://example.com/blog/index.html",
+			post:     "https://example.com/blog/posts/a.html",
+		},
+		{
+			siteRoot: "http://localhost:8080/my%20blog",
+			home:     "http://localhost:8080/my%20blog/index.html",
+			post:     "http://localhost:8080/my%20blog/posts/a.html",
+		},
+		{
+			siteRoot: "file:///tmp/site",
+			home:     "file:///tmp/site/index.html",
+			post:     "file:///tmp/site/posts/a.html",
+		},
+	} {
+		t.Run(tc.siteRoot, func(t *testing.T) {
+			base, err := parseSiteRoot(tc.siteRoot)
+			if err != nil {
+				t.Fatal(err)
+			}
+			post := base.ResolveReference(&url.URL{Path: "posts/a.html"})
+			for _, found := range []struct{ name, wanted, found string }{
+				{"home", tc.home, indexURL(base, "", false).String()},
+				{"post", tc.post, post.String()},
+			} {
+				if found.found != found.wanted {
+					t.Errorf(
+						"%s: wanted `%s`; found `%s`",
+						found.name,
+						found.wanted,
+						found.found,
+					)
+				}
+			}
+		})
+	}
+}
+
+func TestInvalidSiteRoot(t *testing.T) {
+	for _, tc := range []struct {
+		siteRoot string
+		reason   string
+	}{
+		{"ftp://example.com/", "expected an `http`, `https`, or `file` url"},
+		{"example.com/blog/", "expected an `http`, `https`, or `file` url"},
+		{"https:///blog/", "missing host"},
+		{"https://example.com/#top", "unexpected fragment"},
+		{"https://example.com/#", "unexpected fragment"},
+		{"https://example.com/?page=1", "unexpected query"},
+		{"https://example.com/?", "unexpected query"},
+	} {
+		t.Run(tc.siteRoot, func(t *testing.T) {
+			_, err := LoadPipeline(writeProject(t, testTheme, nil), tc.siteRoot)
+			var rootErr *InvalidSiteRootError
+			if !errors.As(err, &rootErr) {
+				t.Fatalf("wanted an `*InvalidSiteRootError`; found `%v`", err)
+			}
+			if rootErr.Value != tc.siteRoot || rootErr.Reason != tc.reason {
+				t.Errorf(
+					"wanted `%s` (%s); found `%s` (%s)",
+					tc.siteRoot,
+					tc.reason,
+					rootErr.Value,
+					rootErr.Reason,
+				)
+			}
+		})
+	}
+}
+
+func TestDefaultTitle(t *testing.T) {
+	dir := writeProject(t, testTheme, nil)
+	for _, tc := range []struct {
+		siteRoot string
+		wanted   string
+	}{
+		{"https://example.com/blog/", "example.com"},
+		{"", filepath.Base(dir)},
+		{"file:///tmp/site", filepath.Base(dir)},
+	} {
+		pipeline, err := LoadPipeline(dir, tc.siteRoot)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if pipeline.Title != tc.wanted {
+			t.Errorf(
+				"`%s`: wanted the title `%s`; found `%s`",
+				tc.siteRoot,
+				tc.wanted,
+				pipeline.Title,
+			)
+		}
+	}
+}
@@ -0,0 +1,89 @@
+package futhorc
+
+import (
+	"errors"
+	"fmt"
+	"io/fs"
+	"path"
+	"path/filepath"
+	"strings"
+)
+
+// outputPath returns the output path of the post `p`, which is derived from
+// its source path unless its frontmatter sets a `Slug` to replace the file
+// name (e.g., `2021-04-16-hello-world.md` with the slug `hello-world` becomes
+// `hello-world.html`).
+func (converter *PostPageConverter) outputPath(p *Post) string {
+	source := filepath.ToSlash(p.Path)
+	if p.Slug != "" {
+		source = path.Join(path.Dir(source), p.Slug+markdownSuffix)
+	}
+	return convertPath(source, converter.PrettyURLs)
+}
+
+// SlugAliases scans the posts in `sources` for `Slug` overrides and maps the
+// URL of each slugged post's source file to the URL of its page, so links
+// written against the source file name (see `markdown.Config.Aliases`) still
+// resolve. Posts are read with the same options as the pipeline's
+// `PostParser`, and posts which fail to parse are skipped, since the pipeline
+// reports them when it parses them for real.
+func (converter *PostPageConverter) SlugAliases(
+	sources fs.FS,
+	lossyEncoding bool,
+	limits *PostLimits,
+) (map[string]string, error) {
+	aliases := map[string]string{}
+	err := fs.WalkDir(
+		sources,
+		".",
+		func(p string, entry fs.DirEntry, err error) error {
+			if err != nil {
+				return err
+			}
+			if entry.IsDir() || !strings.HasSuffix(p, markdownSuffix) {
+				return nil
+			}
+			data, err := fs.ReadFile(sources, p)
+			if err != nil {
+				return err
+			}
+			post, err := readPost(
+				FileData{Path: p, Data: data},
+				lossyEncoding,
+				limits,
+			)
+			if err != nil || post.Slug == "" {
+				return nil
+			}
+			page, err := converter.PageConverter.Convert(
+				converter.outputPath(&post),
+				0,
+				post,
+			)
+			if err != nil {
+				return err
+			}
+			source := converter.BaseURL.JoinPath(converter.Directory, p)
+			aliases[source.String()] = page.URL.String()
+			return nil
+		},
+	)
+	if errors.Is(err, fs.ErrNotExist) && len(aliases) < 1 {
+		err = nil
+	}
+	if err != nil {
+		return nil, fmt.Errorf("scanning post slugs: %w", err)
+	}
+	return aliases, nil
+}
+
+// validateSlug returns an error unless `slug` is empty or a valid file name.
+func validateSlug(slug string) error {
+	if strings.ContainsAny(slug, `/\`) || slug == "." || slug == ".." {
+		return fmt.Errorf(
+			"invalid slug `%s`: slugs must be file names, not paths",
+			slug,
+		)
+	}
+	return nil
+}
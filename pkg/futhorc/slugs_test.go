@@ -0,0 +1,56 @@
+package futhorc
+
+import (
+	"errors"
+	"strings"
+	"testing"
+
+	"github.com/go-git/go-billy/v5/util"
+)
+
+func TestSlugs(t *testing.T) {
+	output, err := buildProject(writeProject(t, testTheme, map[string]string{
+		"posts/2021-04-16-hello-world.md": "---\nTitle: Hello\n" +
+			"Date: 2021-04-16\nSlug: hello-world\n---\n",
+		"posts/other.md": "---\nTitle: Other\nDate: 2021-04-17\n---\n\n" +
+			"[x](2021-04-16-hello-world.md#top)\n",
+	}))
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	// the slug replaces the file name, and links to the file name follow it
+	if _, err := output.Stat("posts/hello-world.html"); err != nil {
+		t.Errorf("wanted the slugged post: %v", err)
+	}
+	if _, err := output.Stat("posts/2021-04-16-hello-world.html"); err == nil {
+		t.Error("wanted no post at the source file name")
+	}
+	data, err := util.ReadFile(output, "posts/other.html")
+	if err != nil {
+		t.Fatal(err)
+	}
+	link := `href="` + goldenSiteRoot + `posts/hello-world.html#top"`
+	if !strings.Contains(string(data), link) {
+		t.Errorf("wanted %q in %q", link, data)
+	}
+}
+
+func TestSlugCollision(t *testing.T) {
+	_, err := buildProject(writeProject(t, testTheme, map[string]string{
+		"posts/a.md":    "---\nTitle: A\nDate: 2024-01-01\nSlug: same\n---\n",
+		"posts/same.md": "---\nTitle: Same\nDate: 2024-01-02\n---\n",
+	}))
+	var conflict *OutputConflictError
+	if !errors.As(err, &conflict) {
+		t.Fatalf("wanted an `*OutputConflictError`; found `%v`", err)
+	}
+	if conflict.Path != "posts/same.html" {
+		t.Errorf("wanted the path `posts/same.html`; found `%s`", conflict.Path)
+	}
+	for _, source := range []string{"`a.md`", "`same.md`"} {
+		if !strings.Contains(err.Error(), source) {
+			t.Errorf("wanted the error to name %s: %v", source, err)
+		}
+	}
+}
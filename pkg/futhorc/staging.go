@@ -0,0 +1,358 @@
+package futhorc
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"io"
+	"io/fs"
+	"log/slog"
+	"os"
+	"path"
+	"path/filepath"
+	"strings"
+	"sync"
+	"time"
+
+	"github.com/go-git/go-billy/v5"
+	"github.com/go-git/go-billy/v5/osfs"
+)
+
+// RunStaged runs the pipeline into a staging directory beside `OutputPath`
+// and only swaps the result in for the output directory if the build
+// succeeds, so a failed build never leaves the previous output
+// half-overwritten. The staging directory is always removed. Without an
+// `OutputPath`, it's the same as `Run`.
+//
+// Files in the output directory which the build no longer produces (e.g., the
+// pages of a deleted post) are removed.
+//
+// The staging directory starts as a copy of the output directory made of
+// hard links, so the build sees the previous output as if it were building in
+// place: unchanged pages aren't rewritten and assets are skipped according to
+// `CopyCheck`, keeping their modification times. Writes replace files rather
+// than writing through the links, so the output directory is untouched until
+// the swap.
+//
+// The output directory is locked (see `AcquireLock`) for the duration of the
+// build so that concurrent builds don't interleave their writes. Unless
+// `Force` is set, it must be empty or carry the `.futhorc` marker which
+// every successful build writes.
+func (pipeline *Pipeline) RunStaged(ctx context.Context) (err error) {
+	if pipeline.OutputPath == "" {
+		return pipeline.Run(ctx)
+	}
+
+	lock, err := AcquireLock(ctx, pipeline.OutputPath, pipeline.WaitForLock)
+	if err != nil {
+		return err
+	}
+	defer func() { err = errors.Join(err, lock.Release()) }()
+	if !pipeline.Force {
+		if err := checkOutputMarker(pipeline.OutputPath); err != nil {
+			return err
+		}
+	}
+
+	// stage beside the output directory, which is usually on the same
+	// filesystem (so it can be linked from and renamed into place)
+	parent := filepath.Dir(filepath.Clean(pipeline.OutputPath))
+	if err := os.MkdirAll(parent, 0755); err != nil {
+		return fmt.Errorf("staging build: %w", err)
+	}
+	staging, err := os.MkdirTemp(parent, ".futhorc-staging-")
+	if err != nil {
+		return fmt.Errorf("staging build: %w", err)
+	}
+	defer func() { err = errors.Join(err, os.RemoveAll(staging)) }()
+	if err := cloneOutput(staging, pipeline.OutputPath); err != nil {
+		return fmt.Errorf("staging build: %w", err)
+	}
+
+	output := pipeline.OutputDirectory
+	tracked := newTrackedOutput(osfs.New(staging))
+	pipeline.OutputDirectory = tracked
+	defer func() { pipeline.OutputDirectory = output }()
+	if err := pipeline.Run(ctx); err != nil {
+		return err
+	}
+	if pipeline.Report.Removed, err = removeStale(
+		staging,
+		tracked,
+	); err != nil {
+		return fmt.Errorf("removing stale outputs: %w", err)
+	}
+	if pipeline.Report.Removed > 0 {
+		slog.Info("removed stale outputs", "count", pipeline.Report.Removed)
+	}
+	if err := writeOutputMarker(
+		pipeline.OutputDirectory,
+		pipeline.ProjectDir,
+	); err != nil {
+		return err
+	}
+
+	if err := swapOutput(
+		filepath.Clean(pipeline.OutputPath),
+		staging,
+	); err != nil {
+		return fmt.Errorf("swapping in staged build: %w", err)
+	}
+	return nil
+}
+
+// cloneOutput fills the staging directory `staging` with hard links to the
+// files in the output directory `output`. Files which can't be linked (e.g.,
+// on filesystems without hard links) are copied with their modification
+// times. A missing output directory has no files.
+func cloneOutput(staging, output string) error {
+	if _, err := os.Stat(output); errors.Is(err, fs.ErrNotExist) {
+		return nil
+	}
+	return filepath.WalkDir(
+		output,
+		func(p string, entry fs.DirEntry, err error) error {
+			if err != nil {
+				return err
+			}
+			rel, err := filepath.Rel(output, p)
+			if err != nil || rel == "." {
+				return err
+			}
+			target := filepath.Join(staging, rel)
+			if entry.IsDir() {
+				return os.MkdirAll(target, 0755)
+			}
+			if os.Link(p, target) == nil {
+				return nil
+			}
+			return copyOSFile(target, p)
+		},
+	)
+}
+
+// swapOutput replaces the output directory `output` with the staging
+// directory `staging`. The swap is a pair of renames, so readers of the
+// output see either build but never a mix of the two. If the output
+// directory can't be renamed (e.g., it's a mount point), the staged files are
+// moved into it one by one instead. If there's no output directory yet, the
+// staging directory is just renamed into place.
+func swapOutput(output, staging string) error {
+	old := staging + ".old"
+	if err := os.Rename(output, old); err != nil {
+		if errors.Is(err, fs.ErrNotExist) {
+			return os.Rename(staging, output)
+		}
+		return syncOutput(output, staging)
+	}
+	if err := os.Rename(staging, output); err != nil {
+		// put the previous output back
+		return errors.Join(err, os.Rename(old, output))
+	}
+	return os.RemoveAll(old)
+}
+
+// syncOutput moves the files in `staging` into `output`. Files which are
+// still linked to the output's (i.e., which the build didn't change) are
+// left alone, and files are copied if they can't be moved. Files which aren't
+// in `staging` are removed from `output`.
+func syncOutput(output, staging string) error {
+	err := filepath.WalkDir(
+		staging,
+		func(p string, entry fs.DirEntry, err error) error {
+			if err != nil || entry.IsDir() {
+				return err
+			}
+			rel, err := filepath.Rel(staging, p)
+			if err != nil {
+				return err
+			}
+			target := filepath.Join(output, rel)
+			if unchangedFile(p, target) {
+				return nil
+			}
+			if err := os.MkdirAll(filepath.Dir(target), 0755); err != nil {
+				return err
+			}
+			if os.Rename(p, target) == nil {
+				return nil
+			}
+			return copyOSFile(target, p)
+		},
+	)
+	if err != nil {
+		return err
+	}
+	_, err = removeFiles(output, func(rel string) bool {
+		_, err := os.Lstat(filepath.Join(staging, filepath.FromSlash(rel)))
+		return errors.Is(err, fs.ErrNotExist)
+	})
+	return err
+}
+
+// removeStale removes the files in the staging directory `staging` which
+// `output` didn't record as produced (besides the marker), i.e., the previous
+// build's outputs which this build no longer produces. It returns the number
+// of files removed.
+func removeStale(staging string, output *trackedOutput) (int, error) {
+	return removeFiles(staging, func(rel string) bool {
+		return rel != markerFile && !output.produced(rel)
+	})
+}
+
+// removeFiles removes the files in `dir` whose slash-separated relative paths
+// satisfy `stale`, along with any directories left empty. It returns the
+// number of files removed.
+func removeFiles(dir string, stale func(rel string) bool) (int, error) {
+	var removed int
+	var dirs []string
+	err := filepath.WalkDir(
+		dir,
+		func(p string, entry fs.DirEntry, err error) error {
+			if err != nil {
+				return err
+			}
+			if entry.IsDir() {
+				dirs = append(dirs, p)
+				return nil
+			}
+			rel, err := filepath.Rel(dir, p)
+			if err != nil || !stale(filepath.ToSlash(rel)) {
+				return err
+			}
+			removed++
+			return os.Remove(p)
+		},
+	)
+	if err != nil {
+		return removed, err
+	}
+
+	// subdirectories are walked after their parents, so they're visited
+	// first in reverse; `dirs[0]` is `dir` itself, which is kept
+	for i := len(dirs) - 1; i > 0; i-- {
+		if entries, err := os.ReadDir(dirs[i]); err == nil && len(entries) < 1 {
+			if err := os.Remove(dirs[i]); err != nil {
+				return removed, err
+			}
+		}
+	}
+	return removed, nil
+}
+
+// trackedOutput is a staged build's output filesystem. It records every file
+// the build produces, whether it's written or found up to date, so that the
+// files it no longer produces can be removed.
+type trackedOutput struct {
+	billy.Filesystem
+
+	mu      sync.Mutex
+	outputs map[string]struct{}
+}
+
+func newTrackedOutput(fs billy.Filesystem) *trackedOutput {
+	return &trackedOutput{Filesystem: fs, outputs: map[string]struct{}{}}
+}
+
+// recordOutput records that the build produced the file at `p` if `output` is
+// a `trackedOutput`.
+func recordOutput(output billy.Filesystem, p string) {
+	if tracked, ok := output.(*trackedOutput); ok {
+		tracked.mu.Lock()
+		defer tracked.mu.Unlock()
+		tracked.outputs[outputKey(p)] = struct{}{}
+	}
+}
+
+func (output *trackedOutput) produced(p string) bool {
+	output.mu.Lock()
+	defer output.mu.Unlock()
+	_, found := output.outputs[outputKey(p)]
+	return found
+}
+
+// outputKey normalizes an output path, which may be relative or rooted
+// (e.g., `/posts/foo.png` from a `FileCopier`).
+func outputKey(p string) string {
+	return strings.TrimPrefix(path.Clean("/"+filepath.ToSlash(p)), "/")
+}
+
+// The `billy.Change` methods are forwarded to the staging filesystem, since
+// copiers carry modification times over with them.
+
+func (output *trackedOutput) Chmod(name string, mode os.FileMode) error {
+	return output.change().Chmod(name, mode)
+}
+
+func (output *trackedOutput) Lchown(name string, uid, gid int) error {
+	return output.change().Lchown(name, uid, gid)
+}
+
+func (output *trackedOutput) Chown(name string, uid, gid int) error {
+	return output.change().Chown(name, uid, gid)
+}
+
+func (output *trackedOutput) Chtimes(
+	name string,
+	atime time.Time,
+	mtime time.Time,
+) error {
+	return output.change().Chtimes(name, atime, mtime)
+}
+
+func (output *trackedOutput) change() billy.Change {
+	if change, ok := output.Filesystem.(billy.Change); ok {
+		return change
+	}
+	return noChange{}
+}
+
+// noChange ignores changes for filesystems which don't support them.
+type noChange struct{}
+
+func (noChange) Chmod(string, os.FileMode) error { return nil }
+
+func (noChange) Lchown(string, int, int) error { return nil }
+
+func (noChange) Chown(string, int, int) error { return nil }
+
+func (noChange) Chtimes(string, time.Time, time.Time) error { return nil }
+
+// unchangedFile reports whether `a` and `b` are the same file (i.e., one is a
+// hard link to the other).
+func unchangedFile(a, b string) bool {
+	aInfo, err := os.Stat(a)
+	if err != nil {
+		return false
+	}
+	bInfo, err := os.Stat(b)
+	return err == nil && os.SameFile(aInfo, bInfo)
+}
+
+// copyOSFile copies the file at `src` to `dst`, replacing `dst` and carrying
+// over the modification time.
+func copyOSFile(dst, src string) (err error) {
+	info, err := os.Stat(src)
+	if err != nil {
+		return err
+	}
+	if err := os.Remove(dst); err != nil && !errors.Is(err, fs.ErrNotExist) {
+		return err
+	}
+	sf, err := os.Open(src)
+	if err != nil {
+		return err
+	}
+	defer func() { err = errors.Join(err, sf.Close()) }()
+	df, err := os.OpenFile(dst, os.O_WRONLY|os.O_CREATE|os.O_EXCL, 0644)
+	if err != nil {
+		return err
+	}
+	if _, err := io.Copy(df, sf); err != nil {
+		return errors.Join(err, df.Close())
+	}
+	if err := df.Close(); err != nil {
+		return err
+	}
+	return os.Chtimes(dst, info.ModTime(), info.ModTime())
+}
@@ -0,0 +1,308 @@
+package futhorc
+
+import (
+	"context"
+	"errors"
+	"io/fs"
+	"maps"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+	"time"
+)
+
+func TestRunStagedFailure(t *testing.T) {
+	const post = "---\nTitle: A\nDate: 2024-01-01\n---\n\nbody\n"
+	for _, tc := range []struct {
+		name string
+
+		// files replace the project's files after the first build
+		files map[string]string
+
+		// postprocessor is added to the second build, if it isn't nil
+		postprocessor PagePostprocessor
+
+		// is reports whether the second build failed with the wanted error
+		is func(error) bool
+	}{
+		{
+			name: "template",
+			files: map[string]string{
+				"theme/post.html": "{{ index .Path 999 }}",
+			},
+			is: func(err error) bool {
+				var renderErr *RenderPageError
+				return errors.As(err, &renderErr)
+			},
+		},
+		{
+			name:  "post",
+			files: map[string]string{"posts/b.md": "Title: B\n"},
+			is: func(err error) bool {
+				var parseErr *ParsePostError
+				return errors.As(err, &parseErr)
+			},
+		},
+		{
+			name:  "conflict",
+			files: map[string]string{"posts/a.html": "a"},
+			is: func(err error) bool {
+				var conflict *OutputConflictError
+				return errors.As(err, &conflict)
+			},
+		},
+		{
+			name: "postprocessor",
+			postprocessor: PagePostprocessorFunc(
+				func(*PageMeta, []byte) ([]byte, error) {
+					return nil, errPostprocessor
+				},
+			),
+			is: func(err error) bool {
+				return errors.Is(err, errPostprocessor)
+			},
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			dir := writeProject(t, testTheme, map[string]string{
+				"posts/a.md": post,
+			})
+			output := filepath.Join(dir, "_output")
+			if err := buildStaged(t, dir, nil); err != nil {
+				t.Fatal(err)
+			}
+			checkStagingRemoved(t, dir)
+			previous := readTree(t, output)
+
+			writeFiles(t, dir, tc.files)
+			err := buildStaged(t, dir, func(pipeline *Pipeline) {
+				if tc.postprocessor != nil {
+					pipeline.Postprocessors = append(
+						pipeline.Postprocessors,
+						tc.postprocessor,
+					)
+				}
+			})
+			if !tc.is(err) {
+				t.Fatalf("wanted the build to fail; found `%v`", err)
+			}
+
+			// the previous build's output must survive the failure
+			if found := readTree(t, output); !maps.Equal(found, previous) {
+				t.Errorf(
+					"wanted the previous output %v; found %v",
+					previous,
+					found,
+				)
+			}
+			checkStagingRemoved(t, dir)
+		})
+	}
+}
+
+var errPostprocessor = errors.New("postprocessor failed")
+
+func TestSwapOutputFallback(t *testing.T) {
+	// setup stages a build over a previous output in a temporary directory,
+	// as `RunStaged` does: the index is rewritten, a post is removed, and
+	// another is added
+	setup := func(t *testing.T) (output, staging string) {
+		dir := t.TempDir()
+		output = filepath.Join(dir, "_output")
+		writeFiles(t, output, map[string]string{
+			"index.html":             "old index",
+			"posts/a.html":           "a",
+			"posts/old/b.html":       "b",
+			"assets/theme/style.css": "style",
+		})
+		staging = filepath.Join(dir, ".futhorc-staging-test")
+		if err := os.Mkdir(staging, 0755); err != nil {
+			t.Fatal(err)
+		}
+		if err := cloneOutput(staging, output); err != nil {
+			t.Fatal(err)
+		}
+
+		// the staged files are hard links to the output's, so they're
+		// replaced rather than written through
+		index := filepath.Join(staging, "index.html")
+		if err := os.Remove(index); err != nil {
+			t.Fatal(err)
+		}
+		if err := os.WriteFile(index, []byte("new index"), 0644); err != nil {
+			t.Fatal(err)
+		}
+		if err := os.RemoveAll(
+			filepath.Join(staging, "posts", "old"),
+		); err != nil {
+			t.Fatal(err)
+		}
+		writeFiles(t, staging, map[string]string{"posts/new/c.html": "c"})
+		return output, staging
+	}
+
+	output, staging := setup(t)
+	wanted := readTree(t, staging)
+	if err := swapOutput(output, staging); err != nil {
+		t.Fatal(err)
+	}
+	if found := readTree(t, output); !maps.Equal(found, wanted) {
+		t.Errorf("swap: wanted %v; found %v", wanted, found)
+	}
+	for _, p := range []string{staging, staging + ".old"} {
+		if _, err := os.Stat(p); !errors.Is(err, fs.ErrNotExist) {
+			t.Errorf("swap: wanted `%s` to be removed: %v", p, err)
+		}
+	}
+
+	// the fallback for output directories which can't be renamed must
+	// produce the same tree
+	output, staging = setup(t)
+	if err := syncOutput(output, staging); err != nil {
+		t.Fatal(err)
+	}
+	if found := readTree(t, output); !maps.Equal(found, wanted) {
+		t.Errorf("sync: wanted %v; found %v", wanted, found)
+	}
+}
+
+func TestRemoveStaleOutputs(t *testing.T) {
+	dir := writeProject(t, testTheme, map[string]string{
+		"posts/a.md": "---\nTitle: A\nDate: 2024-01-01\nTags: [go]\n---\n",
+		"posts/old/gone.md": "---\nTitle: Gone\nDate: 2024-01-02\n" +
+			"Tags: [zig]\n---\n",
+	})
+	output := filepath.Join(dir, "_output")
+	if err := buildStaged(t, dir, nil); err != nil {
+		t.Fatal(err)
+	}
+	stale := []string{"posts/old/gone.html", "zig/index.html"}
+	for _, p := range stale {
+		_, err := os.Stat(filepath.Join(output, filepath.FromSlash(p)))
+		if err != nil {
+			t.Fatalf("wanted `%s` from the first build: %v", p, err)
+		}
+	}
+
+	// the outputs which the second build leaves alone keep these times
+	past := time.Date(2020, 1, 1, 0, 0, 0, 0, time.UTC)
+	unchanged := []string{
+		"posts/a.html",
+		"go/index.html",
+		"assets/theme/style.css",
+	}
+	for _, p := range unchanged {
+		target := filepath.Join(output, filepath.FromSlash(p))
+		if err := os.Chtimes(target, past, past); err != nil {
+			t.Fatal(err)
+		}
+	}
+
+	// assets are up to date if their modification times match the sources'
+	if err := os.Chtimes(
+		filepath.Join(dir, "theme", "assets", "style.css"),
+		past,
+		past,
+	); err != nil {
+		t.Fatal(err)
+	}
+
+	if err := os.Remove(
+		filepath.Join(dir, "posts", "old", "gone.md"),
+	); err != nil {
+		t.Fatal(err)
+	}
+	if err := buildStaged(t, dir, nil); err != nil {
+		t.Fatal(err)
+	}
+
+	// the deleted post's pages are removed along with the directories
+	// they leave empty
+	for _, p := range append(stale, "posts/old", "zig") {
+		_, err := os.Stat(filepath.Join(output, filepath.FromSlash(p)))
+		if !errors.Is(err, fs.ErrNotExist) {
+			t.Errorf("wanted `%s` to be removed: %v", p, err)
+		}
+	}
+	for _, p := range unchanged {
+		info, err := os.Stat(filepath.Join(output, filepath.FromSlash(p)))
+		if err != nil {
+			t.Fatal(err)
+		}
+		if !info.ModTime().Equal(past) {
+			t.Errorf(
+				"wanted `%s` to be left alone; it was modified at %s",
+				p,
+				info.ModTime(),
+			)
+		}
+	}
+	if _, err := os.Stat(filepath.Join(output, markerFile)); err != nil {
+		t.Errorf("wanted the marker to survive: %v", err)
+	}
+}
+
+// buildStaged loads the project in `dir`, passes the pipeline to `configure`
+// (if it isn't nil), and builds it into its `_output` directory with
+// `RunStaged`.
+func buildStaged(t *testing.T, dir string, configure func(*Pipeline)) error {
+	t.Helper()
+	pipeline, err := LoadPipeline(dir, goldenSiteRoot)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if configure != nil {
+		configure(&pipeline)
+	}
+	return pipeline.RunStaged(context.Background())
+}
+
+// checkStagingRemoved reports each staging directory or lock file left beside
+// the output directory of the project in `dir`.
+func checkStagingRemoved(t *testing.T, dir string) {
+	t.Helper()
+	entries, err := os.ReadDir(dir)
+	if err != nil {
+		t.Fatal(err)
+	}
+	for _, entry := range entries {
+		if strings.HasPrefix(entry.Name(), ".futhorc-staging-") ||
+			strings.Contains(entry.Name(), lockSuffix) {
+			t.Errorf("wanted `%s` to be removed", entry.Name())
+		}
+	}
+}
+
+// readTree maps the slash-separated path of each file under `dir` to its
+// contents, and the path of each directory (with a trailing slash) to "".
+func readTree(t *testing.T, dir string) map[string]string {
+	t.Helper()
+	tree := map[string]string{}
+	if err := filepath.WalkDir(
+		dir,
+		func(p string, entry fs.DirEntry, err error) error {
+			if err != nil {
+				return err
+			}
+			rel, err := filepath.Rel(dir, p)
+			if err != nil || rel == "." {
+				return err
+			}
+			rel = filepath.ToSlash(rel)
+			if entry.IsDir() {
+				tree[rel+"/"] = ""
+				return nil
+			}
+			data, err := os.ReadFile(p)
+			if err != nil {
+				return err
+			}
+			tree[rel] = string(data)
+			return nil
+		},
+	); err != nil {
+		t.Fatal(err)
+	}
+	return tree
+}
@@ -0,0 +1,115 @@
+package futhorc
+
+import (
+	"cmp"
+	"slices"
+	"strconv"
+	"time"
+)
+
+// PostStats holds the statistics of a published post. The counts are the
+// ones its reading time is estimated from.
+type PostStats struct {
+	Path        string    `json:"path"`
+	Title       string    `json:"title"`
+	Date        time.Time `json:"date"`
+	Tags        []string  `json:"tags"`
+	Words       int       `json:"words"`
+	Characters  int       `json:"characters"`
+	ReadingTime int       `json:"readingTime"`
+}
+
+// GroupStats aggregates the statistics of a group of posts (a tag's or a
+// year's).
+type GroupStats struct {
+	Name       string `json:"name"`
+	Posts      int    `json:"posts"`
+	Words      int    `json:"words"`
+	Characters int    `json:"characters"`
+
+	// ReadingTime is the total reading time in minutes, and
+	// AverageReadingTime is the mean per post.
+	ReadingTime        int     `json:"readingTime"`
+	AverageReadingTime float64 `json:"averageReadingTime"`
+}
+
+func (group *GroupStats) add(post *PostStats) {
+	group.Posts++
+	group.Words += post.Words
+	group.Characters += post.Characters
+	group.ReadingTime += post.ReadingTime
+	group.AverageReadingTime = float64(group.ReadingTime) /
+		float64(group.Posts)
+}
+
+// SiteStats holds the statistics of a build's posts: each post's (sorted by
+// date) and their aggregates per tag (sorted by name), per year (sorted
+// chronologically), and for the whole site.
+type SiteStats struct {
+	Posts []PostStats  `json:"posts"`
+	Tags  []GroupStats `json:"tags"`
+	Years []GroupStats `json:"years"`
+	Total GroupStats   `json:"total"`
+}
+
+func newPostStats(p *Post) PostStats {
+	tags := make([]string, len(p.Tags))
+	for i := range p.Tags {
+		tags[i] = p.Tags[i].Text
+	}
+	return PostStats{
+		Path:        p.Path,
+		Title:       p.Title,
+		Date:        time.Time(p.Date),
+		Tags:        tags,
+		Words:       p.WordCount,
+		Characters:  p.CharacterCount,
+		ReadingTime: p.ReadingTime,
+	}
+}
+
+func buildSiteStats(posts []PostStats) (stats SiteStats) {
+	stats.Posts = slices.Clone(posts)
+	slices.SortFunc(stats.Posts, func(a, b PostStats) int {
+		return cmp.Or(a.Date.Compare(b.Date), cmp.Compare(a.Path, b.Path))
+	})
+
+	tags := map[string]*GroupStats{}
+	years := map[string]*GroupStats{}
+	stats.Total.Name = "total"
+	for i := range stats.Posts {
+		post := &stats.Posts[i]
+		stats.Total.add(post)
+		for _, tag := range post.Tags {
+			// tags with the same slug are the same tag
+			slug := normalizeTag(tag)
+			if tags[slug] == nil {
+				tags[slug] = &GroupStats{}
+			}
+			tags[slug].Name = displayName(tags[slug].Name, tag)
+			tags[slug].add(post)
+		}
+		year := strconv.Itoa(post.Date.Year())
+		if years[year] == nil {
+			years[year] = &GroupStats{Name: year}
+		}
+		years[year].add(post)
+	}
+
+	stats.Tags = sortedGroups(tags)
+	stats.Years = sortedGroups(years)
+	return
+}
+
+// sortedGroups returns the groups sorted by name, which orders the (four
+// digit) years chronologically.
+func sortedGroups(groups map[string]*GroupStats) []GroupStats {
+	sorted := make([]GroupStats, 0, len(groups))
+	for _, group := range groups {
+		sorted = append(sorted, *group)
+	}
+	slices.SortFunc(sorted, func(a, b GroupStats) int {
+		return cmp.Compare(a.Name, b.Name)
+	})
+	return sorted
+}
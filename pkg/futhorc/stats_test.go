@@ -0,0 +1,103 @@
+package futhorc
+
+import (
+	"context"
+	"fmt"
+	"slices"
+	"strings"
+	"testing"
+
+	"github.com/go-git/go-billy/v5/memfs"
+)
+
+func TestSiteStats(t *testing.T) {
+	// post writes a post of `words` words, which takes `ceil(words/200)`
+	// minutes to read at the default speed
+	post := func(title, date, tags string, words int) string {
+		return fmt.Sprintf(
+			"---\nTitle: %s\nDate: %s\nTags: [%s]\n---\n\n%s\n",
+			title,
+			date,
+			tags,
+			strings.TrimSpace(strings.Repeat("word ", words)),
+		)
+	}
+	pipeline, err := LoadPipeline(
+		writeProject(t, testTheme, map[string]string{
+			"posts/a.md": post("A", "2023-05-01", "go", 100),
+			"posts/b.md": post("B", "2023-09-01", "go, rust", 450),
+			"posts/c.md": post("C", "2024-02-01", "rust", 650),
+		}),
+		goldenSiteRoot,
+	)
+	if err != nil {
+		t.Fatal(err)
+	}
+	pipeline.OutputPath = ""
+	pipeline.OutputDirectory = memfs.New()
+	if err := pipeline.Run(context.Background()); err != nil {
+		t.Fatal(err)
+	}
+	stats := pipeline.Report.Stats()
+
+	type group struct {
+		name           string
+		posts, words   int
+		minutes        int
+		averageMinutes float64
+	}
+	groups := func(stats []GroupStats) []group {
+		out := make([]group, len(stats))
+		for i, s := range stats {
+			out[i] = group{
+				s.Name,
+				s.Posts,
+				s.Words,
+				s.ReadingTime,
+				s.AverageReadingTime,
+			}
+		}
+		return out
+	}
+
+	// the posts take 1, 3, and 4 minutes to read
+	for _, tc := range []struct {
+		name   string
+		found  []GroupStats
+		wanted []group
+	}{
+		{
+			name:  "tags",
+			found: stats.Tags,
+			wanted: []group{
+				{"go", 2, 550, 4, 2},
+				{"rust", 2, 1100, 7, 3.5},
+			},
+		},
+		{
+			name:  "years",
+			found: stats.Years,
+			wanted: []group{
+				{"2023", 2, 550, 4, 2},
+				{"2024", 1, 650, 4, 4},
+			},
+		},
+		{
+			name:   "total",
+			found:  []GroupStats{stats.Total},
+			wanted: []group{{"total", 3, 1200, 8, float64(8) / 3}},
+		},
+	} {
+		if found := groups(tc.found); !slices.Equal(found, tc.wanted) {
+			t.Errorf("%s: wanted %v; found %v", tc.name, tc.wanted, found)
+		}
+	}
+
+	var titles []string
+	for _, p := range stats.Posts {
+		titles = append(titles, p.Title)
+	}
+	if wanted := []string{"A", "B", "C"}; !slices.Equal(titles, wanted) {
+		t.Errorf("wanted the posts %v by date; found %v", wanted, titles)
+	}
+}
@@ -0,0 +1,53 @@
+package futhorc
+
+import "fmt"
+
+// SummaryMode determines how much of each post an index page summarizes.
+type SummaryMode string
+
+const (
+	// SummaryModeFull summarizes each post with its snippet.
+	SummaryModeFull SummaryMode = "full"
+
+	// SummaryModeCompact lists just each post's title, date, and tags. The
+	// snippets are omitted from the index data (see `IndexJSON`), and themes
+	// should omit them from the page.
+	SummaryModeCompact SummaryMode = "compact"
+)
+
+func ParseSummaryMode(s string) (SummaryMode, error) {
+	switch mode := SummaryMode(s); mode {
+	case "":
+		return SummaryModeFull, nil
+	case SummaryModeFull, SummaryModeCompact:
+		return mode, nil
+	default:
+		return "", fmt.Errorf(
+			"invalid summary mode `%s`: expected `%s` or `%s`",
+			s,
+			SummaryModeFull,
+			SummaryModeCompact,
+		)
+	}
+}
+
+// SummaryModes selects the summary mode of each index.
+type SummaryModes struct {
+	// Default is the mode for the main index and for any tag without an
+	// override.
+	Default SummaryMode
+
+	// Tags maps tags to their overrides.
+	Tags map[string]SummaryMode
+}
+
+// For returns the summary mode for the index with ID `id`.
+func (modes *SummaryModes) For(id string) SummaryMode {
+	if mode, found := modes.Tags[id]; found && id != "" {
+		return mode
+	}
+	if modes.Default == "" {
+		return SummaryModeFull
+	}
+	return modes.Default
+}
@@ -0,0 +1,96 @@
+package futhorc
+
+import (
+	"maps"
+	"strings"
+	"testing"
+
+	"github.com/go-git/go-billy/v5/util"
+)
+
+func TestSummaryModes(t *testing.T) {
+	theme := maps.Clone(testTheme)
+	theme["theme/index.html"] = "{{ .Content.SummaryMode }}:" +
+		"{{ range .Content.Posts }}" +
+		`{{ if ne $.Content.SummaryMode "compact" }}` +
+		"{{ .Content.Snippet }}{{ end }}{{ end }}"
+	output, err := buildProject(writeProject(t, theme, map[string]string{
+		"futhorc.jsonc": `{
+			"indexJSON": true,
+			"tagSummaryModes": {"go": "compact"}
+		}`,
+		"posts/a.md": "---\nTitle: A\nDate: 2024-01-01\n" +
+			"Tags: [go, rust]\n---\n\nThe snippet.\n\n<!-- more -->\n\n" +
+			"The rest.\n",
+	}))
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	// only the `go` index is compact; the main index keeps the default
+	for _, tc := range []struct {
+		path string
+		mode SummaryMode
+	}{
+		{path: "index.html", mode: SummaryModeFull},
+		{path: "go/index.html", mode: SummaryModeCompact},
+		{path: "rust/index.html", mode: SummaryModeFull},
+	} {
+		data, err := util.ReadFile(output, tc.path)
+		if err != nil {
+			t.Fatal(err)
+		}
+		page := string(data)
+		if !strings.HasPrefix(page, string(tc.mode)+":") {
+			t.Errorf(
+				"`%s`: wanted the mode `%s`; found %q",
+				tc.path,
+				tc.mode,
+				page,
+			)
+		}
+		p := indexDataPath(tc.path)
+		if data, err = util.ReadFile(output, p); err != nil {
+			t.Fatal(err)
+		}
+		compact := tc.mode == SummaryModeCompact
+		for _, found := range []struct {
+			path string
+			data string
+		}{{tc.path, page}, {p, string(data)}} {
+			if strings.Contains(found.data, "The snippet.") == compact {
+				t.Errorf(
+					"`%s`: wanted the snippet %t; found %q",
+					found.path,
+					!compact,
+					found.data,
+				)
+			}
+		}
+	}
+}
+
+func TestParseSummaryMode(t *testing.T) {
+	for _, tc := range []struct {
+		s      string
+		wanted SummaryMode
+	}{
+		{"", SummaryModeFull},
+		{"full", SummaryModeFull},
+		{"compact", SummaryModeCompact},
+	} {
+		mode, err := ParseSummaryMode(tc.s)
+		if err != nil || mode != tc.wanted {
+			t.Errorf(
+				"`%s`: wanted `%s`; found `%s` (%v)",
+				tc.s,
+				tc.wanted,
+				mode,
+				err,
+			)
+		}
+	}
+	if _, err := ParseSummaryMode("brief"); err == nil {
+		t.Error("wanted an error for an invalid mode")
+	}
+}
@@ -0,0 +1,44 @@
+package futhorc
+
+import (
+	"errors"
+	"strings"
+
+	"futhorc/pkg/slug"
+)
+
+// normalizeTag returns the slug which identifies a tag in URLs and output
+// paths, so that tags which differ only in case, punctuation, or whitespace
+// (e.g., `macOS` and `MacOS`, or `Operating Systems` and
+// `operating-systems`) are identical. Plus signs are spelled out, since
+// they're significant in tags like `C++` (`c-plus-plus`), which would
+// otherwise be the same tag as `C`.
+func normalizeTag(tag string) string {
+	return slug.Slugify(strings.ReplaceAll(tag, "+", " plus "))
+}
+
+// dedupeTags removes repeated tags (e.g., `rust` and ` Rust `, which have the
+// same slug), preserving the order of first appearance.
+func dedupeTags(tags []Link) []Link {
+	seen := make(map[string]struct{}, len(tags))
+	out := tags[:0]
+	for _, tag := range tags {
+		if _, found := seen[tag.Slug]; !found {
+			seen[tag.Slug] = struct{}{}
+			out = append(out, tag)
+		}
+	}
+	return out
+}
+
+// displayName returns the display name of a tag whose posts spell it as both
+// `a` and `b`. The choice doesn't depend on the order of the posts, so every
+// build picks the same name.
+func displayName(a, b string) string {
+	if a == "" {
+		return b
+	}
+	return min(a, b)
+}
+
+var ErrEmptyTag = errors.New("tag name is empty")
@@ -0,0 +1,112 @@
+package futhorc
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/go-git/go-billy/v5/util"
+)
+
+func TestNormalizeTag(t *testing.T) {
+	for _, tc := range []struct {
+		tag  string
+		want string
+	}{
+		{"rust", "rust"},
+		{"Operating Systems", "operating-systems"},
+		{"  operating   systems  ", "operating-systems"},
+		{"operating-systems", "operating-systems"},
+		{"C++", "c-plus-plus"},
+		{"C", "c"},
+		{"a+b", "a-plus-b"},
+		{"C#", "c"},
+		{"don't panic", "dont-panic"},
+		{"--rust!!", "rust"},
+		{"!!!", ""},
+		{"Café Society", "café-society"},
+		{"日本語", "日本語"},
+		{"ÜBER", "über"},
+		{"macOS", "macos"},
+		{"MacOS", "macos"},
+	} {
+		if got := normalizeTag(tc.tag); got != tc.want {
+			t.Errorf("`%s`: wanted %q; found %q", tc.tag, tc.want, got)
+		}
+	}
+}
+
+func TestDisplayName(t *testing.T) {
+	// the display name of merged tags doesn't depend on the order in which
+	// their spellings are seen
+	ab, ba := displayName("macOS", "MacOS"), displayName("MacOS", "macOS")
+	if ab != ba {
+		t.Errorf("display name depends on order: found %q and %q", ab, ba)
+	}
+	if got := displayName("", "macOS"); got != "macOS" {
+		t.Errorf("wanted `macOS`; found %q", got)
+	}
+}
+
+func TestDedupeTags(t *testing.T) {
+	tags := dedupeTags([]Link{
+		{Text: "macOS", Slug: normalizeTag("macOS")},
+		{Text: "rust", Slug: normalizeTag("rust")},
+		{Text: "MacOS", Slug: normalizeTag("MacOS")},
+	})
+	if len(tags) != 2 || tags[0].Text != "macOS" || tags[1].Text != "rust" {
+		t.Errorf("wanted `macOS` and `rust`; found %v", tags)
+	}
+}
+
+func TestUncategorizedTag(t *testing.T) {
+	output, err := buildProject(writeProject(t, testTheme, map[string]string{
+		"futhorc.jsonc": `{"uncategorizedTag": "misc", "feeds": ["atom"]}`,
+		"theme/post.html": "{{ range .Content.Tags }}" +
+			"{{ .Text }}={{ .Implicit }};{{ end }}",
+		"posts/a.md": "---\nTitle: A\nDate: 2024-01-01\nTags: [go]\n---\n",
+		"posts/b.md": "---\nTitle: B\nDate: 2024-01-02\n---\n",
+	}))
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	// only the untagged post has the tag, which is only implicit there
+	for p, wanted := range map[string]string{
+		"posts/a.html":    "go=false;",
+		"posts/b.html":    "misc=true;",
+		"misc/index.html": "https://example.com/posts/b.html",
+		"go/index.html":   "https://example.com/posts/a.html",
+	} {
+		data, err := util.ReadFile(output, p)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if string(data) != wanted {
+			t.Errorf("`%s`: wanted %q; found %q", p, wanted, data)
+		}
+	}
+
+	// the feed's categories leave it out
+	var atom struct {
+		Categories []struct {
+			Term string `xml:"term,attr"`
+		} `xml:"category"`
+		Entries []struct {
+			Title      string `xml:"title"`
+			Categories []struct {
+				Term string `xml:"term,attr"`
+			} `xml:"category"`
+		} `xml:"entry"`
+	}
+	readXML(t, output, "index.atom", &atom)
+	if len(atom.Categories) != 1 || atom.Categories[0].Term != "go" {
+		t.Errorf("wanted the feed's categories [go]; found %v", atom.Categories)
+	}
+	for _, entry := range atom.Entries {
+		for _, category := range entry.Categories {
+			if strings.EqualFold(category.Term, "misc") {
+				t.Errorf("`%s`: wanted no `misc` category", entry.Title)
+			}
+		}
+	}
+}
@@ -0,0 +1,61 @@
+package futhorc
+
+import (
+	"cmp"
+	"net/url"
+	"path"
+	"slices"
+)
+
+// TagsPage is the page listing every tag, which is rendered with the theme's
+// optional tags template.
+type TagsPage struct {
+	Tags []TagSummary
+}
+
+// TagSummary describes a tag on the tags page and in each index page's
+// `Tags`.
+type TagSummary struct {
+	// Name is the tag's display name.
+	Name string
+
+	// URL is the URL of the tag's index.
+	URL *url.URL
+
+	// Count is the number of posts with the tag.
+	Count int
+}
+
+// summarizeTags returns the summaries of the tag indices in `indices`, sorted
+// by count (most posts first) and then by name.
+func summarizeTags(indices map[string]*Index) []TagSummary {
+	tags := make([]TagSummary, 0, len(indices))
+	for id, idx := range indices {
+		if id == "" {
+			continue
+		}
+		tags = append(tags, TagSummary{
+			Name:  idx.Name,
+			URL:   idx.URL,
+			Count: len(idx.Posts),
+		})
+	}
+	slices.SortFunc(tags, func(a, b TagSummary) int {
+		return cmp.Or(
+			cmp.Compare(b.Count, a.Count),
+			cmp.Compare(a.Name, b.Name),
+		)
+	})
+	return tags
+}
+
+// tagsPagePath returns the output path of the tags page: `tags.html`, or
+// `tags/index.html` with pretty URLs.
+func tagsPagePath(pretty bool) string {
+	if pretty {
+		return path.Join(tagsPageName, indexFile)
+	}
+	return tagsPageName + htmlSuffix
+}
+
+const tagsPageName = "tags"
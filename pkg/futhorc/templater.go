@@ -1,11 +1,14 @@
 package futhorc
 
 import (
+	"bytes"
 	"context"
-	"errors"
 	"fmt"
 	"futhorc/pkg/actor"
 	"html/template"
+	"io"
+	"log/slog"
+	"net/url"
 
 	"github.com/go-git/go-billy/v5"
 )
@@ -13,11 +16,71 @@ import (
 type Templater[T any] actor.Input[*OrderedPage[T]]
 
 type SiteData struct {
+	// Title and Description describe the site (see `Config.Title`).
+	Title       string
+	Description string
+
 	BaseURL     template.URL
 	HomePage    template.URL
 	FeedURL     template.URL
 	FeedType    string
 	ThemeAssets template.URL
+
+	// Feeds links to the feed in each of the configured formats. `FeedURL`
+	// and `FeedType` describe the first.
+	Feeds []FeedLink
+
+	// MetaTags are the `<meta>` tags (e.g., for host verification) which
+	// themes should emit in the `<head>` of every page.
+	MetaTags []MetaTag
+
+	// Webmention holds the endpoints (if configured) which themes should
+	// advertise with `<link>` tags in the `<head>` of every page.
+	Webmention *Webmention
+}
+
+// FeedLink is the URL and MIME type of a feed.
+type FeedLink struct {
+	URL  template.URL
+	Type string
+}
+
+// MetaTag is a `<meta name="..." content="...">` tag. Templates should
+// interpolate the fields into attributes, where `html/template` escapes them.
+type MetaTag struct {
+	Name    string `json:"name"`
+	Content string `json:"content"`
+}
+
+// Webmention configures the endpoints which receive webmentions and
+// pingbacks for the site. Either may be empty.
+type Webmention struct {
+	Endpoint string `json:"endpoint"`
+	Pingback string `json:"pingback"`
+}
+
+// Validate checks that the endpoints are absolute URLs.
+func (webmention *Webmention) Validate() error {
+	for _, endpoint := range []struct{ name, url string }{
+		{"endpoint", webmention.Endpoint},
+		{"pingback", webmention.Pingback},
+	} {
+		if endpoint.url == "" {
+			continue
+		}
+		u, err := url.Parse(endpoint.url)
+		if err != nil {
+			return fmt.Errorf("webmention %s: %w", endpoint.name, err)
+		}
+		if !u.IsAbs() || u.Host == "" {
+			return fmt.Errorf(
+				"webmention %s: url `%s` is not absolute",
+				endpoint.name,
+				endpoint.url,
+			)
+		}
+	}
+	return nil
 }
 
 type TemplaterParams[T any] struct {
@@ -27,6 +90,25 @@ type TemplaterParams[T any] struct {
 	Output      billy.Filesystem
 	Template    *template.Template
 	SiteData    *SiteData
+	Kind        PageKind
+
+	// Postprocessors transform each rendered page, in order, before it's
+	// written.
+	Postprocessors []PagePostprocessor
+
+	// MaxPageSize is the size in bytes above which a rendered page logs a
+	// warning (0 disables the check).
+	MaxPageSize int
+
+	// DebugTemplate, if set, is a copy of `Template` which fails on missing
+	// map keys. It's executed after each page renders successfully, and its
+	// failures are logged as warnings (or returned if `StrictTemplates` is
+	// set), since `html/template` otherwise renders missing values as empty.
+	DebugTemplate   *template.Template
+	StrictTemplates bool
+
+	// Report, if non-nil, records each page written.
+	Report *BuildReport
 }
 
 func NewTemplater[T any](params *TemplaterParams[T]) (templater Templater[T]) {
@@ -38,11 +120,12 @@ func NewTemplater[T any](params *TemplaterParams[T]) (templater Templater[T]) {
 		params.Concurrency,
 		params.Pages,
 		func(ctx context.Context, page *OrderedPage[T]) error {
-			t := params.Template
-			if err := exec(
-				params.Output,
-				page.Path,
-				t,
+			meta := PageMeta{Kind: params.Kind, Path: page.Path, URL: page.URL}
+			if post, ok := any(&page.Content).(*Post); ok {
+				meta.Source = post.Source
+			}
+			size, written, err := params.exec(
+				&meta,
 				struct {
 					*SiteData
 					*OrderedPage[T]
@@ -50,8 +133,25 @@ func NewTemplater[T any](params *TemplaterParams[T]) (templater Templater[T]) {
 					SiteData:    params.SiteData,
 					OrderedPage: page,
 				},
-			); err != nil {
-				return fmt.Errorf("transforming post `%s`: %w'", page.Path, err)
+			)
+			if err != nil {
+				return &RenderPageError{
+					Path:   page.Path,
+					Kind:   params.Kind,
+					Source: meta.Source,
+					Err:    err,
+				}
+			}
+			params.Report.Record(ReportedFile{
+				Path: page.Path,
+				URL:  page.URL.String(),
+				Kind: reportKind(params.Kind, page),
+				Size: size,
+
+				Unchanged: !written,
+			})
+			if post, ok := any(&page.Content).(*Post); ok && !post.Preview {
+				params.Report.RecordPost(post)
 			}
 			return nil
 		},
@@ -60,18 +160,145 @@ func NewTemplater[T any](params *TemplaterParams[T]) (templater Templater[T]) {
 	return
 }
 
-func exec(
-	fs billy.Filesystem,
-	path string,
-	t *template.Template,
+// exec renders the page described by `meta`, returning its size and whether it
+// was written (i.e., it wasn't already up to date).
+func (params *TemplaterParams[T]) exec(
+	meta *PageMeta,
 	v any,
-) (err error) {
-	var f billy.File
-	f, err = fs.Create(path)
-	if err != nil {
+) (size int, written bool, err error) {
+	path := meta.Path
+	var buf bytes.Buffer
+	if err = params.Template.Execute(&buf, v); err != nil {
+		return
+	}
+	if err = params.debug(path, v); err != nil {
 		return
 	}
-	defer func() { err = errors.Join(err, f.Close()) }()
-	err = t.Execute(f, v)
+	var data []byte
+	if data, err = postprocess(
+		params.Postprocessors,
+		meta,
+		buf.Bytes(),
+	); err != nil {
+		return
+	}
+	size = len(data)
+	if params.MaxPageSize > 0 && len(data) > params.MaxPageSize {
+		slog.Warn(
+			"page exceeds the maximum page size",
+			"path", path,
+			"kind", params.Kind,
+			"size", len(data),
+			"max", params.MaxPageSize,
+		)
+	}
+
+	written, err = writeFile(params.Output, path, data)
 	return
 }
+
+func (params *TemplaterParams[T]) debug(path string, v any) error {
+	if params.DebugTemplate == nil {
+		return nil
+	}
+	// the error identifies the template location and the field path that
+	// accessed the missing value
+	err := params.DebugTemplate.Execute(io.Discard, v)
+	if err == nil || params.StrictTemplates {
+		return err
+	}
+	slog.Warn(
+		"template accessed a missing value",
+		"path", path,
+		"kind", params.Kind,
+		"err", err,
+	)
+	return nil
+}
+
+// cloneDebugTemplates makes copies of the pipeline's templates which fail on
+// missing map keys, for `DebugTemplates`. Templates can't be cloned once
+// they've been executed, so `LoadPipeline` clones them up front and every
+// `Run` reuses the copies; only templates which have been replaced since are
+// cloned again.
+func (pipeline *Pipeline) cloneDebugTemplates() error {
+	if pipeline.debugTemplates == nil {
+		pipeline.debugTemplates = map[*template.Template]*template.Template{}
+	}
+	for _, t := range []*template.Template{
+		pipeline.PostTemplate,
+		pipeline.IndexTemplate,
+		pipeline.TagsTemplate,
+	} {
+		if _, found := pipeline.debugTemplates[t]; found || t == nil {
+			continue
+		}
+		clone, err := t.Clone()
+		if err != nil {
+			return fmt.Errorf("cloning template `%s`: %w", t.Name(), err)
+		}
+		pipeline.debugTemplates[t] = clone.Option("missingkey=error")
+	}
+	return nil
+}
+
+// reportKind returns the report kind of a page rendered by a templater of kind
+// `kind`, distinguishing the main index from the tag indices.
+func reportKind[T any](kind PageKind, page *OrderedPage[T]) ReportKind {
+	switch kind {
+	case PageKindPost:
+		return ReportKindPost
+	case PageKindTags:
+		return ReportKindTagsPage
+	}
+	if index, ok := any(&page.Content).(*IndexPage); ok && !index.IsMain() {
+		return ReportKindTagIndex
+	}
+	return ReportKindMainIndex
+}
+
+// PageKind identifies the kind of page being rendered.
+type PageKind string
+
+const (
+	PageKindPost  PageKind = "post"
+	PageKindIndex PageKind = "index"
+	PageKindTags  PageKind = "tags"
+)
+
+// RenderPageError is returned when rendering a page fails. The destination
+// file is only created once the page has rendered successfully, so a failed
+// page never leaves a truncated file in the output directory.
+type RenderPageError struct {
+	// Path is the output path of the page.
+	Path string
+
+	// Kind is the kind of page, which determines the template used.
+	Kind PageKind
+
+	// Source is the source path of the post being rendered (empty for
+	// pages which aren't posts).
+	Source string
+
+	Err error
+}
+
+func (err *RenderPageError) Error() string {
+	if err.Source != "" {
+		return fmt.Sprintf(
+			"rendering %s page `%s` for post `%s`: %v",
+			err.Kind,
+			err.Path,
+			err.Source,
+			err.Err,
+		)
+	}
+	return fmt.Sprintf(
+		"rendering %s page `%s`: %v",
+		err.Kind,
+		err.Path,
+		err.Err,
+	)
+}
+
+func (err *RenderPageError) Unwrap() error { return err.Err }
@@ -0,0 +1,305 @@
+package futhorc
+
+import (
+	"bytes"
+	"context"
+	"errors"
+	"log/slog"
+	"maps"
+	"strings"
+	"sync"
+	"testing"
+
+	"github.com/go-git/go-billy/v5/memfs"
+	"github.com/go-git/go-billy/v5/util"
+)
+
+func TestRenderPageError(t *testing.T) {
+	// `index` fails at execution time when the index is out of range
+	const fail = "{{ index .Path 999 }}"
+	for _, tc := range []struct {
+		name   string
+		theme  map[string]string
+		wanted RenderPageError
+	}{
+		{
+			name: "post",
+			theme: map[string]string{
+				"theme/post.html": `{{ if eq .Content.Title "B" }}` + fail +
+					"{{ end }}{{ .Content.Body }}",
+			},
+			wanted: RenderPageError{
+				Path:   "posts/b.html",
+				Kind:   PageKindPost,
+				Source: "b.md",
+			},
+		},
+		{
+			name:   "index",
+			theme:  map[string]string{"theme/index.html": fail},
+			wanted: RenderPageError{Path: "index.html", Kind: PageKindIndex},
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			theme := maps.Clone(testTheme)
+			maps.Copy(theme, tc.theme)
+			pipeline, err := LoadPipeline(
+				writeProject(t, theme, map[string]string{
+					"posts/a.md": "---\nTitle: A\nDate: 2024-01-01\n---\n",
+					"posts/b.md": "---\nTitle: B\nDate: 2024-01-02\n---\n",
+				}),
+				goldenSiteRoot,
+			)
+			if err != nil {
+				t.Fatal(err)
+			}
+
+			// the page from a previous build must survive the failure
+			output := memfs.New()
+			const previous = "previous build"
+			if err := util.WriteFile(
+				output,
+				tc.wanted.Path,
+				[]byte(previous),
+				0644,
+			); err != nil {
+				t.Fatal(err)
+			}
+			pipeline.OutputPath = ""
+			pipeline.OutputDirectory = output
+			err = pipeline.Run(context.Background())
+
+			var renderErr *RenderPageError
+			if !errors.As(err, &renderErr) {
+				t.Fatalf("wanted a `*RenderPageError`; found `%v`", err)
+			}
+			if renderErr.Path != tc.wanted.Path ||
+				renderErr.Kind != tc.wanted.Kind ||
+				renderErr.Source != tc.wanted.Source {
+				t.Errorf(
+					"wanted the error for %s page `%s` (source `%s`); "+
+						"found one for %s page `%s` (source `%s`)",
+					tc.wanted.Kind,
+					tc.wanted.Path,
+					tc.wanted.Source,
+					renderErr.Kind,
+					renderErr.Path,
+					renderErr.Source,
+				)
+			}
+			data, err := util.ReadFile(output, tc.wanted.Path)
+			if err != nil {
+				t.Fatal(err)
+			}
+			if string(data) != previous {
+				t.Errorf(
+					"wanted `%s` to be left as %q; found %q",
+					tc.wanted.Path,
+					previous,
+					data,
+				)
+			}
+		})
+	}
+}
+
+func TestMaxPageSize(t *testing.T) {
+	logs := captureLogs(t)
+	pipeline, err := LoadPipeline(
+		writeProject(t, testTheme, map[string]string{
+			"futhorc.jsonc": `{"maxPageSizeKB": 1}`,
+			"posts/big.md": "---\nTitle: Big\nDate: 2024-01-01\n---\n\n" +
+				strings.Repeat("word ", 300),
+			"posts/small.md": "---\nTitle: Small\nDate: 2024-01-02\n---\n",
+		}),
+		goldenSiteRoot,
+	)
+	if err != nil {
+		t.Fatal(err)
+	}
+	output := memfs.New()
+	pipeline.OutputPath = ""
+	pipeline.OutputDirectory = output
+	if err := pipeline.Run(context.Background()); err != nil {
+		t.Fatal(err)
+	}
+
+	var warned []string
+	for _, line := range strings.Split(logs.String(), "\n") {
+		if strings.Contains(line, "page exceeds the maximum page size") {
+			warned = append(warned, line)
+		}
+	}
+	if len(warned) != 1 || !strings.Contains(warned[0], "posts/big.html") {
+		t.Errorf("wanted one warning for `posts/big.html`; found %q", warned)
+	}
+
+	// the report has the size of each page as written
+	for _, file := range pipeline.Report.Files() {
+		if file.Kind != ReportKindPost {
+			continue
+		}
+		data, err := util.ReadFile(output, file.Path)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if file.Size != len(data) {
+			t.Errorf(
+				"`%s`: wanted the reported size %d; found %d",
+				file.Path,
+				len(data),
+				file.Size,
+			)
+		}
+	}
+}
+
+func TestDebugTemplates(t *testing.T) {
+	// only `a.md` has a `Subtitle`
+	files := map[string]string{
+		"theme/post.html": "{{ .Content.Extra.Subtitle }}",
+		"posts/a.md": "---\nTitle: A\nDate: 2024-01-01\n" +
+			"Subtitle: Hi\n---\n",
+		"posts/b.md": "---\nTitle: B\nDate: 2024-01-02\nMood: calm\n---\n",
+	}
+	for _, tc := range []struct {
+		name   string
+		debug  bool
+		strict bool
+	}{
+		{name: "off"},
+		{name: "debug", debug: true},
+		{name: "strict", debug: true, strict: true},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			logs := captureLogs(t)
+			pipeline, err := LoadPipeline(
+				writeProject(t, testTheme, files),
+				goldenSiteRoot,
+			)
+			if err != nil {
+				t.Fatal(err)
+			}
+			pipeline.OutputPath = ""
+			pipeline.OutputDirectory = memfs.New()
+			pipeline.DebugTemplates = tc.debug
+			pipeline.StrictTemplates = tc.strict
+			err = pipeline.Run(context.Background())
+
+			if tc.strict {
+				var renderErr *RenderPageError
+				if !errors.As(err, &renderErr) ||
+					renderErr.Path != "posts/b.html" {
+					t.Fatalf(
+						"wanted a `*RenderPageError` for `posts/b.html`; "+
+							"found `%v`",
+						err,
+					)
+				}
+				if !strings.Contains(err.Error(), "Subtitle") {
+					t.Errorf("wanted the error to name the field: %v", err)
+				}
+				return
+			}
+			if err != nil {
+				t.Fatal(err)
+			}
+
+			var warned []string
+			for _, line := range strings.Split(logs.String(), "\n") {
+				if strings.Contains(line, "template accessed a missing value") {
+					warned = append(warned, line)
+				}
+			}
+			if !tc.debug {
+				if len(warned) > 0 {
+					t.Errorf("wanted no warnings; found %q", warned)
+				}
+				return
+			}
+			if len(warned) != 1 ||
+				!strings.Contains(warned[0], "path=posts/b.html") ||
+				!strings.Contains(warned[0], "Subtitle") {
+				t.Errorf(
+					"wanted one warning naming `posts/b.html` and the "+
+						"field; found %q",
+					warned,
+				)
+			}
+		})
+	}
+}
+
+func TestMetaTags(t *testing.T) {
+	const tags = `{{ range .MetaTags }}` +
+		`<meta name="{{ .Name }}" content="{{ .Content }}">{{ end }}`
+	files := map[string]string{
+		"theme/index.html": tags,
+		"theme/post.html":  tags,
+		"posts/a.md":       "---\nTitle: A\nDate: 2024-01-01\n---\n",
+	}
+	for _, tc := range []struct {
+		name   string
+		config string
+		wanted string
+	}{
+		{name: "unconfigured", config: `{}`},
+		{
+			// the content is escaped rather than closing the attribute
+			name: "configured",
+			config: `{"metaTags": [
+				{"name": "google-site-verification", "content": "abc"},
+				{"name": "evil", "content": "x\"><script>"}
+			]}`,
+			wanted: `<meta name="google-site-verification" content="abc">` +
+				`<meta name="evil" content="x&#34;&gt;&lt;script&gt;">`,
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			files := maps.Clone(files)
+			files["futhorc.jsonc"] = tc.config
+			output, err := buildProject(writeProject(t, testTheme, files))
+			if err != nil {
+				t.Fatal(err)
+			}
+			for _, p := range []string{"index.html", "posts/a.html"} {
+				data, err := util.ReadFile(output, p)
+				if err != nil {
+					t.Fatal(err)
+				}
+				if string(data) != tc.wanted {
+					t.Errorf("`%s`: wanted %q; found %q", p, tc.wanted, data)
+				}
+			}
+		})
+	}
+}
+
+// captureLogs directs the default logger to the returned buffer until the
+// test ends.
+func captureLogs(t *testing.T) *logBuffer {
+	t.Helper()
+	var logs logBuffer
+	previous := slog.Default()
+	slog.SetDefault(slog.New(slog.NewTextHandler(&logs, nil)))
+	t.Cleanup(func() { slog.SetDefault(previous) })
+	return &logs
+}
+
+// logBuffer is a buffer which is safe for concurrent use.
+type logBuffer struct {
+	mu  sync.Mutex
+	buf bytes.Buffer
+}
+
+func (logs *logBuffer) Write(p []byte) (int, error) {
+	logs.mu.Lock()
+	defer logs.mu.Unlock()
+	return logs.buf.Write(p)
+}
+
+func (logs *logBuffer) String() string {
+	logs.mu.Lock()
+	defer logs.mu.Unlock()
+	return logs.buf.String()
+}
@@ -2,10 +2,13 @@ package futhorc
 
 import (
 	"encoding/json"
+	"errors"
 	"fmt"
 	"html/template"
 	"io/fs"
+	"log/slog"
 	"net/url"
+	"path"
 	"strings"
 
 	"github.com/tailscale/hujson"
@@ -15,6 +18,14 @@ type Theme struct {
 	IndexTemplate *template.Template
 	PostTemplate  *template.Template
 	Assets        fs.FS
+
+	// TagsTemplate renders the tags page. It's optional, and the page is
+	// only written if the theme has one.
+	TagsTemplate *template.Template
+
+	// AssetFilter holds the theme's asset ignore and include patterns, which
+	// are merged with the project's.
+	AssetFilter AssetFilter
 }
 
 func LoadTheme(dir fs.FS) (theme Theme, err error) {
@@ -30,20 +41,48 @@ func LoadTheme(dir fs.FS) (theme Theme, err error) {
 	var spec struct {
 		IndexTemplate []string `json:"indexTemplate"`
 		PostTemplate  []string `json:"postTemplate"`
+		TagsTemplate  []string `json:"tagsTemplate"`
+
+		// Ellipsis is appended to strings shortened by the `truncateWords` and
+		// `truncateChars` template functions (defaults to `…`).
+		Ellipsis *string `json:"ellipsis"`
+
+		StaticIgnore  []string `json:"staticIgnore"`
+		StaticInclude []string `json:"staticInclude"`
 	}
 	if err = json.Unmarshal(data, &spec); err != nil {
 		err = fmt.Errorf("loading theme: %w", err)
 		return
 	}
 
-	theme.IndexTemplate, err = parse(dir, spec.IndexTemplate...)
-	if err != nil {
-		err = fmt.Errorf("loading theme: %w", err)
-		return
+	ellipsis := defaultEllipsis
+	if spec.Ellipsis != nil {
+		ellipsis = *spec.Ellipsis
+	}
+	funcs := templateFuncs(ellipsis)
+	theme.AssetFilter = AssetFilter{
+		Ignore:  spec.StaticIgnore,
+		Include: spec.StaticInclude,
 	}
 
-	theme.PostTemplate, err = parse(dir, spec.PostTemplate...)
-	if err != nil {
+	// parse every template before failing so that all of their errors are
+	// reported at once
+	var indexErr, postErr, tagsErr error
+	theme.IndexTemplate, indexErr = parse(dir, funcs, spec.IndexTemplate...)
+	if indexErr != nil {
+		indexErr = fmt.Errorf("index template: %w", indexErr)
+	}
+	theme.PostTemplate, postErr = parse(dir, funcs, spec.PostTemplate...)
+	if postErr != nil {
+		postErr = fmt.Errorf("post template: %w", postErr)
+	}
+	if len(spec.TagsTemplate) > 0 {
+		theme.TagsTemplate, tagsErr = parse(dir, funcs, spec.TagsTemplate...)
+		if tagsErr != nil {
+			tagsErr = fmt.Errorf("tags template: %w", tagsErr)
+		}
+	}
+	if err = errors.Join(indexErr, postErr, tagsErr); err != nil {
 		err = fmt.Errorf("loading theme: %w", err)
 		return
 	}
@@ -56,16 +95,46 @@ func LoadTheme(dir fs.FS) (theme Theme, err error) {
 	return
 }
 
-func parse(fs fs.FS, templates ...string) (*template.Template, error) {
-	return template.New(templates[0]).
-		Funcs(template.FuncMap{
-			"url": func(url *url.URL) template.URL {
-				return template.URL(url.String())
-			},
-			"html": func(input string) template.HTML {
-				return template.HTML(input)
-			},
-			"startswith": strings.HasPrefix,
-		}).
-		ParseFS(fs, templates...)
+func parse(
+	fs fs.FS,
+	funcs template.FuncMap,
+	templates ...string,
+) (*template.Template, error) {
+	if len(templates) < 1 {
+		return nil, ErrNoTemplateFiles
+	}
+	seen := make(map[string]struct{}, len(templates))
+	for _, t := range templates {
+		cleaned := path.Clean(t)
+		if _, found := seen[cleaned]; found {
+			return nil, fmt.Errorf(
+				"parsing templates: file `%s` is listed more than once",
+				t,
+			)
+		}
+		seen[cleaned] = struct{}{}
+	}
+	slog.Debug("parsing templates", "files", templates)
+	return template.New(templates[0]).Funcs(funcs).ParseFS(fs, templates...)
+}
+
+var ErrNoTemplateFiles = errors.New("parsing templates: no files listed")
+
+// templateFuncs returns the functions available to theme templates.
+// `html/template` escapes every string value for the context it's rendered
+// in, including frontmatter values like titles, so only the rendered markdown
+// (`Body` and `Snippet`) is trusted as HTML. `html` marks a string as trusted
+// HTML and must never be applied to frontmatter values.
+func templateFuncs(ellipsis string) template.FuncMap {
+	return template.FuncMap{
+		"url": func(url *url.URL) template.URL {
+			return template.URL(url.String())
+		},
+		"html": func(input string) template.HTML {
+			return template.HTML(input)
+		},
+		"startswith":    strings.HasPrefix,
+		"truncateWords": truncateWords(ellipsis),
+		"truncateChars": truncateChars(ellipsis),
+	}
 }
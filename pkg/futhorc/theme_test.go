@@ -0,0 +1,112 @@
+package futhorc
+
+import (
+	"errors"
+	"strings"
+	"testing"
+	"testing/fstest"
+)
+
+func TestLoadThemeTemplateLists(t *testing.T) {
+	for _, tc := range []struct {
+		name string
+
+		// index is the JSON list of the index template's files
+		index string
+
+		// wanted is the rendered index template, if the theme loads
+		wanted string
+
+		// err is in the error message, if the theme fails to load
+		err string
+	}{
+		{
+			// the files are parsed separately, so the first file's last
+			// action isn't glued to the second file's first
+			name:   "no-trailing-newline",
+			index:  `["base.html", "body.html"]`,
+			wanted: "<p>body</p>",
+		},
+		{
+			name:  "duplicate",
+			index: `["base.html", "body.html", "./base.html"]`,
+			err:   "file `./base.html` is listed more than once",
+		},
+		{
+			name:  "empty",
+			index: `[]`,
+			err:   ErrNoTemplateFiles.Error(),
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			theme, err := LoadTheme(fstest.MapFS{
+				"theme.jsonc": {Data: []byte(`{
+					"indexTemplate": ` + tc.index + `,
+					"postTemplate": ["body.html"]
+				}`)},
+				"base.html": {Data: []byte(`<p>{{ template "body" }}</p>`)},
+				"body.html": {
+					Data: []byte(`{{ define "body" }}body{{ end }}`),
+				},
+			})
+			if tc.err != "" {
+				if err == nil || !strings.Contains(err.Error(), tc.err) {
+					t.Fatalf("wanted an error with %q; found `%v`", tc.err, err)
+				}
+				return
+			}
+			if err != nil {
+				t.Fatal(err)
+			}
+			var rendered strings.Builder
+			if err := theme.IndexTemplate.Execute(&rendered, nil); err != nil {
+				t.Fatal(err)
+			}
+			if rendered.String() != tc.wanted {
+				t.Errorf("wanted %q; found %q", tc.wanted, rendered.String())
+			}
+		})
+	}
+
+	_, err := LoadTheme(fstest.MapFS{
+		"theme.jsonc": {Data: []byte(`{"postTemplate": ["post.html"]}`)},
+		"post.html":   {Data: []byte("post")},
+	})
+	if !errors.Is(err, ErrNoTemplateFiles) {
+		t.Errorf(
+			"wanted `ErrNoTemplateFiles` for a missing list; found `%v`",
+			err,
+		)
+	}
+}
+
+func TestLoadThemeReportsEveryTemplate(t *testing.T) {
+	_, err := LoadTheme(fstest.MapFS{
+		"theme.jsonc": {Data: []byte(`{
+			"indexTemplate": ["index.html"],
+			"postTemplate": ["post.html"],
+			"tagsTemplate": ["tags.html"]
+		}`)},
+		"index.html": {Data: []byte("{{ if .Content }}unclosed")},
+		"post.html":  {Data: []byte("\n{{ end }}")},
+		"tags.html":  {Data: []byte("ok")},
+	})
+	if err == nil {
+		t.Fatal("wanted an error for the broken templates")
+	}
+
+	// both broken templates are reported (with their files and lines), and
+	// the valid one isn't
+	msg := err.Error()
+	for _, fragment := range []string{
+		"index template: template: index.html:1:",
+		"post template: template: post.html:2:",
+	} {
+		if !strings.Contains(msg, fragment) {
+			t.Errorf("wanted %q in %q", fragment, msg)
+		}
+	}
+	if strings.Contains(msg, "tags template") {
+		t.Errorf("wanted no error for the tags template; found %q", msg)
+	}
+}
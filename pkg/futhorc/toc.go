@@ -0,0 +1,46 @@
+package futhorc
+
+import (
+	"net/url"
+
+	"futhorc/pkg/markdown"
+)
+
+// TOCEntry is an entry in a post's table of contents.
+type TOCEntry struct {
+	// Level is the heading's rendered level (after the heading shift).
+	Level int
+	Title string
+
+	// URL links to the heading on the post's page.
+	URL string
+
+	// Children holds the headings nested beneath this one.
+	Children []TOCEntry
+}
+
+// buildTOC nests the headings of a post into a table of contents. Each heading
+// is a child of the closest preceding heading with a lower level, so skipped
+// levels (e.g., an `h5` directly beneath an `h3`) still nest. The result is
+// never nil so templates can range over it unconditionally.
+func buildTOC(page *url.URL, headings []markdown.Heading) []TOCEntry {
+	root := TOCEntry{Children: []TOCEntry{}}
+
+	// stack holds the path from the root to the most recent entry
+	stack := []*TOCEntry{&root}
+	for _, heading := range headings {
+		for len(stack) > 1 && stack[len(stack)-1].Level >= heading.Level {
+			stack = stack[:len(stack)-1]
+		}
+		parent := stack[len(stack)-1]
+		link := *page
+		link.Fragment = heading.ID
+		parent.Children = append(parent.Children, TOCEntry{
+			Level: heading.Level,
+			Title: heading.Text,
+			URL:   link.String(),
+		})
+		stack = append(stack, &parent.Children[len(parent.Children)-1])
+	}
+	return root.Children
+}
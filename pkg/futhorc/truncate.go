@@ -0,0 +1,88 @@
+package futhorc
+
+import (
+	"strings"
+	"unicode"
+	"unicode/utf8"
+)
+
+// truncateWords returns a template function which truncates a string to at
+// most `n` whitespace-delimited words, appending `ellipsis` only if the string
+// was truncated.
+func truncateWords(ellipsis string) func(int, string) string {
+	return func(n int, s string) string {
+		var words int
+		var inWord bool
+		for i, r := range s {
+			if unicode.IsSpace(r) {
+				inWord = false
+				continue
+			}
+			if !inWord {
+				if words == n {
+					return truncated(s[:i], ellipsis)
+				}
+				words++
+				inWord = true
+			}
+		}
+		return s
+	}
+}
+
+// truncateChars returns a template function which truncates a string to at
+// most `n` characters, appending `ellipsis` only if the string was truncated.
+// A character is a rune plus any combining marks, emoji modifiers, or
+// zero-width-joined runes which follow it, so truncation never splits a
+// codepoint or separates a character from its accents.
+func truncateChars(ellipsis string) func(int, string) string {
+	return func(n int, s string) string {
+		var chars int
+		for i := 0; i < len(s); i += charLen(s[i:]) {
+			if chars == n {
+				return truncated(s[:i], ellipsis)
+			}
+			chars++
+		}
+		return s
+	}
+}
+
+func truncated(s, ellipsis string) string {
+	return strings.TrimRightFunc(s, unicode.IsSpace) + ellipsis
+}
+
+// charLen returns the length in bytes of the first character in `s`.
+func charLen(s string) int {
+	_, i := utf8.DecodeRuneInString(s)
+	for i < len(s) {
+		r, size := utf8.DecodeRuneInString(s[i:])
+		if r == zeroWidthJoiner {
+			// the joiner attaches the rune that follows it to the current
+			// character.
+			i += size
+			if i < len(s) {
+				_, size = utf8.DecodeRuneInString(s[i:])
+				i += size
+			}
+			continue
+		}
+		if !extendsChar(r) {
+			break
+		}
+		i += size
+	}
+	return i
+}
+
+func extendsChar(r rune) bool {
+	return unicode.In(r, unicode.Mn, unicode.Mc, unicode.Me) ||
+		(r >= emojiModifierMin && r <= emojiModifierMax)
+}
+
+const (
+	zeroWidthJoiner  = '\u200D'
+	emojiModifierMin = '\U0001F3FB'
+	emojiModifierMax = '\U0001F3FF'
+	defaultEllipsis  = "…"
+)
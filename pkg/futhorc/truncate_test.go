@@ -0,0 +1,107 @@
+package futhorc
+
+import (
+	"strings"
+	"testing"
+	"unicode/utf8"
+)
+
+// truncateCorpus holds strings which are easy to truncate incorrectly: emoji
+// (with modifiers and joiners), CJK text, and combining marks.
+var truncateCorpus = []string{
+	"plain ascii words",
+	"he\u0301llo wo\u0308rld",
+	"日本語のテキスト です",
+	"\U0001F44D\U0001F3FD thumbs \U0001F44D up",
+	"\U0001F468\u200D\U0001F469\u200D\U0001F467 family",
+	"\u2764\uFE0F love",
+	"a\u0301\u0302\u0303 stacked",
+	"  leading and trailing  ",
+	"",
+}
+
+func TestTruncate(t *testing.T) {
+	const (
+		thumbsUp = "\U0001F44D\U0001F3FD"
+		family   = "\U0001F468\u200D\U0001F469\u200D\U0001F467"
+	)
+	for _, tc := range []struct {
+		name   string
+		words  bool
+		n      int
+		s      string
+		wanted string
+	}{
+		{"chars-combining", false, 2, "he\u0301llo", "he\u0301…"},
+		{"chars-cjk", false, 3, "日本語のテキスト", "日本語…"},
+		{"chars-modifier", false, 1, thumbsUp + thumbsUp, thumbsUp + "…"},
+		{"chars-joiner", false, 1, family + "!", family + "…"},
+		{"chars-trailing-space", false, 2, "ab cd", "ab…"},
+		{"chars-fits", false, 5, "he\u0301llo", "he\u0301llo"},
+		{"words-cjk", true, 2, "日本語 テキスト です", "日本語 テキスト…"},
+		{"words-emoji", true, 1, thumbsUp + " up", thumbsUp + "…"},
+		{"words-fits", true, 3, "a b  c ", "a b  c "},
+		{"words-zero", true, 0, "a b", "…"},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			truncate := truncateChars(defaultEllipsis)
+			if tc.words {
+				truncate = truncateWords(defaultEllipsis)
+			}
+			if found := truncate(tc.n, tc.s); found != tc.wanted {
+				t.Errorf("wanted %q; found %q", tc.wanted, found)
+			}
+		})
+	}
+}
+
+// TestTruncateProperties checks every truncation of each string in the corpus
+// for broken output: invalid UTF-8, text that isn't a prefix of the input,
+// characters split from their combining marks, and a missing or unnecessary
+// ellipsis.
+func TestTruncateProperties(t *testing.T) {
+	for _, f := range []struct {
+		name     string
+		truncate func(int, string) string
+	}{
+		{"truncateWords", truncateWords(defaultEllipsis)},
+		{"truncateChars", truncateChars(defaultEllipsis)},
+	} {
+		for _, s := range truncateCorpus {
+			for n := range utf8.RuneCountInString(s) + 2 {
+				checkTruncation(t, f.name, n, s, f.truncate(n, s))
+			}
+		}
+	}
+}
+
+func checkTruncation(t *testing.T, name string, n int, s, found string) {
+	t.Helper()
+	if !utf8.ValidString(found) {
+		t.Errorf("%s(%d, %q): invalid UTF-8 %q", name, n, s, found)
+		return
+	}
+	if found == s {
+		return
+	}
+	prefix, ok := strings.CutSuffix(found, defaultEllipsis)
+	if !ok {
+		t.Errorf("%s(%d, %q): missing the ellipsis in %q", name, n, s, found)
+		return
+	}
+	rest, ok := strings.CutPrefix(s, prefix)
+	if !ok {
+		t.Errorf("%s(%d, %q): %q isn't a prefix", name, n, s, prefix)
+		return
+	}
+	if rest == "" {
+		t.Errorf("%s(%d, %q): unnecessary ellipsis in %q", name, n, s, found)
+	}
+	if last, _ := utf8.DecodeLastRuneInString(prefix); last == zeroWidthJoiner {
+		t.Errorf("%s(%d, %q): split after a joiner: %q", name, n, s, found)
+	}
+	if first, _ := utf8.DecodeRuneInString(rest); extendsChar(first) ||
+		first == zeroWidthJoiner {
+		t.Errorf("%s(%d, %q): split a character: %q", name, n, s, found)
+	}
+}
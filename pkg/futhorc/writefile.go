@@ -0,0 +1,53 @@
+package futhorc
+
+import (
+	"bytes"
+	"errors"
+	"io/fs"
+
+	"github.com/go-git/go-billy/v5"
+	"github.com/go-git/go-billy/v5/util"
+)
+
+// writeFile writes `data` to the file at `path` unless the file already holds
+// exactly `data`, in which case it's left untouched so that its modification
+// time doesn't change (and tools like `rsync` see that it's unchanged). It
+// reports whether the file was written.
+func writeFile(
+	output billy.Filesystem,
+	path string,
+	data []byte,
+) (written bool, err error) {
+	recordOutput(output, path)
+	if unchanged(output, path, data) {
+		return false, nil
+	}
+	f, err := createFile(output, path)
+	if err != nil {
+		return false, err
+	}
+	if _, err := f.Write(data); err != nil {
+		return false, errors.Join(err, f.Close())
+	}
+	return true, f.Close()
+}
+
+// createFile creates the file at `path`, replacing any existing file rather
+// than truncating it. A staged build's existing files are hard links to the
+// output directory's (see `RunStaged`), which mustn't be written through.
+func createFile(output billy.Filesystem, path string) (billy.File, error) {
+	if err := output.Remove(path); err != nil &&
+		!errors.Is(err, fs.ErrNotExist) {
+		return nil, err
+	}
+	return output.Create(path)
+}
+
+func unchanged(output billy.Filesystem, path string, data []byte) bool {
+	info, err := output.Stat(path)
+	if err != nil || info.Size() != int64(len(data)) {
+		return false
+	}
+	existing, err := util.ReadFile(output, path)
+	return err == nil && bytes.Equal(existing, data)
+}
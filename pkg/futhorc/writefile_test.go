@@ -0,0 +1,80 @@
+package futhorc
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"testing"
+)
+
+// TestNestedOutputDirectories builds a project whose pages and assets are
+// nested several directories deep (where no other page has created their
+// parents) and checks that every one of them was written.
+func TestNestedOutputDirectories(t *testing.T) {
+	files := map[string]string{
+		"posts/2024/06/trip/index.md": "---\nTitle: Trip\n" +
+			"Date: 2024-06-01\nTags: [travel]\n---\n",
+		"posts/2024/06/trip/photos/day-1/photo.png": "photo",
+	}
+	for i := range 11 {
+		files[fmt.Sprintf("posts/notes/a/b/c/post-%02d.md", i)] = fmt.Sprintf(
+			"---\nTitle: Post %d\nDate: 2024-01-%02d\nTags: [rust]\n---\n",
+			i,
+			i+1,
+		)
+	}
+
+	for _, tc := range []struct {
+		name   string
+		pretty bool
+		wanted []string
+	}{
+		{
+			name: "plain",
+			wanted: []string{
+				"index.html",
+				"page-001.html",
+				"posts/2024/06/trip/index.html",
+				"posts/2024/06/trip/photos/day-1/photo.png",
+				"posts/notes/a/b/c/post-00.html",
+				"posts/notes/a/b/c/post-10.html",
+				"rust/index.html",
+				"rust/page-001.html",
+				"travel/index.html",
+				"assets/theme/style.css",
+			},
+		},
+		{
+			name:   "pretty",
+			pretty: true,
+			wanted: []string{
+				"index.html",
+				"page-001/index.html",
+				"posts/2024/06/trip/index.html",
+				"posts/2024/06/trip/photos/day-1/photo.png",
+				"posts/notes/a/b/c/post-00/index.html",
+				"posts/notes/a/b/c/post-10/index.html",
+				"rust/index.html",
+				"rust/page-001/index.html",
+				"travel/index.html",
+				"assets/theme/style.css",
+			},
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			files["futhorc.jsonc"] = fmt.Sprintf(
+				`{"prettyURLs": %t}`,
+				tc.pretty,
+			)
+			output := buildOnDisk(t, writeProject(t, testTheme, files))
+			for _, p := range tc.wanted {
+				info, err := os.Stat(filepath.Join(output, p))
+				if err != nil {
+					t.Errorf("wanted output file `%s`: %v", p, err)
+				} else if !info.Mode().IsRegular() {
+					t.Errorf("output `%s` isn't a regular file", p)
+				}
+			}
+		})
+	}
+}
@@ -2,64 +2,326 @@ package markdown
 
 import (
 	"bytes"
+	"errors"
 	"fmt"
 	"html/template"
 	"io"
 	"log/slog"
 	"net/url"
+	"slices"
+	"strconv"
 	"strings"
+	"unicode"
+	"unicode/utf8"
 	"unsafe"
 
+	"futhorc/pkg/slug"
+
 	"github.com/gomarkdown/markdown"
 	"github.com/gomarkdown/markdown/ast"
 	"github.com/gomarkdown/markdown/html"
 	"github.com/gomarkdown/markdown/parser"
 )
 
+// Document is the result of converting a markdown document.
+type Document struct {
+	// HTML is the rendered document.
+	HTML template.HTML
+
+	// Words is the number of words in the document's prose, including inline
+	// code but excluding code blocks.
+	Words int
+
+	// Characters is the number of characters in the same prose as `Words`.
+	Characters int
+
+	// CodeLines is the number of lines in the document's code blocks.
+	CodeLines int
+
+	// Headings lists the document's headings in order. Each heading is
+	// rendered with its `ID` so it can be linked to.
+	Headings []Heading
+
+	// IDs lists every `id` attribute in the document (the footnotes' and
+	// the headings'), each of which is unique, for validating links to
+	// fragments of the document.
+	IDs []string
+
+	// Links lists the document's links and images which point at the site
+	// (i.e., below `Config.BaseURL`), for checking that their targets exist.
+	Links []Link
+
+	// Snippet is the start of the document which summarizes it: everything
+	// before the first more marker (an HTML comment like `<!-- more -->`; see
+	// `Config.MoreMarker`) or, failing that, the first paragraph, cut after
+	// `MaxSnippet` bytes. Every element which is open where it's cut (e.g.,
+	// the list around a marker) is closed, so the snippet is balanced HTML.
+	// The markers themselves aren't rendered, and markers inside of code are
+	// text rather than HTML, so they never cut the snippet.
+	Snippet template.HTML
+}
+
+// Link is a link (or an image) to a page or file on the site.
+type Link struct {
+	// Href is the link's destination as it's rendered.
+	Href string
+
+	// URL is the absolute URL of the link's target, without its query or
+	// fragment.
+	URL string
+}
+
 // Convert converts a document from markdown to HTML. `url` should be the
-// absolute path for the output document; it's used to convert source urls to
-// target urls.
-func Convert(c *Config, url *url.URL, doc template.HTML) template.HTML {
+// absolute path for the output document (or, if the output document lives in
+// a different directory than its source, for the document's source); it's used
+// to convert source urls to target urls.
+func Convert(c *Config, url *url.URL, doc template.HTML) (Document, error) {
 	data := *(*[]byte)(unsafe.Pointer(&doc))
 	parser := parser.NewWithExtensions(c.ParserExtensions | parser.Footnotes)
 	node := parser.Parse(data)
-	ast.Walk(node, &visitor{Config: c, url: url})
+	if c.MaxNesting > 0 && exceedsNesting(node, 0, c.MaxNesting) {
+		return Document{}, &NestingError{Limit: c.MaxNesting}
+	}
+	v := visitor{Config: c, url: url}
+	v.ids.reserveFootnotes(node)
+	ast.Walk(node, &v)
+	var snippet, paragraph []byte
+	var links []Link
+	var linkErr error
+	var marked bool
 	renderer := html.NewRenderer(html.RendererOptions{
 		RenderNodeHook: func(
 			w io.Writer,
 			node ast.Node,
 			entering bool,
 		) (ast.WalkStatus, bool) {
-			// Make footnote links absolute so footnotes contained in snippets
-			// still l to the correct page.
+			// `markdown.Render` renders into a buffer, so it holds everything
+			// before the marker.
+			if entering && c.isMoreMarker(node) {
+				if buf, ok := w.(*bytes.Buffer); ok && !marked {
+					marked = true
+					snippet = closeElements(buf.Bytes(), node.GetParent())
+				}
+				return ast.GoToNext, true
+			}
+			if entering && paragraph == nil && inParagraph(node) {
+				if buf, ok := w.(*bytes.Buffer); ok {
+					paragraph = capSnippet(buf.Bytes(), node)
+				}
+			}
+			if p, ok := node.(*ast.Paragraph); ok && !entering &&
+				paragraph == nil {
+				if buf, ok := w.(*bytes.Buffer); ok {
+					paragraph = closeElements(buf.Bytes(), p)
+				}
+			}
+
+			// Footnote links are local fragments in the document itself;
+			// see `AbsoluteFootnotes` for copies of the document (or parts of
+			// it) which are rendered on other pages.
 			if l, ok := node.(*ast.Link); ok && entering {
+				if err := c.checkLink(l.Destination); err != nil &&
+					l.NoteID == 0 && linkErr == nil {
+					linkErr = err
+				}
 				if l.NoteID > 0 {
 					// ignoring error because we're writing to memory
 					_, _ = w.Write(fmt.Appendf(
 						nil,
-						`<sup class="footnote-ref" id="fnref:%[2]d">`+
-							`<a href="%[1]s#fn:%[2]d">%[2]d</a>`+
+						`<sup class="footnote-ref" `+footnoteRefID+`%[1]s">`+
+							footnoteHref+`%[1]s">%[1]s</a>`+
 							`</sup>`,
-						url,
-						l.NoteID,
+						escapeAttr(strconv.Itoa(l.NoteID)),
 					))
 					return ast.SkipChildren, true
 
 					// make sure non-footnote links to other markdown pages in
 					// this site are converted into links to the target HTML.
-				} else if isSite(c.BaseURL, l.Destination) && isMD(l.Destination) {
-					l.Destination = append(
-						l.Destination[:len(l.Destination)-len(suffixMarkdown)],
-						[]byte(suffixHTML)...,
+					// The query and fragment are kept, and fragment-only links
+					// are left alone.
+				} else if p, suffix := splitSuffix(l.Destination); len(p) > 0 {
+					if alias, found := c.Aliases[resolve(url, p)]; found {
+						l.Destination = []byte(alias + suffix)
+					} else if isSite(c.BaseURL, p) && isMD(p) {
+						l.Destination = append(c.targetPath(p), suffix...)
+					}
+					l.Destination = encodeURL(l.Destination)
+					links = c.appendLink(links, url, l.Destination)
+				}
+				if c.ExternalLinksNewTab &&
+					isExternal(c.BaseURL, l.Destination) {
+					l.AdditionalAttributes = append(
+						l.AdditionalAttributes,
+						`target="_blank"`,
+						`rel="noopener noreferrer"`,
 					)
 				}
 				return ast.SkipChildren, false
 			}
+			// the renderer writes a code block's language into its `class`
+			// as it's written
+			if block, ok := node.(*ast.CodeBlock); ok && entering {
+				block.Info = []byte(escapeAttr(string(block.Info)))
+			}
+			if img, ok := node.(*ast.Image); ok && entering {
+				if err := c.checkLink(img.Destination); err != nil &&
+					linkErr == nil {
+					linkErr = err
+				}
+				img.Destination = encodeURL(img.Destination)
+				links = c.appendLink(links, url, img.Destination)
+			}
 			return ast.GoToNext, false
 		},
 	})
 	tmp := markdown.Render(node, renderer)
-	return *(*template.HTML)(unsafe.Pointer(&tmp))
+	if linkErr != nil {
+		return Document{}, linkErr
+	}
+	if !marked {
+		snippet = paragraph
+	}
+	return Document{
+		HTML:       *(*template.HTML)(unsafe.Pointer(&tmp)),
+		Words:      v.words,
+		Characters: v.characters,
+		CodeLines:  v.codeLines,
+		Headings:   v.headings,
+		IDs:        v.ids.ids,
+		Links:      links,
+		Snippet:    *(*template.HTML)(unsafe.Pointer(&snippet)),
+	}, nil
+}
+
+// closeElements returns a copy of the HTML rendered so far, `rendered`, with
+// `node` and each of its ancestors closed as they would be by the renderer.
+func closeElements(rendered []byte, node ast.Node) []byte {
+	buf := bytes.NewBuffer(slices.Clone(rendered))
+	closer := html.NewRenderer(html.RendererOptions{})
+	for ; node != nil; node = node.GetParent() {
+		closer.RenderNode(buf, node, false)
+	}
+	return buf.Bytes()
+}
+
+// capSnippet returns the first-paragraph snippet cut at `MaxSnippet` bytes if
+// rendering `node` would take the HTML rendered so far, `rendered`, past it,
+// and nil otherwise. A text node is cut between characters, so the snippet
+// keeps as much of its text as fits.
+func capSnippet(rendered []byte, node ast.Node) []byte {
+	room := MaxSnippet - len(rendered)
+	var text string
+	if leaf, ok := node.(*ast.Text); ok {
+		text = string(leaf.Literal)
+	}
+	if text == "" && room > 0 ||
+		text != "" && len(escapeAttr(text)) <= room {
+		return nil
+	}
+
+	cut := slices.Clone(rendered)
+	for _, r := range text {
+		escaped := escapeAttr(string(r))
+		if room -= len(escaped); room < 0 {
+			break
+		}
+		cut = append(cut, escaped...)
+	}
+	return closeElements(cut, node.GetParent())
+}
+
+// inParagraph reports whether `node` is inside of a paragraph.
+func inParagraph(node ast.Node) bool {
+	for parent := node.GetParent(); parent != nil; parent = parent.GetParent() {
+		if _, ok := parent.(*ast.Paragraph); ok {
+			return true
+		}
+	}
+	return false
+}
+
+// isMoreMarker reports whether `node` is a more marker: an HTML comment, as
+// a block or inline, with the same text as `Config.MoreMarker`. Code blocks
+// and code spans are text rather than HTML, so a marker written inside of
+// one never matches.
+func (c *Config) isMoreMarker(node ast.Node) bool {
+	var literal []byte
+	switch n := node.(type) {
+	case *ast.HTMLBlock:
+		literal = n.Literal
+	case *ast.HTMLSpan:
+		literal = n.Literal
+	default:
+		return false
+	}
+	text, ok := commentText(string(literal))
+	if !ok {
+		return false
+	}
+	marker, _ := commentText(c.moreMarker())
+	return text == marker
+}
+
+func (c *Config) moreMarker() string {
+	if c.MoreMarker == "" {
+		return MoreMarker
+	}
+	return c.MoreMarker
+}
+
+// MoreMarker is the default marker which separates a post's snippet from the
+// rest of its body.
+const MoreMarker = "<!-- more -->"
+
+// MaxSnippet is the most bytes of HTML which a snippet without a more marker
+// may hold (not counting the tags which close it).
+const MaxSnippet = 1024
+
+// commentText returns the text of the HTML comment `comment` without the
+// whitespace around it, so `<!--more-->` and `<!--  more -->` are the same
+// marker as `<!-- more -->` (but `<!-- More -->` isn't). It reports whether
+// `comment` is an HTML comment, but its text is returned either way.
+func commentText(comment string) (string, bool) {
+	comment = strings.TrimSpace(comment)
+	text, opened := strings.CutPrefix(comment, "<!--")
+	text, closed := strings.CutSuffix(text, "-->")
+	return strings.TrimSpace(text), opened && closed
+}
+
+// AbsoluteFootnotes rewrites the footnote links in HTML rendered by `Convert`
+// so they point at the footnotes on the document's page (`url`). This is
+// necessary for snippets and feed content, which are rendered on other pages.
+// The footnote IDs are namespaced by the page's URL, since every document
+// numbers its footnotes from 1 and an index page has several snippets.
+func AbsoluteFootnotes(doc template.HTML, url *url.URL) template.HTML {
+	page := escapeAttr(url.String())
+	namespace := slug.Slugify(url.Path)
+	return template.HTML(strings.NewReplacer(
+		footnoteHref, `<a href="`+page+`#fn:`,
+		footnoteReturnHref, `href="`+page+`#fnref:`,
+		footnoteRefID, `id="`+namespace+`-fnref:`,
+		footnoteID, `id="`+namespace+`-fn:`,
+	).Replace(string(doc)))
+}
+
+const (
+	// footnoteHref is the start of each footnote link written by `Convert`,
+	// and footnoteRefID is the start of the ID of each footnote reference.
+	footnoteHref  = `<a href="#fn:`
+	footnoteRefID = `id="fnref:`
+
+	// footnoteID is the start of the ID of each footnote, and
+	// footnoteReturnHref is the start of each footnote's link back to its
+	// reference. Both are written by the renderer.
+	footnoteID         = `id="fn:`
+	footnoteReturnHref = `href="#fnref:`
+)
+
+// escapeAttr escapes a value for interpolation into a double-quoted HTML
+// attribute. All attribute values written by the render hook must go through
+// it.
+func escapeAttr(value string) string {
+	return template.HTMLEscapeString(value)
 }
 
 func isSite(baseURL *url.URL, target []byte) bool {
@@ -71,37 +333,322 @@ func isSite(baseURL *url.URL, target []byte) bool {
 	return false
 }
 
+// resolve returns the absolute URL of `target` as it's linked from the
+// document at `base`, so relative (`foo.md`) and root-relative
+// (`/posts/foo.md`) links compare equal to absolute ones.
+func resolve(base *url.URL, target []byte) string {
+	t, err := url.Parse(string(target))
+	if err != nil {
+		return ""
+	}
+	return base.ResolveReference(t).String()
+}
+
+// appendLink appends the link to `target` from the document at `doc` to
+// `links` if it points at the site. Fragment-only links are never passed in.
+func (c *Config) appendLink(links []Link, doc *url.URL, target []byte) []Link {
+	t, err := url.Parse(string(target))
+	if err != nil {
+		return links
+	}
+	resolved := doc.ResolveReference(t)
+	resolved.RawQuery, resolved.Fragment, resolved.RawFragment = "", "", ""
+	if u := resolved.String(); strings.HasPrefix(u, c.BaseURL.String()) {
+		links = append(links, Link{Href: string(target), URL: u})
+	}
+	return links
+}
+
+// splitSuffix splits a link's destination into its path and any query and
+// fragment which follow it. The suffix is copied, since the path may be
+// rewritten in place.
+func splitSuffix(target []byte) (path []byte, suffix string) {
+	if i := bytes.IndexAny(target, "?#"); i >= 0 {
+		return target[:i], string(target[i:])
+	}
+	return target, ""
+}
+
+// encodeURL percent-encodes the spaces and non-ASCII characters in a link's
+// destination (e.g., a bundle asset named `screen shot.png` written as
+// `<screen shot.png>`), which browsers tolerate but validators and feed
+// readers don't. Other destinations are returned as they were written.
+func encodeURL(target []byte) []byte {
+	if !bytes.ContainsFunc(target, func(r rune) bool {
+		return r == ' ' || r >= utf8.RuneSelf
+	}) {
+		return target
+	}
+	u, err := url.Parse(string(target))
+	if err != nil {
+		return target
+	}
+	return []byte(u.String())
+}
+
+// isExternal reports whether `target` is a web URL on another host than the
+// site's. It's resolved against the site's URL first, so protocol-relative
+// URLs (`//example.com/`) are compared by their hosts while other relative
+// URLs (including fragments) are never external, and neither are other
+// schemes (e.g., `mailto:`).
+func isExternal(baseURL *url.URL, target []byte) bool {
+	t, err := url.Parse(string(target))
+	if err != nil {
+		return false
+	}
+	if t = baseURL.ResolveReference(t); t.Scheme != "http" &&
+		t.Scheme != "https" {
+		return false
+	}
+	return !strings.EqualFold(t.Hostname(), baseURL.Hostname())
+}
+
 func isMD(target []byte) bool {
 	return bytes.HasSuffix(target, []byte(suffixMarkdown))
 }
 
+// targetPath converts the path of a markdown page into the path of its HTML
+// page: `foo.md` becomes `foo.html` or, with pretty URLs, `foo/` (and
+// `foo/index.md` becomes `foo/`).
+func (c *Config) targetPath(target []byte) []byte {
+	target = target[:len(target)-len(suffixMarkdown)]
+	if !c.PrettyURLs {
+		return append(target, suffixHTML...)
+	}
+	if stem := bytes.TrimSuffix(target, []byte("index")); len(stem) > 0 &&
+		stem[len(stem)-1] == '/' {
+		return stem
+	}
+	return append(target, '/')
+}
+
 type Config struct {
-	BaseURL           *url.URL
-	ParserExtensions  parser.Extensions
+	BaseURL          *url.URL
+	ParserExtensions parser.Extensions
+
+	// DeprecateHeadings is the number of levels by which headings are
+	// demoted. They're never demoted below `<h6>`.
 	DeprecateHeadings uint8
+
+	// PrettyURLs links to markdown pages by their directory (`foo/`) rather
+	// than their HTML file (`foo.html`).
+	PrettyURLs bool
+
+	// MaxNesting, if positive, is the depth of nested elements above which
+	// `Convert` fails with a `NestingError` rather than rendering.
+	MaxNesting int
+
+	// ExternalLinksNewTab opens links to other hosts than `BaseURL`'s in a
+	// new tab (with `target="_blank"` and `rel="noopener noreferrer"`).
+	ExternalLinksNewTab bool
+
+	// MoreMarker is the HTML comment which separates a post's snippet from
+	// the rest of its body (`MoreMarker` if it's empty). Comments match if
+	// their text is the same, so the whitespace around it doesn't matter.
+	MoreMarker string
+
+	// Aliases maps the absolute URLs of markdown pages whose HTML pages
+	// aren't at the usual `targetPath` (e.g., posts with slugs) to the URLs
+	// of their HTML pages. Links are resolved against the document's URL
+	// before they're looked up.
+	Aliases map[string]string
+}
+
+// InvalidLinkError is returned by `Convert` for a link or image to the site
+// whose destination isn't a valid URL (e.g., it has an invalid `%` escape).
+type InvalidLinkError struct {
+	// Href is the link's destination as it's written.
+	Href string
+
+	Err error
+}
+
+func (err *InvalidLinkError) Error() string {
+	return fmt.Sprintf("invalid link `%s`: %v", err.Href, err.Err)
+}
+
+func (err *InvalidLinkError) Unwrap() error { return err.Err }
+
+// checkLink returns an `InvalidLinkError` if `target` isn't a valid URL and
+// points at the site. Invalid links to other sites are only logged, since
+// they're the other sites' business (and browsers usually cope).
+func (c *Config) checkLink(target []byte) error {
+	_, err := url.Parse(string(target))
+	if err == nil {
+		return nil
+	}
+	var urlErr *url.Error
+	if errors.As(err, &urlErr) {
+		err = urlErr.Err
+	}
+	if !onSite(c.BaseURL, target) {
+		slog.Warn("invalid link", "href", string(target), "err", err)
+		return nil
+	}
+	return &InvalidLinkError{Href: string(target), Err: err}
+}
+
+// onSite reports whether `target`, which may not be a valid URL, points at
+// the site: it's either relative (but not protocol-relative) or below the
+// site's URL.
+func onSite(baseURL *url.URL, target []byte) bool {
+	if bytes.HasPrefix(target, []byte(baseURL.String())) {
+		return true
+	}
+	if bytes.HasPrefix(target, []byte("//")) {
+		return false
+	}
+	scheme, _, found := bytes.Cut(target, []byte(":"))
+	return !found || !isScheme(scheme)
+}
+
+// isScheme reports whether `s` is a valid URL scheme (RFC 3986, section 3.1).
+// Text before a colon which isn't a scheme (e.g., in `./a:b`) is part of a
+// relative path.
+func isScheme(s []byte) bool {
+	for i, r := range string(s) {
+		switch {
+		case 'a' <= r && r <= 'z', 'A' <= r && r <= 'Z':
+		case i > 0 && ('0' <= r && r <= '9' || strings.ContainsRune("+-.", r)):
+		default:
+			return false
+		}
+	}
+	return len(s) > 0
+}
+
+// NestingError is returned by `Convert` when a document's elements are
+// nested more deeply than `Config.MaxNesting`.
+type NestingError struct {
+	Limit int
+}
+
+func (err *NestingError) Error() string {
+	return fmt.Sprintf(
+		"markdown is nested more than %d elements deep",
+		err.Limit,
+	)
+}
+
+// exceedsNesting reports whether `node`, at `depth`, has descendants nested
+// more than `limit` elements deep. It stops descending at the limit.
+func exceedsNesting(node ast.Node, depth, limit int) bool {
+	if depth > limit {
+		return true
+	}
+	for _, child := range node.GetChildren() {
+		if exceedsNesting(child, depth+1, limit) {
+			return true
+		}
+	}
+	return false
 }
 
 const CommonExtensions = parser.CommonExtensions &^ parser.MathJax
 
 type visitor struct {
 	*Config
-	url *url.URL
+	url        *url.URL
+	words      int
+	characters int
+	codeLines  int
+	headings   []Heading
+	ids        idRegistry
+}
+
+// Heading describes a heading in a document.
+type Heading struct {
+	// Level is the rendered level (after `DeprecateHeadings`).
+	Level int
+
+	// ID is the heading's fragment identifier: the slugified heading text,
+	// with a numeric suffix if an earlier heading has the same slug.
+	ID string
+
+	// Text is the heading's plain text.
+	Text string
 }
 
 func (visitor *visitor) Visit(node ast.Node, entering bool) ast.WalkStatus {
 	if heading, ok := node.(*ast.Heading); ok && entering {
-		heading.Level += int(visitor.DeprecateHeadings)
-		return ast.SkipChildren
+		heading.Level = min(heading.Level+int(visitor.DeprecateHeadings), 6)
+		// the heading's text is still visited, so it counts as prose (and
+		// its links are patched)
+		visitor.addHeading(heading)
 	} else if link, ok := node.(*ast.Link); ok && entering {
 		link.Destination = visitor.patchURL(link.Destination)
 	} else if img, ok := node.(*ast.Image); ok && entering {
 		img.Destination = visitor.patchURL(img.Destination)
+	} else if text, ok := node.(*ast.Text); ok && entering {
+		visitor.countProse(text.Literal)
+	} else if code, ok := node.(*ast.Code); ok && entering {
+		visitor.countProse(code.Literal)
+	} else if block, ok := node.(*ast.CodeBlock); ok && entering {
+		visitor.codeLines += countLines(block.Literal)
 	}
 	return ast.GoToNext
 }
 
+func (visitor *visitor) countProse(text []byte) {
+	visitor.words += len(bytes.Fields(text))
+	visitor.characters += utf8.RuneCount(text)
+}
+
+// addHeading records a heading, assigning it a unique ID unless it already has
+// an explicit one. The renderer writes IDs into attributes as they are, so
+// explicit IDs which would need escaping are replaced too.
+func (visitor *visitor) addHeading(heading *ast.Heading) {
+	text := plainText(heading)
+	id := slug.Slugify(text)
+	if id == "" {
+		id = "section"
+	}
+	if explicit := heading.HeadingID; escapeAttr(explicit) != explicit ||
+		strings.ContainsFunc(explicit, unicode.IsSpace) {
+		slog.Warn(
+			"replacing invalid heading ID",
+			"id", explicit,
+			"replacement", id,
+		)
+		heading.HeadingID = ""
+	}
+	visitor.ids.claimHeading(heading, id)
+	visitor.headings = append(visitor.headings, Heading{
+		Level: heading.Level,
+		ID:    heading.HeadingID,
+		Text:  text,
+	})
+}
+
+// plainText returns the text of a node's descendants without any markup.
+func plainText(node ast.Node) string {
+	var sb strings.Builder
+	ast.WalkFunc(node, func(n ast.Node, entering bool) ast.WalkStatus {
+		if entering {
+			switch n := n.(type) {
+			case *ast.Text:
+				sb.Write(n.Literal)
+			case *ast.Code:
+				sb.Write(n.Literal)
+			}
+		}
+		return ast.GoToNext
+	})
+	return sb.String()
+}
+
+func countLines(data []byte) int {
+	lines := bytes.Count(data, []byte("\n"))
+	if len(data) > 0 && data[len(data)-1] != '\n' {
+		lines++
+	}
+	return lines
+}
+
+// patchURL resolves a link's destination against the document's URL.
+// Fragment-only links (`#section`) are left alone.
 func (visitor *visitor) patchURL(dst []byte) []byte {
-	if len(dst) > 0 {
+	if len(dst) > 0 && dst[0] != '#' {
 		d, err := url.Parse(*(*string)(unsafe.Pointer(&dst)))
 		if err != nil {
 			slog.Warn(
@@ -117,9 +664,15 @@ func (visitor *visitor) patchURL(dst []byte) []byte {
 	return dst
 }
 
+// patchURL resolves `u` against the URL of the current document, except that
+// root-relative URLs (`/posts/foo.md`) are relative to the site's URL. Their
+// queries and fragments are kept.
 func patchURL(base, current, u *url.URL) *url.URL {
 	if u.Host == "" && u.Scheme == "" && len(u.Path) > 0 && u.Path[0] == '/' {
-		return base.JoinPath(u.Path[1:])
+		joined := base.JoinPath(u.Path[1:])
+		joined.RawQuery = u.RawQuery
+		joined.Fragment, joined.RawFragment = u.Fragment, u.RawFragment
+		return joined
 	}
 	return current.ResolveReference(u)
 }
@@ -0,0 +1,670 @@
+package markdown
+
+import (
+	"encoding/xml"
+	"errors"
+	"fmt"
+	"html/template"
+	"io"
+	"net/url"
+	"regexp"
+	"slices"
+	"strings"
+	"testing"
+)
+
+const (
+	testBaseURL = "https://example.com/"
+	testPageURL = "https://example.com/posts/foo.html"
+)
+
+func mustParseURL(t *testing.T, s string) *url.URL {
+	t.Helper()
+	u, err := url.Parse(s)
+	if err != nil {
+		t.Fatal(err)
+	}
+	return u
+}
+
+// testConfig returns the configuration the tests convert documents with
+// unless they need something else.
+func testConfig(t *testing.T) *Config {
+	t.Helper()
+	return &Config{
+		BaseURL:          mustParseURL(t, testBaseURL),
+		ParserExtensions: CommonExtensions,
+	}
+}
+
+// convert converts `doc` as the page at `testPageURL`.
+func convert(t *testing.T, c *Config, doc string) Document {
+	t.Helper()
+	page := mustParseURL(t, testPageURL)
+	document, err := Convert(c, page, template.HTML(doc))
+	if err != nil {
+		t.Fatalf("converting %q: %v", doc, err)
+	}
+	return document
+}
+
+func TestMoreMarker(t *testing.T) {
+	for _, tc := range []struct {
+		name    string
+		marker  string
+		comment string
+		marked  bool
+	}{
+		{name: "default", comment: "<!-- more -->", marked: true},
+		{name: "no-spaces", comment: "<!--more-->", marked: true},
+		{name: "extra-spaces", comment: "<!--   more  -->", marked: true},
+		{name: "different-text", comment: "<!-- More! -->"},
+		{
+			name:    "custom",
+			marker:  "<!-- ✂ -->",
+			comment: "<!--✂-->",
+			marked:  true,
+		},
+		{name: "custom-only", marker: "<!-- ✂ -->", comment: "<!-- more -->"},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			c := testConfig(t)
+			c.MoreMarker = tc.marker
+			doc := convert(
+				t,
+				c,
+				fmt.Sprintf("intro\n\nmiddle\n\n%s\n\nrest\n", tc.comment),
+			)
+
+			snippet, body := string(doc.Snippet), string(doc.HTML)
+			if !strings.Contains(body, "rest") {
+				t.Errorf("wanted the whole body; found %q", body)
+			}
+			if tc.marked {
+				if !strings.Contains(snippet, "middle") ||
+					strings.Contains(snippet, "rest") {
+					t.Errorf("wanted a cut at the marker; found %q", snippet)
+				}
+				if strings.Contains(body, "<!--") {
+					t.Errorf("wanted the marker removed; found %q", body)
+				}
+				return
+			}
+			// without a marker, the snippet is the first paragraph
+			if strings.Contains(snippet, "middle") {
+				t.Errorf("wanted the first paragraph; found %q", snippet)
+			}
+			if !strings.Contains(body, tc.comment) {
+				t.Errorf("wanted the comment kept; found %q", body)
+			}
+		})
+	}
+}
+
+func TestMoreMarkerInCode(t *testing.T) {
+	// each document has a marker after the one which is in code
+	const (
+		escaped = "&lt;!-- more --&gt;"
+		tail    = "\n\nafter\n\n<!-- more -->\n\nrest\n"
+	)
+	for _, tc := range []struct {
+		name string
+		doc  string
+
+		// snippet lists fragments of the wanted snippet
+		snippet []string
+	}{
+		{
+			name:    "fenced-block",
+			doc:     "```html\n<!-- more -->\n```" + tail,
+			snippet: []string{escaped, "</pre>", "after"},
+		},
+		{
+			name:    "indented-block",
+			doc:     "intro\n\n    <!-- more -->" + tail,
+			snippet: []string{escaped, "</pre>", "after"},
+		},
+		{
+			name:    "inline-code",
+			doc:     "intro `<!-- more -->`" + tail,
+			snippet: []string{"<code>" + escaped + "</code>", "after"},
+		},
+		{
+			name:    "top-level",
+			doc:     "intro\n\n<!-- more -->\n\nrest\n",
+			snippet: []string{"intro"},
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			snippet := string(convert(t, testConfig(t), tc.doc).Snippet)
+			for _, fragment := range tc.snippet {
+				if !strings.Contains(snippet, fragment) {
+					t.Errorf("wanted %q in %q", fragment, snippet)
+				}
+			}
+			if strings.Contains(snippet, "rest") {
+				t.Errorf("wanted a cut at the marker; found %q", snippet)
+			}
+		})
+	}
+}
+
+func TestSnippetBalanced(t *testing.T) {
+	for _, tc := range []struct {
+		name string
+		doc  string
+		want string
+	}{
+		{
+			name: "nested-list",
+			doc:  "- one\n  - two\n\n    <!-- more -->\n\n    rest\n- rest\n",
+			want: "two",
+		},
+		{
+			name: "blockquote",
+			doc:  "> quote\n>\n> <!-- more -->\n>\n> rest\n",
+			want: "quote",
+		},
+		{
+			name: "list-in-blockquote",
+			doc:  "> - one\n>\n>   <!-- more -->\n>\n>   rest\n",
+			want: "one",
+		},
+		{
+			name: "inline",
+			doc:  "*intro <!-- more --> rest*\n",
+			want: "intro",
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			snippet := string(convert(t, testConfig(t), tc.doc).Snippet)
+			if !strings.Contains(snippet, tc.want) ||
+				strings.Contains(snippet, "rest") {
+				t.Errorf("wanted a cut at the marker; found %q", snippet)
+			}
+			if err := checkBalanced(snippet); err != nil {
+				t.Errorf("unbalanced snippet %q: %v", snippet, err)
+			}
+		})
+	}
+}
+
+func TestSnippetCap(t *testing.T) {
+	long := strings.TrimSpace(strings.Repeat("word & ", 300))
+	for _, tc := range []struct {
+		name string
+		doc  string
+	}{
+		{name: "paragraph", doc: long + "\n\nrest\n"},
+		{name: "emphasis", doc: "*" + long + "*\n\nrest\n"},
+		{name: "list", doc: "- " + long + "\n\n  rest\n"},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			// the cut may be followed by the tags which close it
+			const closing = 32
+			snippet := string(convert(t, testConfig(t), tc.doc).Snippet)
+			if len(snippet) > MaxSnippet+closing ||
+				!strings.Contains(snippet, "word &amp; word") ||
+				strings.Contains(snippet, "rest") {
+				t.Errorf(
+					"wanted a cut after %d bytes; found %d bytes: %q",
+					MaxSnippet,
+					len(snippet),
+					snippet,
+				)
+			}
+			if err := checkBalanced(snippet); err != nil {
+				t.Errorf("unbalanced snippet %q: %v", snippet, err)
+			}
+		})
+	}
+
+	// a more marker sets the snippet's length itself
+	doc := convert(t, testConfig(t), long+"\n\n<!-- more -->\n\nrest\n")
+	if len(doc.Snippet) <= MaxSnippet {
+		t.Errorf(
+			"wanted the snippet cut at the marker; found %d bytes",
+			len(doc.Snippet),
+		)
+	}
+}
+
+// checkBalanced parses `fragment` strictly, failing unless every element is
+// closed in order.
+func checkBalanced(fragment string) error {
+	decoder := xml.NewDecoder(strings.NewReader("<div>" + fragment + "</div>"))
+	decoder.Strict = true
+	for {
+		if _, err := decoder.Token(); err != nil {
+			if errors.Is(err, io.EOF) {
+				return nil
+			}
+			return err
+		}
+	}
+}
+
+func TestAbsoluteFootnotes(t *testing.T) {
+	const doc = "Alpha[^1] and beta[^x].\n\n[^1]: First.\n[^x]: Second.\n"
+	seen := map[string]string{}
+	for _, page := range []string{
+		"https://example.com/posts/a.html",
+		"https://example.com/posts/b.html",
+	} {
+		u := mustParseURL(t, page)
+		document, err := Convert(testConfig(t), u, doc)
+		if err != nil {
+			t.Fatal(err)
+		}
+		html := string(AbsoluteFootnotes(document.HTML, u))
+
+		// references are numbered in order, whatever their names
+		for _, ref := range []string{
+			`<a href="` + page + `#fn:1">1</a>`,
+			`<a href="` + page + `#fn:2">2</a>`,
+		} {
+			if !strings.Contains(html, ref) {
+				t.Errorf("wanted %q in %q", ref, html)
+			}
+		}
+		if strings.Contains(html, ">x</a>") {
+			t.Errorf("wanted the footnote names replaced in %q", html)
+		}
+
+		for _, m := range idPattern.FindAllStringSubmatch(html, -1) {
+			if other, found := seen[m[1]]; found {
+				t.Errorf("id `%s` is used by `%s` and `%s`", m[1], other, page)
+			}
+			seen[m[1]] = page
+		}
+	}
+	if len(seen) < 8 {
+		t.Errorf("wanted 4 ids on each page; found %v", seen)
+	}
+}
+
+var idPattern = regexp.MustCompile(`\bid="([^"]*)"`)
+
+func TestDeprecateHeadings(t *testing.T) {
+	const doc = "# a\n\n## b\n\n### c\n\n#### d\n\n##### e\n\n###### f\n"
+	for _, tc := range []struct {
+		shift uint8
+		want  []int
+	}{
+		{0, []int{1, 2, 3, 4, 5, 6}},
+		{2, []int{3, 4, 5, 6, 6, 6}},
+		{5, []int{6, 6, 6, 6, 6, 6}},
+	} {
+		t.Run(fmt.Sprint(tc.shift), func(t *testing.T) {
+			c := testConfig(t)
+			c.DeprecateHeadings = tc.shift
+			document := convert(t, c, doc)
+
+			levels := make([]int, len(document.Headings))
+			for i, heading := range document.Headings {
+				levels[i] = heading.Level
+			}
+			if !slices.Equal(levels, tc.want) {
+				t.Errorf("wanted levels %v; found %v", tc.want, levels)
+			}
+			html := string(document.HTML)
+			for _, level := range tc.want {
+				tag := fmt.Sprintf("<h%d", level)
+				if !strings.Contains(html, tag) {
+					t.Errorf("wanted %q in %q", tag, html)
+				}
+			}
+			if strings.Contains(html, "<h7") {
+				t.Errorf("wanted headings clamped to h6; found %q", html)
+			}
+		})
+	}
+}
+
+func TestHeadingIDs(t *testing.T) {
+	const doc = "## Installation\n\n## Installation\n\n## Installation\n\n" +
+		"### Using `go install`\n\n### See [the docs](https://example.org/)\n"
+	document := convert(t, testConfig(t), doc)
+
+	wanted := []Heading{
+		{Level: 2, ID: "installation", Text: "Installation"},
+		{Level: 2, ID: "installation-1", Text: "Installation"},
+		{Level: 2, ID: "installation-2", Text: "Installation"},
+		{Level: 3, ID: "using-go-install", Text: "Using go install"},
+		{Level: 3, ID: "see-the-docs", Text: "See the docs"},
+	}
+	if !slices.Equal(document.Headings, wanted) {
+		t.Errorf("wanted the headings %v; found %v", wanted, document.Headings)
+	}
+	html := string(document.HTML)
+	for _, heading := range wanted {
+		tag := fmt.Sprintf(`<h%d id="%s">`, heading.Level, heading.ID)
+		if !strings.Contains(html, tag) {
+			t.Errorf("wanted %q in %q", tag, html)
+		}
+	}
+}
+
+func TestIDCollisions(t *testing.T) {
+	// explicit heading IDs collide with the footnote's, with each other, and
+	// with a generated ID
+	const doc = "## Notes {#fn:1}\n\nAlpha[^1].\n\n## Notes {#fn:1}\n\n" +
+		"## Intro {#intro}\n\n## Intro\n\n[^1]: First.\n"
+	document := convert(t, testConfig(t), doc)
+
+	wanted := []Heading{
+		{Level: 2, ID: "fn:1-1", Text: "Notes"},
+		{Level: 2, ID: "fn:1-2", Text: "Notes"},
+		{Level: 2, ID: "intro", Text: "Intro"},
+		{Level: 2, ID: "intro-1", Text: "Intro"},
+	}
+	if !slices.Equal(document.Headings, wanted) {
+		t.Errorf("wanted the headings %v; found %v", wanted, document.Headings)
+	}
+
+	// every `id` attribute is unique and claimed
+	html := string(document.HTML)
+	var ids []string
+	for _, m := range idPattern.FindAllStringSubmatch(html, -1) {
+		if slices.Contains(ids, m[1]) {
+			t.Errorf("id `%s` is used twice in %q", m[1], html)
+		}
+		ids = append(ids, m[1])
+	}
+	for _, id := range append(ids, "fn:1", "fnref:1") {
+		if !slices.Contains(document.IDs, id) {
+			t.Errorf("wanted `%s` in the claimed IDs %v", id, document.IDs)
+		}
+	}
+
+	// the IDs don't depend on anything but the document
+	again := convert(t, testConfig(t), doc)
+	if again.HTML != document.HTML || !slices.Equal(again.IDs, document.IDs) {
+		t.Errorf(
+			"wanted the same IDs from a second conversion; found %v and %v",
+			document.IDs,
+			again.IDs,
+		)
+	}
+}
+
+func TestIsExternal(t *testing.T) {
+	base := mustParseURL(t, testBaseURL)
+	for _, tc := range []struct {
+		target string
+		want   bool
+	}{
+		{"https://example.com/about.html", false},
+		{"HTTPS://EXAMPLE.COM/about.html", false},
+		{"http://example.com/about.html", false},
+		{"about.html", false},
+		{"/about.html", false},
+		{"../about.html", false},
+		{"#top", false},
+		{"mailto:me@example.org", false},
+		{"ftp://example.org/file", false},
+		{"//example.com/about.html", false},
+		{"//example.org/", true},
+		{"https://example.org/", true},
+		{"http://www.example.com/", true},
+	} {
+		if got := isExternal(base, []byte(tc.target)); got != tc.want {
+			t.Errorf("`%s`: wanted %t; found %t", tc.target, tc.want, got)
+		}
+	}
+}
+
+func TestExternalLinksNewTab(t *testing.T) {
+	c := testConfig(t)
+	c.ExternalLinksNewTab = true
+	html := string(convert(
+		t,
+		c,
+		"[a](https://example.org/) [b](about.html) "+
+			"[c](https://example.com/about.html) [d](mailto:me@example.org) "+
+			"[e](#top)\n",
+	).HTML)
+
+	const external = `<a href="https://example.org/" target="_blank" ` +
+		`rel="noopener noreferrer">`
+	if !strings.Contains(html, external) {
+		t.Errorf("wanted %q in %q", external, html)
+	}
+	if strings.Count(html, "target=") != 1 {
+		t.Errorf("wanted only the external link changed; found %q", html)
+	}
+}
+
+func TestMarkdownLinkSuffixes(t *testing.T) {
+	for _, tc := range []struct {
+		name   string
+		target string
+		pretty bool
+		want   string
+	}{
+		{
+			name:   "relative-fragment",
+			target: "install.md#prerequisites",
+			want:   "https://example.com/posts/install.html#prerequisites",
+		},
+		{
+			name:   "relative-query",
+			target: "install.md?v=2#prerequisites",
+			want:   "https://example.com/posts/install.html?v=2#prerequisites",
+		},
+		{
+			name:   "root-relative",
+			target: "/posts/install.md#prerequisites",
+			want:   "https://example.com/posts/install.html#prerequisites",
+		},
+		{
+			name:   "absolute",
+			target: "https://example.com/posts/install.md#prerequisites",
+			want:   "https://example.com/posts/install.html#prerequisites",
+		},
+		{
+			name:   "bundle",
+			target: "trip/index.md#map",
+			want:   "https://example.com/posts/trip/index.html#map",
+		},
+		{
+			name:   "pretty",
+			target: "install.md#prerequisites",
+			pretty: true,
+			want:   "https://example.com/posts/install/#prerequisites",
+		},
+		{
+			name:   "pretty-bundle",
+			target: "trip/index.md#map",
+			pretty: true,
+			want:   "https://example.com/posts/trip/#map",
+		},
+		{
+			name:   "external",
+			target: "https://example.org/install.md#prerequisites",
+			want:   "https://example.org/install.md#prerequisites",
+		},
+		{name: "fragment-only", target: "#section", want: "#section"},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			c := testConfig(t)
+			c.PrettyURLs = tc.pretty
+			html := string(convert(t, c, "[x]("+tc.target+")\n").HTML)
+			if want := `href="` + tc.want + `"`; !strings.Contains(html, want) {
+				t.Errorf("wanted %q in %q", want, html)
+			}
+		})
+	}
+}
+
+func TestEncodeURL(t *testing.T) {
+	for _, tc := range []struct {
+		target string
+		want   string
+	}{
+		{"photo.png", "photo.png"},
+		{"photo(1).png", "photo(1).png"},
+		{"already%20encoded.png", "already%20encoded.png"},
+		{"screen shot 2021.png", "screen%20shot%202021.png"},
+		{"screen shot (1).png", "screen%20shot%20%281%29.png"},
+		{"写真.png", "%E5%86%99%E7%9C%9F.png"},
+		{"写真.png#top", "%E5%86%99%E7%9C%9F.png#top"},
+		{
+			"https://example.com/posts/trip/写真.png",
+			"https://example.com/posts/trip/%E5%86%99%E7%9C%9F.png",
+		},
+	} {
+		if got := string(encodeURL([]byte(tc.target))); got != tc.want {
+			t.Errorf("`%s`: wanted `%s`; found `%s`", tc.target, tc.want, got)
+		}
+	}
+}
+
+func TestEncodeAssetLinks(t *testing.T) {
+	html := string(convert(
+		t,
+		testConfig(t),
+		"![a](<screen shot (1).png>) ![b](写真.png)\n",
+	).HTML)
+	for _, src := range []string{
+		`src="https://example.com/posts/screen%20shot%20%281%29.png"`,
+		`src="https://example.com/posts/%E5%86%99%E7%9C%9F.png"`,
+	} {
+		if !strings.Contains(html, src) {
+			t.Errorf("wanted %q in %q", src, html)
+		}
+	}
+}
+
+func TestInvalidLinks(t *testing.T) {
+	for _, tc := range []struct {
+		name    string
+		doc     string
+		invalid string
+	}{
+		{name: "relative", doc: "[x](100%zz.html)", invalid: "100%zz.html"},
+		{name: "image", doc: "![x](bad%zz.png)", invalid: "bad%zz.png"},
+		{
+			name:    "site",
+			doc:     "[x](https://example.com/100%zz)",
+			invalid: "https://example.com/100%zz",
+		},
+		{name: "other-site", doc: "[x](https://example.org/100%zz)"},
+		{name: "protocol-relative", doc: "[x](//example.org/100%zz)"},
+		{name: "valid", doc: "[x](100%25.html)"},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			page := mustParseURL(t, testPageURL)
+			_, err := Convert(testConfig(t), page, template.HTML(tc.doc))
+			if tc.invalid == "" {
+				if err != nil {
+					t.Fatalf("unexpected error: %v", err)
+				}
+				return
+			}
+			var linkErr *InvalidLinkError
+			if !errors.As(err, &linkErr) {
+				t.Fatalf("wanted an `*InvalidLinkError`; found `%v`", err)
+			}
+			if linkErr.Href != tc.invalid {
+				t.Errorf("wanted `%s`; found `%s`", tc.invalid, linkErr.Href)
+			}
+		})
+	}
+}
+
+func TestOnSite(t *testing.T) {
+	base := mustParseURL(t, testBaseURL)
+	for _, tc := range []struct {
+		target string
+		want   bool
+	}{
+		{"foo%zz.html", true},
+		{"/foo%zz.html", true},
+		{"./a:b%zz", true},
+		{"https://example.com/foo%zz", true},
+		{"https://example.org/foo%zz", false},
+		{"//example.com/foo%zz", false},
+		{"mailto:foo%zz", false},
+	} {
+		if got := onSite(base, []byte(tc.target)); got != tc.want {
+			t.Errorf("`%s`: wanted %t; found %t", tc.target, tc.want, got)
+		}
+	}
+}
+
+func TestHostileAttributes(t *testing.T) {
+	// text which markdown would take for inline HTML (e.g., in the alt
+	// text) is avoided, since it's rendered as it's written
+	for _, tc := range []struct {
+		name string
+		doc  string
+	}{
+		{"image-title", `![x](a.png "t\"><script>&")`},
+		{"image-alt", `![a"< b >&c](a.png)`},
+		{"image-href", `![x](a"b<script>.png)`},
+		{"link-title", `[x](a.html "t\"><script>&")`},
+		{"link-title-newline", "[x](a.html \"line\nbreak\\\"><script>\")"},
+		{"link-href", `[x](a"b<script>.html)`},
+		{"footnote", "x[^a\"< b >]\n\n[^a\"< b >]: note\n"},
+		{"code-fence", "```x\"><script>\ncode\n```\n"},
+		{"heading-id", "## Title {#a\"><script>}\n"},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			html := string(convert(t, testConfig(t), tc.doc).HTML)
+			if strings.Contains(html, "<script") {
+				t.Errorf("wanted the markup escaped; found %q", html)
+			}
+			// an unescaped quote or angle bracket in an attribute ends its
+			// tag early, leaving a malformed tag
+			for _, tag := range tagPattern.FindAllString(html, -1) {
+				if !wellFormedTag.MatchString(tag) {
+					t.Errorf("malformed tag %q in %q", tag, html)
+				}
+			}
+		})
+	}
+}
+
+var (
+	tagPattern    = regexp.MustCompile(`<[^<>]*>`)
+	wellFormedTag = regexp.MustCompile(
+		`^</?[a-zA-Z][a-zA-Z0-9]*(\s+[a-zA-Z-]+(="[^"<>]*")?)*\s*/?>$`,
+	)
+)
+
+func TestMaxNesting(t *testing.T) {
+	// the document holds the blockquotes, the innermost of which holds a
+	// paragraph of text, so `n` blockquotes nest the text `n+2` deep
+	const limit = 10
+	for _, tc := range []struct {
+		name        string
+		blockquotes int
+		exceeds     bool
+	}{
+		{name: "below", blockquotes: limit - 3},
+		{name: "at", blockquotes: limit - 2},
+		{name: "above", blockquotes: limit - 1, exceeds: true},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			c := testConfig(t)
+			c.MaxNesting = limit
+			_, err := Convert(
+				c,
+				mustParseURL(t, testPageURL),
+				template.HTML(strings.Repeat("> ", tc.blockquotes)+"deep\n"),
+			)
+			var nestingErr *NestingError
+			if errors.As(err, &nestingErr) != tc.exceeds {
+				t.Fatalf("exceeds: wanted %t; found `%v`", tc.exceeds, err)
+			}
+			if tc.exceeds && nestingErr.Limit != limit {
+				t.Errorf(
+					"wanted the limit %d in the error; found %d",
+					limit,
+					nestingErr.Limit,
+				)
+			}
+		})
+	}
+}
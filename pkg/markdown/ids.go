@@ -0,0 +1,82 @@
+package markdown
+
+import (
+	"fmt"
+	"log/slog"
+	"strconv"
+
+	"github.com/gomarkdown/markdown/ast"
+)
+
+// idRegistry hands out the `id` attributes of a document so that no two
+// elements share one (e.g., a heading with the explicit ID `fn:1` and the
+// first footnote).
+type idRegistry struct {
+	claimed map[string]struct{}
+
+	// ids lists the claimed IDs in the order they were claimed.
+	ids []string
+}
+
+// reserve claims IDs which the renderer assigns itself and which therefore
+// can't be changed.
+func (r *idRegistry) reserve(id string) {
+	if r.claimed == nil {
+		r.claimed = map[string]struct{}{}
+	}
+	if _, found := r.claimed[id]; !found {
+		r.claimed[id] = struct{}{}
+		r.ids = append(r.ids, id)
+	}
+}
+
+// claim returns `id` if it's available or, failing that, `id` with the first
+// available numeric suffix (`id-1`, `id-2`, ...).
+func (r *idRegistry) claim(id string) string {
+	unique := id
+	for i := 1; ; i++ {
+		if _, found := r.claimed[unique]; !found {
+			break
+		}
+		unique = fmt.Sprintf("%s-%d", id, i)
+	}
+	r.reserve(unique)
+	return unique
+}
+
+// reserveFootnotes reserves the IDs of the document's footnotes and their
+// references before any headings claim theirs. Footnotes are identified by
+// their numbers rather than the names they're given in the source (`[^1]`
+// may be the second footnote), so each footnote's name is replaced with its
+// number, which the renderer uses for its ID and its link back to the
+// reference.
+func (r *idRegistry) reserveFootnotes(node ast.Node) {
+	ast.WalkFunc(node, func(n ast.Node, entering bool) ast.WalkStatus {
+		if l, ok := n.(*ast.Link); ok && entering && l.NoteID > 0 {
+			r.reserve(fmt.Sprintf("fnref:%d", l.NoteID))
+			r.reserve(fmt.Sprintf("fn:%d", l.NoteID))
+			if item, ok := l.Footnote.(*ast.ListItem); ok {
+				item.RefLink = []byte(strconv.Itoa(l.NoteID))
+			}
+		}
+		return ast.GoToNext
+	})
+}
+
+// claimHeading claims the ID of a heading. Explicit IDs are kept unless
+// they collide with another element's, in which case they're deduplicated
+// like generated ones.
+func (r *idRegistry) claimHeading(heading *ast.Heading, generated string) {
+	if heading.HeadingID == "" {
+		heading.HeadingID = r.claim(generated)
+		return
+	}
+	if id := r.claim(heading.HeadingID); id != heading.HeadingID {
+		slog.Warn(
+			"renaming duplicate heading ID",
+			"id", heading.HeadingID,
+			"renamed", id,
+		)
+		heading.HeadingID = id
+	}
+}
@@ -0,0 +1,35 @@
+// Package slug converts titles and headings into strings suitable for file
+// names, URL path segments, and fragment identifiers.
+package slug
+
+import (
+	"strings"
+	"unicode"
+)
+
+// Slugify converts a title into a string suitable for a file name or URL path
+// segment: letters (including non-ASCII letters) are lowercased, digits are
+// kept, and every run of other characters becomes a single hyphen. Leading
+// and trailing hyphens are trimmed, so a title with no letters or digits
+// yields an empty slug.
+func Slugify(s string) string {
+	var b strings.Builder
+	b.Grow(len(s))
+	pendingHyphen := false
+	for _, r := range s {
+		if unicode.IsLetter(r) || unicode.IsDigit(r) {
+			if pendingHyphen && b.Len() > 0 {
+				b.WriteByte('-')
+			}
+			pendingHyphen = false
+			b.WriteRune(unicode.ToLower(r))
+			continue
+		}
+		// apostrophes join the parts of a word (e.g., "don't" becomes
+		// "dont" rather than "don-t")
+		if r != '\'' && r != '\u2019' {
+			pendingHyphen = true
+		}
+	}
+	return b.String()
+}
@@ -0,0 +1,32 @@
+package slug
+
+import "testing"
+
+func TestSlugify(t *testing.T) {
+	for _, tc := range []struct {
+		title string
+		want  string
+	}{
+		{"Hello World", "hello-world"},
+		{"Hello, World!", "hello-world"},
+		{"Wait... What?!", "wait-what"},
+		{"  --Leading & Trailing--  ", "leading-trailing"},
+		{"C++ / Rust", "c-rust"},
+		{"2024: A Year in Review", "2024-a-year-in-review"},
+		{"Don't Panic", "dont-panic"},
+		{"It’s Here", "its-here"},
+		{"'Tis the Season", "tis-the-season"},
+		{"Crème Brûlée", "crème-brûlée"},
+		{"ÜBER Straße", "über-straße"},
+		{"Ελληνικά Νέα", "ελληνικά-νέα"},
+		{"日本語のタイトル", "日本語のタイトル"},
+		{"Привет, мир!", "привет-мир"},
+		{"!!! ???", ""},
+		{"—", ""},
+		{"", ""},
+	} {
+		if got := Slugify(tc.title); got != tc.want {
+			t.Errorf("`%s`: wanted %q; found %q", tc.title, tc.want, got)
+		}
+	}
+}